@@ -10,12 +10,15 @@ use option::OptionTrait;
 use traits::{Into, TryInto};
 use zeroable::NonZeroIntoImpl;
 
+// A prefix of the panic data of a panic with a ByteArray message, followed by the serialized
+// ByteArray. Used to distinguish such panics from panics with short string felt252s.
+const BYTE_ARRAY_MAGIC: felt252 = 0x46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3;
 const BYTES_IN_U128: usize = 16;
 // TODO(yuval): change to `BYTES_IN_BYTES31 - 1` once consteval_int supports non-literals.
 const BYTES_IN_BYTES31_MINUS_ONE: usize = consteval_int!(31 - 1);
 
 // TODO(yuval): don't allow creation of invalid ByteArray?
-#[derive(Drop, Clone, PartialEq)]
+#[derive(Drop, Clone, PartialEq, Serde)]
 struct ByteArray {
     // Full "words" of 31 bytes each. The first byte of each word in the byte array
     // is the most significant byte in the word.
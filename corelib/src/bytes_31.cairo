@@ -189,6 +189,8 @@ fn one_shift_left_bytes_u128(n_bytes: usize) -> u128 {
     }
 }
 
+impl Bytes31Serde = serde::into_felt252_based::SerdeImpl<bytes31>;
+
 impl Bytes31PartialEq of PartialEq<bytes31> {
     fn eq(lhs: @bytes31, rhs: @bytes31) -> bool {
         let lhs_as_felt252: felt252 = (*lhs).into();
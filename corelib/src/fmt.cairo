@@ -0,0 +1,171 @@
+use array::{ArrayTrait, SpanTrait};
+use byte_array::ByteArrayTrait;
+use option::OptionTrait;
+use result::ResultTrait;
+use to_byte_array::AppendFormattedToByteArray;
+use traits::{Into, TryInto};
+use zeroable::NonZero;
+
+/// An error of formatting.
+#[derive(Drop)]
+struct Error {}
+
+/// The state of formatting.
+#[derive(Default, Drop)]
+struct Formatter {
+    /// The result of the formatting so far.
+    buffer: ByteArray,
+}
+
+/// A trait for user-facing formatting of values.
+trait Display<T> {
+    fn fmt(self: @T, ref f: Formatter) -> Result<(), Error>;
+}
+
+/// A trait for programmer-facing formatting of values, for debugging.
+trait Debug<T> {
+    fn fmt(self: @T, ref f: Formatter) -> Result<(), Error>;
+}
+
+// === Display impls ===
+
+impl DisplayInteger<
+    T, +AppendFormattedToByteArray<T>, +Into<u8, T>, +TryInto<T, NonZero<T>>, +Copy<T>, +Drop<T>
+> of Display<T> {
+    fn fmt(self: @T, ref f: Formatter) -> Result<(), Error> {
+        let base: T = 10_u8.into();
+        (*self).append_formatted_to_byte_array(ref f.buffer, base.try_into().unwrap());
+        Result::Ok(())
+    }
+}
+
+impl DisplayBool of Display<bool> {
+    fn fmt(self: @bool, ref f: Formatter) -> Result<(), Error> {
+        if *self {
+            f.buffer.append(@"true");
+        } else {
+            f.buffer.append(@"false");
+        }
+        Result::Ok(())
+    }
+}
+
+impl DisplayByteArray of Display<ByteArray> {
+    fn fmt(self: @ByteArray, ref f: Formatter) -> Result<(), Error> {
+        f.buffer.append(self);
+        Result::Ok(())
+    }
+}
+
+impl DisplaySnapshot<T, +Display<T>> of Display<@T> {
+    fn fmt(self: @@T, ref f: Formatter) -> Result<(), Error> {
+        Display::fmt(*self, ref f)
+    }
+}
+
+// === Debug impls ===
+
+impl DebugInteger<
+    T, +AppendFormattedToByteArray<T>, +Into<u8, T>, +TryInto<T, NonZero<T>>, +Copy<T>, +Drop<T>
+> of Debug<T> {
+    fn fmt(self: @T, ref f: Formatter) -> Result<(), Error> {
+        Display::fmt(self, ref f)
+    }
+}
+
+impl DebugBool of Debug<bool> {
+    fn fmt(self: @bool, ref f: Formatter) -> Result<(), Error> {
+        Display::fmt(self, ref f)
+    }
+}
+
+impl DebugByteArray of Debug<ByteArray> {
+    fn fmt(self: @ByteArray, ref f: Formatter) -> Result<(), Error> {
+        f.buffer.append(@"\"");
+        f.buffer.append(self);
+        f.buffer.append(@"\"");
+        Result::Ok(())
+    }
+}
+
+impl DebugSnapshot<T, +Debug<T>> of Debug<@T> {
+    fn fmt(self: @@T, ref f: Formatter) -> Result<(), Error> {
+        Debug::fmt(*self, ref f)
+    }
+}
+
+impl DebugSpan<T, +Debug<T>> of Debug<Span<T>> {
+    fn fmt(self: @Span<T>, ref f: Formatter) -> Result<(), Error> {
+        let mut span = *self;
+        f.buffer.append(@"[");
+        let mut result = Result::Ok(());
+        loop {
+            match span.pop_front() {
+                Option::Some(value) => {
+                    match Debug::fmt(value, ref f) {
+                        Result::Ok(()) => {},
+                        Result::Err(err) => {
+                            result = Result::Err(err);
+                            break;
+                        },
+                    };
+                    if span.len() != 0 {
+                        f.buffer.append(@", ");
+                    }
+                },
+                Option::None => { break; },
+            };
+        };
+        f.buffer.append(@"]");
+        result
+    }
+}
+
+impl DebugArray<T, +Debug<T>> of Debug<Array<T>> {
+    fn fmt(self: @Array<T>, ref f: Formatter) -> Result<(), Error> {
+        Debug::fmt(@self.span(), ref f)
+    }
+}
+
+impl DebugTuple0 of Debug<()> {
+    fn fmt(self: @(), ref f: Formatter) -> Result<(), Error> {
+        f.buffer.append(@"()");
+        Result::Ok(())
+    }
+}
+
+impl DebugTuple1<E0, +Debug<E0>> of Debug<(E0,)> {
+    fn fmt(self: @(E0,), ref f: Formatter) -> Result<(), Error> {
+        let (e0,) = self;
+        f.buffer.append(@"(");
+        Debug::fmt(e0, ref f)?;
+        f.buffer.append(@",)");
+        Result::Ok(())
+    }
+}
+
+impl DebugTuple2<E0, E1, +Debug<E0>, +Debug<E1>> of Debug<(E0, E1)> {
+    fn fmt(self: @(E0, E1), ref f: Formatter) -> Result<(), Error> {
+        let (e0, e1) = self;
+        f.buffer.append(@"(");
+        Debug::fmt(e0, ref f)?;
+        f.buffer.append(@", ");
+        Debug::fmt(e1, ref f)?;
+        f.buffer.append(@")");
+        Result::Ok(())
+    }
+}
+
+impl DebugTuple3<E0, E1, E2, +Debug<E0>, +Debug<E1>, +Debug<E2>> of Debug<(E0, E1, E2)> {
+    fn fmt(self: @(E0, E1, E2), ref f: Formatter) -> Result<(), Error> {
+        let (e0, e1, e2) = self;
+        f.buffer.append(@"(");
+        Debug::fmt(e0, ref f)?;
+        f.buffer.append(@", ");
+        Debug::fmt(e1, ref f)?;
+        f.buffer.append(@", ");
+        Debug::fmt(e2, ref f)?;
+        f.buffer.append(@")");
+        Result::Ok(())
+    }
+}
@@ -283,6 +283,14 @@ fn panic_with_felt252(err_code: felt252) -> never {
     panic(array![err_code])
 }
 
+/// Panics with the given ByteArray message.
+#[inline(always)]
+fn panic_with_byte_array(err: @ByteArray) -> never {
+    let mut serialized = array![byte_array::BYTE_ARRAY_MAGIC];
+    serde::Serde::serialize(err, ref serialized);
+    panic(serialized)
+}
+
 #[inline(always)]
 fn assert(cond: bool, err_code: felt252) {
     if !cond {
@@ -334,6 +342,9 @@ use byte_array::{ByteArray, ByteArrayIndexView, ByteArrayTrait};
 // to_byte_array.
 mod to_byte_array;
 
+// Formatting.
+mod fmt;
+
 #[cfg(test)]
 mod test;
 
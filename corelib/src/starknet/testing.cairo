@@ -1,4 +1,4 @@
-use starknet::ContractAddress;
+use starknet::{ContractAddress, StorageAddress};
 use array::ArrayTrait;
 use array::SpanTrait;
 use traits::Into;
@@ -30,6 +30,13 @@ fn set_sequencer_address(address: ContractAddress) {
     cheatcode::<'set_sequencer_address'>(array![address.into()].span());
 }
 
+// Set the hash of the block with the given number.
+// Like in the sequencer, `get_block_hash_syscall` only returns it for blocks that are at least 10
+// blocks older than the current block number.
+fn set_block_hash(block_number: u64, value: felt252) {
+    cheatcode::<'set_block_hash'>(array![block_number.into(), value].span());
+}
+
 // Set the block timestamp to the provided value.
 fn set_block_timestamp(block_timestamp: u64) {
     cheatcode::<'set_block_timestamp'>(array![block_timestamp.into()].span());
@@ -60,16 +67,118 @@ fn set_chain_id(chain_id: felt252) {
     cheatcode::<'set_chain_id'>(array![chain_id].span());
 }
 
-// Set the nonce.
+// Set the nonce of the transaction, which is the nonce of the accounts without one set by
+// `set_account_nonce`.
 fn set_nonce(nonce: felt252) {
     cheatcode::<'set_nonce'>(array![nonce].span());
 }
 
+// Set the nonce of the given account contract, overriding the nonce of the transaction.
+fn set_account_nonce(address: ContractAddress, nonce: felt252) {
+    cheatcode::<'set_account_nonce'>(array![address.into(), nonce].span());
+}
+
+// Set the caller address seen by the contract at `target` to the provided value, until stopped.
+fn start_prank(target: ContractAddress, caller_address: ContractAddress) {
+    cheatcode::<'start_prank'>(array![target.into(), caller_address.into()].span());
+}
+
+// Set the caller address seen by the contract at `target` to the provided value, for the next
+// `calls` calls into it.
+fn start_prank_for_calls(target: ContractAddress, caller_address: ContractAddress, calls: u32) {
+    cheatcode::<'start_prank'>(array![target.into(), caller_address.into(), calls.into()].span());
+}
+
+// Stop overriding the caller address seen by the contract at `target`.
+fn stop_prank(target: ContractAddress) {
+    cheatcode::<'stop_prank'>(array![target.into()].span());
+}
+
+// Set the block number seen by the contract at `target` to the provided value, until stopped.
+fn start_roll(target: ContractAddress, block_number: u64) {
+    cheatcode::<'start_roll'>(array![target.into(), block_number.into()].span());
+}
+
+// Set the block number seen by the contract at `target` to the provided value, for the next
+// `calls` calls into it.
+fn start_roll_for_calls(target: ContractAddress, block_number: u64, calls: u32) {
+    cheatcode::<'start_roll'>(array![target.into(), block_number.into(), calls.into()].span());
+}
+
+// Stop overriding the block number seen by the contract at `target`.
+fn stop_roll(target: ContractAddress) {
+    cheatcode::<'stop_roll'>(array![target.into()].span());
+}
+
+// Set the block timestamp seen by the contract at `target` to the provided value, until stopped.
+fn start_warp(target: ContractAddress, block_timestamp: u64) {
+    cheatcode::<'start_warp'>(array![target.into(), block_timestamp.into()].span());
+}
+
+// Set the block timestamp seen by the contract at `target` to the provided value, for the next
+// `calls` calls into it.
+fn start_warp_for_calls(target: ContractAddress, block_timestamp: u64, calls: u32) {
+    cheatcode::<'start_warp'>(array![target.into(), block_timestamp.into(), calls.into()].span());
+}
+
+// Stop overriding the block timestamp seen by the contract at `target`.
+fn stop_warp(target: ContractAddress) {
+    cheatcode::<'stop_warp'>(array![target.into()].span());
+}
+
 // Set the signature.
 fn set_signature(signature: Span<felt252>) {
     cheatcode::<'set_signature'>(signature);
 }
 
+// Read `size` consecutive values from the storage of the contract, starting at `key`.
+fn load(address: ContractAddress, key: StorageAddress, size: u32) -> Span<felt252> {
+    cheatcode::<'load'>(array![address.into(), key.into(), size.into()].span())
+}
+
+// Write the given values to consecutive addresses of the storage of the contract, starting at
+// `key`.
+fn store(address: ContractAddress, key: StorageAddress, values: Span<felt252>) {
+    let mut input = array![address.into(), key.into()];
+    values.serialize(ref input);
+    cheatcode::<'store'>(input.span());
+}
+
+// Mock calls to the entry point with the given selector of the contract at `address`, returning
+// `ret_data` instead of executing it. The contract does not need to be deployed.
+fn mock_call(address: ContractAddress, entry_point_selector: felt252, ret_data: Span<felt252>) {
+    let mut input = array![address.into(), entry_point_selector];
+    ret_data.serialize(ref input);
+    cheatcode::<'mock_call'>(input.span());
+}
+
+// Mock calls to the entry point with the given selector of the contract at `address`, failing
+// with `revert_reason` instead of executing it.
+fn mock_call_revert(
+    address: ContractAddress, entry_point_selector: felt252, revert_reason: Span<felt252>
+) {
+    let mut input = array![address.into(), entry_point_selector];
+    revert_reason.serialize(ref input);
+    cheatcode::<'mock_call_revert'>(input.span());
+}
+
+// Stop mocking calls to the entry point with the given selector of the contract at `address`.
+fn stop_mock_call(address: ContractAddress, entry_point_selector: felt252) {
+    cheatcode::<'stop_mock_call'>(array![address.into(), entry_point_selector].span());
+}
+
+// Save a snapshot of the whole Starknet state, returning its id.
+fn snapshot() -> felt252 {
+    let mut id = cheatcode::<'snapshot'>(array![].span());
+    *id.pop_front().unwrap()
+}
+
+// Revert the whole Starknet state to the snapshot with the given id.
+// The snapshot is kept, so the state may be reverted to it again.
+fn revert_to_snapshot(snapshot_id: felt252) {
+    cheatcode::<'revert_to_snapshot'>(array![snapshot_id].span());
+}
+
 // Pop the earliest unpopped logged event for the contract.
 fn pop_log_raw(address: ContractAddress) -> Option<(Span<felt252>, Span<felt252>)> {
     let mut log = cheatcode::<'pop_log'>(array![address.into()].span());
@@ -82,6 +191,59 @@ fn pop_log<T, +starknet::Event<T>>(address: ContractAddress) -> Option<T> {
     starknet::Event::deserialize(ref keys, ref data)
 }
 
+// A handle to the events collected since its creation by `spy_events`.
+#[derive(Copy, Drop)]
+struct EventSpy {
+    id: felt252,
+}
+
+// Start collecting all the events emitted by any contract, including in nested calls.
+fn spy_events() -> EventSpy {
+    let mut id = cheatcode::<'spy_events'>(array![].span());
+    EventSpy { id: *id.pop_front().unwrap() }
+}
+
+#[generate_trait]
+impl EventSpyImpl of EventSpyTrait {
+    // Pop the earliest unpopped collected event, along with the address of the emitting contract.
+    fn pop_raw(ref self: EventSpy) -> Option<(ContractAddress, Span<felt252>, Span<felt252>)> {
+        let mut log = cheatcode::<'pop_spied_event'>(array![self.id].span());
+        Option::Some(
+            (
+                serde::Serde::deserialize(ref log)?,
+                serde::Serde::deserialize(ref log)?,
+                serde::Serde::deserialize(ref log)?,
+            )
+        )
+    }
+
+    // Pop the earliest unpopped collected event as the requested type, along with the address of
+    // the emitting contract.
+    fn pop<T, +starknet::Event<T>>(ref self: EventSpy) -> Option<(ContractAddress, T)> {
+        let (address, mut keys, mut data) = self.pop_raw()?;
+        Option::Some((address, starknet::Event::deserialize(ref keys, ref data)?))
+    }
+
+    // Assert that exactly the given events were emitted by the given contracts, in this order,
+    // since the earliest unpopped collected event. Pops all the collected events.
+    fn assert_emitted<T, +starknet::Event<T>, +PartialEq<T>, +Drop<T>>(
+        ref self: EventSpy, mut expected: Array<(ContractAddress, T)>
+    ) {
+        loop {
+            match expected.pop_front() {
+                Option::Some(expected) => {
+                    let (expected_address, expected_event) = expected;
+                    let (address, event) = self.pop::<T>().expect('Missing event');
+                    assert(address == expected_address, 'Unexpected event emitter');
+                    assert(event == expected_event, 'Unexpected event');
+                },
+                Option::None => { break (); },
+            };
+        };
+        assert(self.pop_raw().is_none(), 'Unexpected extra event');
+    }
+}
+
 // TODO(Ilya): Decide if we limit the type of `to_address`.
 // Pop the earliest unpopped l2 to l1 message for the contract.
 fn pop_l2_to_l1_message(address: ContractAddress) -> Option<(felt252, Span<felt252>)> {
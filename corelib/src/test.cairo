@@ -1,11 +1,13 @@
 mod array_test;
 mod bool_test;
 mod box_test;
+mod byte_array_test;
 mod bytes31_test;
 mod cmp_test;
 mod dict_test;
 mod ec_test;
 mod felt_test;
+mod fmt_test;
 mod hash_test;
 mod integer_test;
 mod keccak_test;
@@ -468,6 +468,24 @@ fn test_reverse() {
     assert_eq(@palindrome, @palindrome.rev(), 'palindrome is not a palindrome');
 }
 
+#[test]
+#[available_gas(1000000)]
+fn test_serde() {
+    let ba: ByteArray = "This is a ByteArray longer than a single word";
+    let mut serialized = array![];
+    serde::Serde::serialize(@ba, ref serialized);
+    let expected_serialized = array![
+        1,
+        0x54686973206973206120427974654172726179206c6f6e676572207468616e,
+        0x20612073696e676c6520776f7264,
+        14
+    ];
+    assert_eq(@serialized, @expected_serialized, 'wrong serialization');
+    let mut serialized = serialized.span();
+    let deserialized: ByteArray = serde::Serde::deserialize(ref serialized).unwrap();
+    assert_eq(@deserialized, @ba, 'wrong deserialization');
+}
+
 // ========= Test helper functions =========
 
 use debug::PrintTrait;
@@ -0,0 +1,113 @@
+use core::fmt::Formatter;
+
+fn format<T, +core::fmt::Debug<T>>(value: @T) -> ByteArray {
+    let mut f: Formatter = Default::default();
+    core::fmt::Debug::fmt(value, ref f).unwrap();
+    f.buffer
+}
+
+#[test]
+#[available_gas(10000000)]
+fn test_debug() {
+    assert(format(@12_u8) == "12", 'bad u8');
+    assert(format(@0_u32) == "0", 'bad u32');
+    assert(format(@1000000000000_u128) == "1000000000000", 'bad u128');
+    assert(
+        format(
+            @1000000000000000000000000000000000000000_u256
+        ) == "1000000000000000000000000000000000000000",
+        'bad u256'
+    );
+    assert(
+        format(
+            @-1
+        ) == "3618502788666131213697322783095070105623107215331596699973092056135872020480",
+        'bad felt252'
+    );
+    assert(format(@true) == "true", 'bad bool');
+    let byte_array: ByteArray = "abc";
+    assert(format(@byte_array) == "\"abc\"", 'bad ByteArray');
+    assert(format(@@5_u8) == "5", 'bad snapshot');
+    assert(format(@array![1_u8, 2, 3]) == "[1, 2, 3]", 'bad array');
+    assert(format(@array![1_u8].span()) == "[1]", 'bad span');
+    assert(format(@ArrayTrait::<u8>::new()) == "[]", 'bad empty array');
+    assert(format(@()) == "()", 'bad unit');
+    assert(format(@(1_u8,)) == "(1,)", 'bad 1-tuple');
+    assert(
+        format(@(1_u8, byte_array, (true, 2_u16, 3))) == "(1, \"abc\", (true, 2, 3))", 'bad tuple'
+    );
+}
+
+#[derive(Debug, Drop)]
+struct Empty {}
+
+#[derive(Debug, Drop)]
+struct Point {
+    x: u8,
+    y: u8,
+}
+
+#[derive(Debug, Drop)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[derive(Debug, Drop)]
+enum Shape {
+    Dot,
+    Circle: (Point, u8),
+}
+
+#[test]
+#[available_gas(10000000)]
+fn test_derive_debug() {
+    assert(format(@Empty {}) == "Empty", 'bad empty struct');
+    assert(format(@Point { x: 1, y: 2 }) == "Point { x: 1, y: 2 }", 'bad struct');
+    assert(format(@Wrapper { value: true }) == "Wrapper { value: true }", 'bad generic struct');
+    assert(format(@Shape::Dot) == "Shape::Dot", 'bad unit variant');
+    assert(
+        format(
+            @Shape::Circle((Point { x: 1, y: 2 }, 3))
+        ) == "Shape::Circle((Point { x: 1, y: 2 }, 3))",
+        'bad variant'
+    );
+}
+
+#[test]
+#[available_gas(10000000)]
+fn test_assert_macros_success() {
+    let a = array![1_u8, 2];
+    assert_eq!(a, array![1, 2]);
+    assert_eq!(a, array![1, 2], "custom message");
+    assert_ne!(a, array![2, 1]);
+    assert_lt!(1_u8, 2);
+    assert_le!(2_u8, 2);
+    assert_gt!(3_u256, 2);
+    assert_ge!(2_u16, 2);
+    // The compared values are not moved.
+    assert(a.len() == 2, 'a was moved');
+}
+
+#[test]
+#[available_gas(10000000)]
+#[should_panic(expected: "assertion `lhs == rhs` failed.\n  lhs: [1, 2]\n  rhs: [2, 1]")]
+fn test_assert_eq_failure() {
+    assert_eq!(array![1_u8, 2], array![2_u8, 1]);
+}
+
+#[test]
+#[available_gas(10000000)]
+#[should_panic(
+    expected: "assertion `lhs != rhs` failed: values are equal.\n  lhs: \"a\"\n  rhs: \"a\""
+)]
+fn test_assert_ne_failure_with_message() {
+    let a: ByteArray = "a";
+    assert_ne!(a, a.clone(), "values are equal");
+}
+
+#[test]
+#[available_gas(10000000)]
+#[should_panic(expected: "assertion `lhs < rhs` failed: not smaller.\n  lhs: 3\n  rhs: 2")]
+fn test_assert_lt_failure() {
+    assert_lt!(3_u32, 2, "not smaller");
+}
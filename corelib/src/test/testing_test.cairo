@@ -13,6 +13,33 @@ fn test_assert_false() {
     assert(false, 'assert(false)');
 }
 
+#[test]
+#[available_gas(100000)]
+#[should_panic(expected: "panic_with_byte_array() with a message longer than a word")]
+fn test_panic_with_byte_array() {
+    panic_with_byte_array(@"panic_with_byte_array() with a message longer than a word")
+}
+
+#[test]
+#[available_gas(100000)]
+#[should_panic(expected_substring: "with a message")]
+fn test_panic_with_byte_array_substring() {
+    panic_with_byte_array(@"panic_with_byte_array() with a message longer than a word")
+}
+
+#[test]
+#[available_gas(100000)]
+#[should_panic(expected_prefix: "panic_with")]
+fn test_panic_with_byte_array_prefix() {
+    panic_with_byte_array(@"panic_with_byte_array() with a message longer than a word")
+}
+
+#[test]
+#[should_panic(expected_substring: "felt252")]
+fn test_panic_with_felt252_substring() {
+    panic_with_felt252('panic_with_felt252()')
+}
+
 #[test]
 fn test_assert_true() {
     assert(true, 'assert(true)');
@@ -1,18 +1,30 @@
 //! Compiles and runs a Cairo program.
 
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{Context, Ok};
+use cairo_felt::Felt252;
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_compiler::project::{check_compiler_path, setup_project};
 use cairo_lang_diagnostics::ToOption;
-use cairo_lang_runner::short_string::as_cairo_short_string;
-use cairo_lang_runner::{SierraCasmRunner, StarknetState};
+use cairo_lang_filesystem::db::FilesGroupEx;
+use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_filesystem::ids::FlagId;
+use cairo_lang_runner::short_string::{as_cairo_byte_array_panic, as_cairo_short_string};
+use cairo_lang_runner::{
+    collect_enum_variant_names, collect_statement_locations, Arg, RunLimits, SierraCasmRunner,
+    StarknetState,
+};
 use cairo_lang_sierra_generator::db::SierraGenGroup;
+use cairo_lang_sierra_generator::program_generator::SierraProgramWithDebug;
 use cairo_lang_sierra_generator::replace_ids::{DebugReplacer, SierraIdReplacer};
 use cairo_lang_starknet::contract::get_contracts_info;
 use clap::Parser;
+use num_bigint::BigInt;
+use num_traits::Num;
 
 /// Command line args parser.
 /// Exits with 0/1 if the input is formatted correctly/incorrectly.
@@ -27,9 +39,35 @@ struct Args {
     /// In cases where gas is available, the amount of provided gas.
     #[arg(long)]
     available_gas: Option<usize>,
+    /// The function to run, given by its path suffix (e.g. `main` or `my_module::my_function`).
+    #[arg(long, default_value = "main")]
+    function: String,
+    /// The arguments to pass to the function, separated by whitespace or commas. Each argument is
+    /// a felt252 in decimal or `0x`-prefixed hex, or an array or span of such felts wrapped in
+    /// square brackets, e.g. `--args "1 0x2 [3 4 5]"`.
+    #[arg(long, default_value = "")]
+    args: String,
     /// Whether to print the memory.
     #[arg(long, default_value_t = false)]
     print_full_memory: bool,
+    /// A JSON file to write the step-level trace of the run to, mapped back to Sierra statements
+    /// and Cairo functions.
+    #[arg(long)]
+    trace_file: Option<PathBuf>,
+    /// Whether to print the steps, memory holes and builtins used by each Sierra function of the
+    /// run.
+    #[arg(long, default_value_t = false)]
+    profile: bool,
+    /// A file to write the steps used per call stack of the run to, in the collapsed-stack format
+    /// used by flamegraph tools.
+    #[arg(long)]
+    profile_stacks: Option<PathBuf>,
+    /// A JSON file with the Starknet state to start the run from (default: an empty state).
+    #[arg(long)]
+    state_in: Option<PathBuf>,
+    /// A JSON file to write the Starknet state at the end of a successful run to.
+    #[arg(long)]
+    state_out: Option<PathBuf>,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -38,7 +76,17 @@ fn main() -> anyhow::Result<()> {
     // Check if args.path is a file or a directory.
     check_compiler_path(args.single_file, &args.path)?;
 
+    let function_args = parse_function_args(&args.args)?;
+
     let db = &mut RootDatabase::builder().detect_corelib().build()?;
+    if args.trace_file.is_some() {
+        // The trace is mapped back to the Cairo code through the locations of the statements.
+        let add_statement_locations_flag_id = FlagId::new(&*db, "add_statement_locations");
+        db.set_flag(
+            add_statement_locations_flag_id,
+            Some(Arc::new(Flag::AddStatementLocations(true))),
+        );
+    }
 
     let main_crate_ids = setup_project(db, Path::new(&args.path))?;
 
@@ -46,8 +94,8 @@ fn main() -> anyhow::Result<()> {
         anyhow::bail!("failed to compile: {}", args.path.display());
     }
 
-    let sierra_program = db
-        .get_sierra_program(main_crate_ids.clone())
+    let SierraProgramWithDebug { program: sierra_program, debug_info } = &*db
+        .get_sierra_program_with_debug(main_crate_ids.clone())
         .to_option()
         .with_context(|| "Compilation failed without any diagnostics.")?;
     let replacer = DebugReplacer { db };
@@ -55,40 +103,86 @@ fn main() -> anyhow::Result<()> {
         anyhow::bail!("Program requires gas counter, please provide `--available-gas` argument.");
     }
 
+    let enum_variant_names = collect_enum_variant_names(db, &main_crate_ids);
+    let statement_locations = collect_statement_locations(db, &debug_info.statements_locations);
     let contracts_info = get_contracts_info(db, main_crate_ids, &replacer)?;
 
     let runner = SierraCasmRunner::new(
-        replacer.apply(&sierra_program),
+        replacer.apply(sierra_program),
         if args.available_gas.is_some() { Some(Default::default()) } else { None },
         contracts_info,
     )
-    .with_context(|| "Failed setting up runner.")?;
+    .with_context(|| "Failed setting up runner.")?
+    .with_trace_collection(
+        args.trace_file.is_some() || args.profile || args.profile_stacks.is_some(),
+    );
+    let starknet_state = match &args.state_in {
+        Some(path) => serde_json::from_str(
+            &fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}.", path.display()))?,
+        )
+        .with_context(|| "Starknet state deserialization failed.")?,
+        None => StarknetState::default(),
+    };
+    let func = runner.find_function(&format!("::{}", args.function))?;
     let result = runner
         .run_function_with_starknet_context(
-            runner.find_function("::main")?,
-            &[],
+            func,
+            &function_args,
             args.available_gas,
-            StarknetState::default(),
+            starknet_state,
+            RunLimits::default(),
         )
         .with_context(|| "Failed to run the function.")?;
+    if let (Some(path), Some(trace)) = (&args.trace_file, &result.trace) {
+        let file = fs::File::create(path)
+            .with_context(|| format!("Failed to create {}.", path.display()))?;
+        serde_json::to_writer(
+            std::io::BufWriter::new(file),
+            &runner.map_trace(trace, &statement_locations),
+        )
+        .with_context(|| "Failed to write the trace.")?;
+    }
     match result.value {
         cairo_lang_runner::RunResultValue::Success(values) => {
-            println!("Run completed successfully, returning {values:?}")
+            println!(
+                "Run completed successfully, returning {}",
+                runner.decode_return_value(func, &values, &result.memory, &enum_variant_names)
+            );
+            if let Some(path) = &args.state_out {
+                let state = serde_json::to_string_pretty(&result.starknet_state)
+                    .with_context(|| "Starknet state serialization failed.")?;
+                fs::write(path, state).with_context(|| "Failed to write Starknet state.")?;
+            }
         }
         cairo_lang_runner::RunResultValue::Panic(values) => {
-            print!("Run panicked with [");
-            for value in &values {
-                match as_cairo_short_string(value) {
-                    Some(as_string) => print!("{value} ('{as_string}'), "),
-                    None => print!("{value}, "),
+            if let Some(message) = as_cairo_byte_array_panic(&values) {
+                println!("Run panicked with \"{message}\".");
+            } else {
+                print!("Run panicked with [");
+                for value in &values {
+                    match as_cairo_short_string(value) {
+                        Some(as_string) => print!("{value} ('{as_string}'), "),
+                        None => print!("{value}, "),
+                    }
                 }
+                println!("].")
             }
-            println!("].")
         }
     }
     if let Some(gas) = result.gas_counter {
         println!("Remaining gas: {gas}");
     }
+    if let (true, Some(trace)) = (args.profile || args.profile_stacks.is_some(), &result.trace) {
+        let profile = runner.profile(trace, &result.memory);
+        if args.profile {
+            print!("Profile:\n{profile}");
+        }
+        if let Some(path) = &args.profile_stacks {
+            fs::write(path, profile.collapsed_stacks())
+                .with_context(|| format!("Failed to write {}.", path.display()))?;
+        }
+    }
     if args.print_full_memory {
         print!("Full memory: [");
         for cell in &result.memory {
@@ -101,3 +195,44 @@ fn main() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+/// Parses the `--args` value into the arguments of the run function.
+fn parse_function_args(input: &str) -> anyhow::Result<Vec<Arg>> {
+    let spaced = input.replace('[', " [ ").replace(']', " ] ");
+    let mut tokens =
+        spaced.split(|c: char| c.is_whitespace() || c == ',').filter(|t| !t.is_empty());
+    let mut function_args = vec![];
+    while let Some(token) = tokens.next() {
+        match token {
+            "[" => {
+                let mut values = vec![];
+                loop {
+                    match tokens.next() {
+                        Some("]") => break,
+                        Some("[") => anyhow::bail!("Nested arrays are not supported in `--args`."),
+                        Some(token) => values.push(parse_felt252(token)?),
+                        None => anyhow::bail!("Missing `]` in `--args`."),
+                    }
+                }
+                function_args.push(Arg::Array(values));
+            }
+            "]" => anyhow::bail!("Unexpected `]` in `--args`."),
+            _ => function_args.push(Arg::Value(parse_felt252(token)?)),
+        }
+    }
+    Ok(function_args)
+}
+
+/// Parses a felt252 given in decimal or `0x`-prefixed hex, possibly negative.
+fn parse_felt252(token: &str) -> anyhow::Result<Felt252> {
+    let (negative, abs) = match token.strip_prefix('-') {
+        Some(abs) => (true, abs),
+        None => (false, token),
+    };
+    let value = match abs.strip_prefix("0x") {
+        Some(hex) => BigInt::from_str_radix(hex, 16),
+        None => BigInt::from_str_radix(abs, 10),
+    }
+    .with_context(|| format!("Invalid felt252 argument `{token}`."))?;
+    Ok(Felt252::from(if negative { -value } else { value }))
+}
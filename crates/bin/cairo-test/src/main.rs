@@ -1,10 +1,14 @@
 //! Compiles and runs a Cairo program.
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Ok;
 use cairo_lang_compiler::project::check_compiler_path;
-use cairo_lang_test_runner::{TestRunConfig, TestRunner};
+use cairo_lang_runner::RunLimits;
+use cairo_lang_test_runner::{
+    GasSnapshotMode, ReportFormat, TestFilter, TestRunConfig, TestRunner,
+};
 use clap::Parser;
 
 /// Command line args parser.
@@ -17,9 +21,21 @@ struct Args {
     /// Whether path is a single file.
     #[arg(short, long)]
     single_file: bool,
-    /// The filter for the tests, running only tests containing the filter string.
+    /// The filter for the tests, running only tests containing the filter string. Filters
+    /// containing `*` or `?` are glob patterns, matched against the full names of the tests.
     #[arg(short, long, default_value_t = String::default())]
     filter: String,
+    /// Match the filter and the skip patterns against the full names of the tests exactly, rather
+    /// than as substrings.
+    #[arg(long, default_value_t = false)]
+    exact: bool,
+    /// Skip the tests matching the pattern (as the filter does). May be given multiple times.
+    #[arg(long)]
+    skip: Vec<String>,
+    /// List the selected tests and their configuration, in the format given by `--format`, instead
+    /// of running them.
+    #[arg(long, default_value_t = false)]
+    list: bool,
     /// Should we run ignored tests as well.
     #[arg(long, default_value_t = false)]
     include_ignored: bool,
@@ -29,6 +45,46 @@ struct Args {
     /// Should we add the starknet plugin to run the tests.
     #[arg(long, default_value_t = false)]
     starknet: bool,
+    /// Whether to print the steps, memory holes and builtins used by each Sierra function of each
+    /// test.
+    #[arg(long, default_value_t = false)]
+    profile: bool,
+    /// The format of the report printed to the standard output: `pretty` for human readable text,
+    /// or `json` for a JSON object per event of the run.
+    #[arg(long, default_value = "pretty")]
+    format: ReportFormat,
+    /// A file to write a JUnit XML report of the run to.
+    #[arg(long)]
+    junit_file: Option<PathBuf>,
+    /// A file to write the gas usage and the number of steps of each passing test to. The entries
+    /// of tests that were not run are kept.
+    #[arg(long, conflicts_with = "check_gas_snapshot")]
+    gas_snapshot: Option<PathBuf>,
+    /// A gas snapshot file to compare the gas usage of the passing tests with. The run fails if
+    /// the gas usage of a test grew by more than `--gas-tolerance`. Run with `CAIRO_FIX_TESTS=1`
+    /// to update the file instead.
+    #[arg(long)]
+    check_gas_snapshot: Option<PathBuf>,
+    /// The allowed growth of the gas usage of each test when checking a gas snapshot, in percent.
+    #[arg(long, default_value_t = 0.0, requires = "check_gas_snapshot")]
+    gas_tolerance: f64,
+    /// A file to write an LCOV report of the line and branch coverage of the run to.
+    #[arg(long)]
+    coverage_file: Option<PathBuf>,
+    /// The maximal number of steps of each run of a test, unless the test sets its own limit with
+    /// the `#[max_steps(<steps>)]` attribute.
+    #[arg(long)]
+    max_steps: Option<usize>,
+    /// The maximal duration of each run of a test in seconds, unless the test sets its own timeout
+    /// with the `#[timeout(<seconds>)]` attribute.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Keep running, and re-run the tests affected by each change of the tested Cairo files.
+    #[arg(
+        long,
+        conflicts_with_all = ["gas_snapshot", "check_gas_snapshot", "coverage_file", "list"]
+    )]
+    watch: bool,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -38,13 +94,33 @@ fn main() -> anyhow::Result<()> {
     check_compiler_path(args.single_file, &args.path)?;
 
     let config = TestRunConfig {
-        filter: args.filter,
+        filter: TestFilter { pattern: args.filter, exact: args.exact, skip: args.skip },
         ignored: args.ignored,
         include_ignored: args.include_ignored,
+        run_profiler: args.profile,
+        format: args.format,
+        junit_path: args.junit_file,
+        gas_snapshot: match (args.gas_snapshot, args.check_gas_snapshot) {
+            (Some(path), _) => Some(GasSnapshotMode::Write(path)),
+            (None, Some(path)) => {
+                Some(GasSnapshotMode::Check { path, tolerance: args.gas_tolerance })
+            }
+            (None, None) => None,
+        },
+        coverage_path: args.coverage_file,
+        list: args.list,
+        limits: RunLimits {
+            max_steps: args.max_steps,
+            timeout: args.timeout.map(Duration::from_secs),
+        },
     };
 
-    let runner = TestRunner::new(&args.path, args.starknet, config)?;
-    runner.run()?;
+    let mut runner = TestRunner::new(&args.path, args.starknet, config)?;
+    if args.watch {
+        runner.watch()?;
+    } else {
+        runner.run()?;
+    }
 
     Ok(())
 }
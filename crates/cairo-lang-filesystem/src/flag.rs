@@ -4,4 +4,8 @@ pub enum Flag {
     /// Whether automatically add `withdraw_gas` calls in code cycles.
     /// Default is true - automatically add.
     AddWithdrawGas(bool),
+    /// Whether to record the Cairo code locations of the generated Sierra statements, as used for
+    /// coverage reports and traces.
+    /// Default is false - not recorded.
+    AddStatementLocations(bool),
 }
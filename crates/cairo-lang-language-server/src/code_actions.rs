@@ -0,0 +1,439 @@
+//! Quick fixes of diagnostics - code actions editing the code to resolve the diagnostics of a
+//! file.
+
+use std::collections::HashMap;
+use std::iter::successors;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{ModuleId, ModuleItemId, TopLevelLanguageElementId, VarId};
+use cairo_lang_diagnostics::DiagnosticEntry;
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::FileId;
+use cairo_lang_filesystem::span::TextOffset;
+use cairo_lang_lowering::db::LoweringGroup;
+use cairo_lang_lowering::diagnostic::LoweringDiagnosticKind;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::diagnostic::SemanticDiagnosticKind;
+use cairo_lang_semantic::items::function_with_body::SemanticExprLookup;
+use cairo_lang_semantic::items::trt::ConcreteTraitGenericFunctionId;
+use cairo_lang_semantic::types::peel_snapshots;
+use cairo_lang_semantic::{ConcreteTypeId, Expr, TypeLongId};
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, Terminal, TypedSyntaxNode};
+use lsp::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, WorkspaceEdit,
+};
+use smol_str::SmolStr;
+
+use crate::references::function_with_body_id;
+use crate::signature_help::parameter_label;
+use crate::{find_node_module, from_pos, get_diagnostic, get_lookup_items, get_uri};
+
+#[cfg(test)]
+#[path = "code_actions_test.rs"]
+mod test;
+
+/// The body of generated functions and match arms which are yet to be implemented.
+const UNIMPLEMENTED_BODY: &str = "core::panic_with_felt252('Not implemented')";
+
+/// A fix of a diagnostic - edits of the file of the diagnostic, with a title describing them.
+struct Fix {
+    title: String,
+    edits: Vec<TextEdit>,
+}
+
+/// Returns the quick fixes of the diagnostics of a file, which are in the given range.
+pub fn code_actions(db: &RootDatabase, file: FileId, range: Range) -> Vec<CodeActionOrCommand> {
+    let mut actions = vec![];
+    for diagnostic in db.file_semantic_diagnostics(file).unwrap_or_default().get_all() {
+        let node = diagnostic.stable_location.syntax_node(db);
+        let fixes = match &diagnostic.kind {
+            SemanticDiagnosticKind::PathNotFound(_) => import_fixes(db, file, &node),
+            SemanticDiagnosticKind::MissingItemsInImpl { item_names } => {
+                missing_impl_items_fix(db, file, &node, item_names).into_iter().collect()
+            }
+            SemanticDiagnosticKind::AssignmentToImmutableVar
+            | SemanticDiagnosticKind::RefArgNotMutable => {
+                missing_mut_fix(db, file, &node).into_iter().collect()
+            }
+            _ => continue,
+        };
+        add_quick_fixes(db, file, range, &diagnostic, &node, fixes, &mut actions);
+    }
+    for diagnostic in db.file_lowering_diagnostics(file).unwrap_or_default().get_all() {
+        let node = diagnostic.location.stable_location.syntax_node(db);
+        let fixes = match &diagnostic.kind {
+            LoweringDiagnosticKind::UnsupportedMatchArms => {
+                missing_match_arms_fix(db, file, &node).into_iter().collect()
+            }
+            _ => continue,
+        };
+        add_quick_fixes(db, file, range, &diagnostic, &node, fixes, &mut actions);
+    }
+    actions
+}
+
+/// Adds the code actions of the fixes of a diagnostic, if it is in the given file and range.
+fn add_quick_fixes<T: DiagnosticEntry<DbType = dyn SemanticGroup>>(
+    db: &RootDatabase,
+    file: FileId,
+    range: Range,
+    diagnostic: &T,
+    node: &SyntaxNode,
+    fixes: Vec<Fix>,
+    actions: &mut Vec<CodeActionOrCommand>,
+) {
+    // Diagnostics in code generated by plugins cannot be fixed by editing it.
+    if fixes.is_empty() || node.stable_ptr().file_id(db) != file {
+        return;
+    }
+    let semantic_db: &dyn SemanticGroup = db;
+    let lsp_diagnostic = get_diagnostic(semantic_db, diagnostic);
+    if lsp_diagnostic.range.end < range.start || range.end < lsp_diagnostic.range.start {
+        return;
+    }
+    let is_preferred = fixes.len() == 1;
+    for Fix { title, edits } in fixes {
+        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![lsp_diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(get_uri(db, file), edits)])),
+                ..WorkspaceEdit::default()
+            }),
+            is_preferred: Some(is_preferred),
+            ..CodeAction::default()
+        }));
+    }
+}
+
+/// Returns fixes of a path whose first segment is not found, each adding a `use` of an item of
+/// that name from another module.
+fn import_fixes(db: &RootDatabase, file: FileId, node: &SyntaxNode) -> Vec<Fix> {
+    if node.kind(db) != SyntaxKind::TerminalIdentifier {
+        return vec![];
+    }
+    // A `use` only helps if the identifier is the first segment of the path.
+    let Some(path) = node.parent().and_then(|segment| segment.parent()) else { return vec![] };
+    if path.kind(db) != SyntaxKind::ExprPath
+        || path.children(db).next().and_then(|segment| segment.children(db).next()).as_ref()
+            != Some(node)
+    {
+        return vec![];
+    }
+    let name = ast::TerminalIdentifier::from_syntax_node(db, node.clone()).text(db);
+    let Some(module_id) = find_node_module(db, file, node.clone()) else { return vec![] };
+    let Some((position, indent)) = module_items_start(db, file, module_id) else { return vec![] };
+    let mut fixes = vec![];
+    for crate_id in db.crates() {
+        for other_module_id in db.crate_modules(crate_id).iter().copied() {
+            if other_module_id == module_id {
+                continue;
+            }
+            let Ok(Some(item)) = db.module_item_by_name(other_module_id, name.clone()) else {
+                continue;
+            };
+            if matches!(item, ModuleItemId::Use(_)) {
+                continue;
+            }
+            let item_path = format!("{}::{name}", other_module_id.full_path(db));
+            fixes.push(Fix {
+                title: format!("Import `{item_path}`"),
+                edits: vec![TextEdit {
+                    range: Range::new(position, position),
+                    new_text: format!("use {item_path};\n{indent}"),
+                }],
+            });
+        }
+    }
+    fixes
+}
+
+/// Returns the position of the start of the items of a module in the given file, where uses are
+/// added, and the indentation of the items.
+fn module_items_start(
+    db: &RootDatabase,
+    file: FileId,
+    module_id: ModuleId,
+) -> Option<(Position, String)> {
+    let offset = match module_id {
+        ModuleId::Submodule(submodule_id) => {
+            match submodule_id.stable_ptr(db).lookup(db).body(db) {
+                ast::MaybeModuleBody::Some(body) => {
+                    body.items(db).as_syntax_node().span_start_without_trivia(db)
+                }
+                // A module in its own file.
+                ast::MaybeModuleBody::None(_) => TextOffset::default(),
+            }
+        }
+        ModuleId::CrateRoot(_) => TextOffset::default(),
+    };
+    Some((from_pos(offset.position_in_file(db, file)?), line_indent(db, file, offset)?))
+}
+
+/// Returns a fix of an impl missing functions of its trait, adding unimplemented functions with
+/// their signatures to it.
+fn missing_impl_items_fix(
+    db: &RootDatabase,
+    file: FileId,
+    node: &SyntaxNode,
+    item_names: &[SmolStr],
+) -> Option<Fix> {
+    let impl_node = node.parent().filter(|parent| parent.kind(db) == SyntaxKind::ItemImpl)?;
+    let impl_ast = ast::ItemImpl::from_syntax_node(db, impl_node.clone());
+    let ast::MaybeImplBody::Some(body) = impl_ast.body(db) else { return None };
+    let module_id = find_node_module(db, file, impl_node.clone())?;
+    let ModuleItemId::Impl(impl_def_id) =
+        db.module_item_by_name(module_id, impl_ast.name(db).text(db)).ok()??
+    else {
+        return None;
+    };
+    let concrete_trait_id = db.impl_def_concrete_trait(impl_def_id).ok()?;
+    let trait_id = concrete_trait_id.trait_id(db);
+
+    let indent = line_indent(db, file, impl_node.span_start_without_trivia(db))?;
+    let inner_indent = format!("{indent}    ");
+    let has_items = !body.items(db).elements(db).is_empty();
+    let mut text = String::new();
+    for name in item_names {
+        let trait_function_id = db.trait_function_by_name(trait_id, name.clone()).ok()??;
+        let signature = db
+            .concrete_trait_function_signature(ConcreteTraitGenericFunctionId::new(
+                db,
+                concrete_trait_id,
+                trait_function_id,
+            ))
+            .ok()?;
+        let generic_params = trait_function_id
+            .stable_ptr(db)
+            .lookup(db)
+            .declaration(db)
+            .generic_params(db)
+            .as_syntax_node()
+            .get_text_without_trivia(db);
+        let params = signature
+            .params
+            .iter()
+            .map(|param| parameter_label(db, param))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut declaration = format!("fn {name}{generic_params}({params})");
+        if !signature.return_type.is_unit(db) {
+            declaration.push_str(&format!(" -> {}", signature.return_type.format(db)));
+        }
+        if !signature.panicable {
+            declaration.push_str(" nopanic");
+        }
+        // Functions are separated by empty lines.
+        if has_items || !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!(
+            "\n{inner_indent}{declaration} {{\n{inner_indent}    \
+             {UNIMPLEMENTED_BODY}\n{inner_indent}}}"
+        ));
+    }
+    let anchor = match body.items(db).elements(db).last() {
+        Some(item) => item.as_syntax_node(),
+        None => body.lbrace(db).as_syntax_node(),
+    };
+    let rbrace = body.rbrace(db).as_syntax_node();
+    let range = insertion_range(db, file, &anchor, &rbrace, &indent, &mut text)?;
+    Some(Fix {
+        title: "Implement missing trait functions".into(),
+        edits: vec![TextEdit { range, new_text: text }],
+    })
+}
+
+/// Returns a fix of a change of an immutable variable, by an assignment or as a `ref` argument,
+/// declaring the variable as mutable.
+fn missing_mut_fix(db: &RootDatabase, file: FileId, node: &SyntaxNode) -> Option<Fix> {
+    let function_id = get_lookup_items(db, file, node.clone())?
+        .first()
+        .copied()
+        .and_then(function_with_body_id)?;
+    let expr_ast = ast::Expr::from_syntax_node(db, node.clone());
+    // The diagnostic of an assignment is on the whole assignment, rather than the variable.
+    let changed_expr = match &expr_ast {
+        ast::Expr::Binary(binary) if matches!(binary.op(db), ast::BinaryOperator::Eq(_)) => {
+            binary.lhs(db)
+        }
+        _ => expr_ast,
+    };
+    let expr_id = db.lookup_expr_by_ptr(function_id, changed_expr.stable_ptr()).ok()?;
+    let var_id = match db.expr_semantic(function_id, expr_id) {
+        Expr::Var(expr) => expr.var,
+        Expr::MemberAccess(expr) => expr.member_path?.base_var(),
+        _ => return None,
+    };
+    let identifier = match var_id {
+        VarId::Param(param_id) => param_id.stable_ptr(db).lookup(db).name(db),
+        VarId::Local(local_var_id) => local_var_id.stable_ptr(db).lookup(db),
+    };
+    let identifier = identifier.as_syntax_node();
+    if identifier.stable_ptr().file_id(db) != file {
+        return None;
+    }
+    let position = from_pos(identifier.span_start_without_trivia(db).position_in_file(db, file)?);
+    Some(Fix {
+        title: format!("Make `{}` mutable", identifier.get_text_without_trivia(db)),
+        edits: vec![TextEdit { range: Range::new(position, position), new_text: "mut ".into() }],
+    })
+}
+
+/// Returns a fix of a match on an enum missing arms of some of its variants, adding unimplemented
+/// arms for them in the order of the variants.
+fn missing_match_arms_fix(db: &RootDatabase, file: FileId, node: &SyntaxNode) -> Option<Fix> {
+    let match_node = successors(Some(node.clone()), SyntaxNode::parent)
+        .find(|ancestor| ancestor.kind(db) == SyntaxKind::ExprMatch)?;
+    let match_ast = ast::ExprMatch::from_syntax_node(db, match_node.clone());
+    let function_id = get_lookup_items(db, file, match_node.clone())?
+        .first()
+        .copied()
+        .and_then(function_with_body_id)?;
+    let expr_ptr = ast::Expr::from_syntax_node(db, match_node.clone()).stable_ptr();
+    let expr_id = db.lookup_expr_by_ptr(function_id, expr_ptr).ok()?;
+    let Expr::Match(match_expr) = db.expr_semantic(function_id, expr_id) else { return None };
+    let matched_ty = db.expr_semantic(function_id, match_expr.matched_expr).ty();
+    let (_, TypeLongId::Concrete(ConcreteTypeId::Enum(concrete_enum_id))) =
+        peel_snapshots(db, matched_ty)
+    else {
+        return None;
+    };
+    let enum_id = concrete_enum_id.enum_id(db);
+    let variants = db.enum_variants(enum_id).ok()?;
+
+    // The indices of the variants of the existing arms, which must all be variants in order.
+    let arms = match_ast.arms(db).elements(db);
+    let mut arm_variant_indices = vec![];
+    let mut arm_enum_paths = vec![];
+    for arm in &arms {
+        let path = match arm.pattern(db) {
+            ast::Pattern::Enum(pattern) => pattern.path(db),
+            ast::Pattern::Path(path) => path,
+            _ => return None,
+        };
+        let mut segments: Vec<_> = path
+            .elements(db)
+            .into_iter()
+            .map(|segment| segment.as_syntax_node().get_text_without_trivia(db))
+            .collect();
+        let name = segments.pop()?;
+        let index = variants.keys().position(|variant_name| *variant_name == name)?;
+        if arm_variant_indices.last().is_some_and(|last| *last >= index) {
+            return None;
+        }
+        arm_variant_indices.push(index);
+        arm_enum_paths.push(segments.join("::"));
+    }
+    // The new arms name the variants the way the existing arms do.
+    let enum_path = match arm_enum_paths.into_iter().next() {
+        Some(path) if !path.is_empty() => path,
+        _ => enum_id.full_path(db),
+    };
+    let body = if match_expr.ty.is_unit(db) { "{}" } else { UNIMPLEMENTED_BODY };
+    let match_indent = line_indent(db, file, match_node.span_start_without_trivia(db))?;
+    let lbrace = match_ast.lbrace(db).as_syntax_node();
+    let rbrace = match_ast.rbrace(db).as_syntax_node();
+    let arm_indent = match arms.first() {
+        Some(arm) if !on_same_line(db, file, &lbrace, &arm.as_syntax_node())? => {
+            line_indent(db, file, arm.as_syntax_node().span_start_without_trivia(db))?
+        }
+        _ => format!("{match_indent}    "),
+    };
+
+    // The new arms, by the index of the existing arm they are added before, if any.
+    let mut new_arms: Vec<(Option<usize>, String)> = vec![];
+    for (index, (name, variant_id)) in variants.iter().enumerate() {
+        if arm_variant_indices.contains(&index) {
+            continue;
+        }
+        let variant = db.variant_semantic(enum_id, *variant_id).ok()?;
+        let pattern = if variant.ty.is_unit(db) {
+            format!("{enum_path}::{name}")
+        } else {
+            format!("{enum_path}::{name}(_)")
+        };
+        let next_arm = arm_variant_indices.iter().position(|arm_index| *arm_index > index);
+        new_arms.push((next_arm, format!("{pattern} => {body},")));
+    }
+    if new_arms.is_empty() {
+        return None;
+    }
+    let mut edits = vec![];
+    for (arm_index, arm) in arms.iter().enumerate() {
+        let text: String = new_arms
+            .iter()
+            .filter(|(next_arm, _)| *next_arm == Some(arm_index))
+            .map(|(_, new_arm)| format!("{new_arm}\n{arm_indent}"))
+            .collect();
+        if !text.is_empty() {
+            let position = from_pos(
+                arm.as_syntax_node().span_start_without_trivia(db).position_in_file(db, file)?,
+            );
+            edits.push(TextEdit { range: Range::new(position, position), new_text: text });
+        }
+    }
+    let mut text: String = new_arms
+        .iter()
+        .filter(|(next_arm, _)| next_arm.is_none())
+        .map(|(_, new_arm)| format!("\n{arm_indent}{new_arm}"))
+        .collect();
+    if !text.is_empty() {
+        let arms_list = match_ast.arms(db);
+        let anchor = if arms.is_empty() {
+            lbrace
+        } else {
+            // The last arm is not followed by a comma.
+            if arms_list.has_tail(db) {
+                text.insert(0, ',');
+            }
+            arms_list.as_syntax_node()
+        };
+        let range = insertion_range(db, file, &anchor, &rbrace, &match_indent, &mut text)?;
+        edits.push(TextEdit { range, new_text: text });
+    }
+    Some(Fix { title: "Add missing match arms".into(), edits })
+}
+
+/// Returns the range of an insertion of lines of text after a node, followed by a closing brace. If
+/// the brace is on the same line as the node, it is moved to a new line with the given indentation,
+/// replacing the whitespace before it.
+fn insertion_range(
+    db: &RootDatabase,
+    file: FileId,
+    anchor: &SyntaxNode,
+    rbrace: &SyntaxNode,
+    brace_indent: &str,
+    text: &mut String,
+) -> Option<Range> {
+    let start = from_pos(anchor.span_end_without_trivia(db).position_in_file(db, file)?);
+    if !on_same_line(db, file, anchor, rbrace)? {
+        return Some(Range::new(start, start));
+    }
+    text.push_str(&format!("\n{brace_indent}"));
+    let end = from_pos(rbrace.span_start_without_trivia(db).position_in_file(db, file)?);
+    Some(Range::new(start, end))
+}
+
+/// Returns the indentation of the line of an offset in a file.
+fn line_indent(db: &RootDatabase, file: FileId, offset: TextOffset) -> Option<String> {
+    let line = offset.position_in_file(db, file)?.line;
+    let line_start = *db.file_summary(file)?.line_offsets.get(line)?;
+    let content = db.file_content(file)?;
+    Some(line_start.take_from(&content).chars().take_while(|c| *c == ' ' || *c == '\t').collect())
+}
+
+/// Returns whether the end of a node and the start of a following one are on the same line.
+fn on_same_line(
+    db: &RootDatabase,
+    file: FileId,
+    node: &SyntaxNode,
+    next_node: &SyntaxNode,
+) -> Option<bool> {
+    let end = node.span_end_without_trivia(db).position_in_file(db, file)?;
+    let next_start = next_node.span_start_without_trivia(db).position_in_file(db, file)?;
+    Some(end.line == next_start.line)
+}
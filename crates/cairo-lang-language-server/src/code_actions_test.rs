@@ -0,0 +1,261 @@
+use indoc::indoc;
+use lsp::{CodeActionOrCommand, Position, Range, TextEdit};
+
+use super::code_actions;
+use crate::test_utils::{position_of, setup_test_crate};
+
+/// Returns the titles of the code actions of the only file of a test crate with the given content,
+/// which are in the given range, and the content after applying each of them.
+fn fixes_in_range(content: &str, range: Range) -> Vec<(String, String)> {
+    let (db, files) = setup_test_crate(&[("lib.cairo", content)]);
+    code_actions(&db, files[0], range)
+        .into_iter()
+        .map(|action| {
+            let CodeActionOrCommand::CodeAction(action) = action else {
+                panic!("Expected a code action.")
+            };
+            let mut changes = action.edit.unwrap().changes.unwrap();
+            assert_eq!(changes.len(), 1);
+            let (_, edits) = changes.drain().next().unwrap();
+            (action.title, apply_edits(content, edits))
+        })
+        .collect()
+}
+
+/// Returns the code actions of the whole content, as `fixes_in_range` does.
+fn fixes(content: &str) -> Vec<(String, String)> {
+    let end = Position::new(content.lines().count() as u32, 0);
+    fixes_in_range(content, Range::new(Position::new(0, 0), end))
+}
+
+/// Returns the content after applying edits, which do not overlap, to it.
+fn apply_edits(content: &str, mut edits: Vec<TextEdit>) -> String {
+    let offset = |position: Position| {
+        let line_start: usize =
+            content.split_inclusive('\n').take(position.line as usize).map(str::len).sum();
+        line_start + position.character as usize
+    };
+    edits.sort_by_key(|edit| edit.range.start);
+    let mut result = content.to_string();
+    for edit in edits.into_iter().rev() {
+        result.replace_range(offset(edit.range.start)..offset(edit.range.end), &edit.new_text);
+    }
+    result
+}
+
+#[test]
+fn test_missing_match_arms_fix() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        enum Color {
+            Red,
+            Green: u8,
+            Blue,
+            Alpha: u8,
+        }
+        fn foo(c: Color) -> u8 {
+            match c {
+                Color::Green(x) => x,
+                Color::Blue => 0
+            }
+        }
+    "};
+    // The arms are added in the order of the variants.
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Add missing match arms".into(),
+            indoc! {"
+                #[derive(Drop)]
+                enum Color {
+                    Red,
+                    Green: u8,
+                    Blue,
+                    Alpha: u8,
+                }
+                fn foo(c: Color) -> u8 {
+                    match c {
+                        Color::Red => core::panic_with_felt252('Not implemented'),
+                        Color::Green(x) => x,
+                        Color::Blue => 0,
+                        Color::Alpha(_) => core::panic_with_felt252('Not implemented'),
+                    }
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_missing_match_arms_fix_on_one_line() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        enum Color {
+            Red,
+            Green,
+        }
+        fn foo(c: Color) {
+            match c { Color::Red => {} }
+        }
+    "};
+    // Arms of unit matches do nothing.
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Add missing match arms".into(),
+            indoc! {"
+                #[derive(Drop)]
+                enum Color {
+                    Red,
+                    Green,
+                }
+                fn foo(c: Color) {
+                    match c { Color::Red => {},
+                        Color::Green => {},
+                    }
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_missing_mut_fix() {
+    let content = indoc! {"
+        fn foo() -> felt252 {
+            let x = 1;
+            x = 2;
+            x
+        }
+    "};
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Make `x` mutable".into(),
+            indoc! {"
+                fn foo() -> felt252 {
+                    let mut x = 1;
+                    x = 2;
+                    x
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_import_fix() {
+    let content = indoc! {"
+        mod utils {
+            fn helper() -> felt252 {
+                1
+            }
+        }
+        fn foo() -> felt252 {
+            helper()
+        }
+    "};
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Import `test::utils::helper`".into(),
+            indoc! {"
+                use test::utils::helper;
+                mod utils {
+                    fn helper() -> felt252 {
+                        1
+                    }
+                }
+                fn foo() -> felt252 {
+                    helper()
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_missing_impl_items_fix() {
+    let content = indoc! {"
+        trait Shape<T> {
+            fn area(self: @T) -> u32;
+            fn scale(ref self: T, factor: u32);
+        }
+        impl U32Shape of Shape<u32> {
+            fn area(self: @u32) -> u32 {
+                *self
+            }
+        }
+    "};
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Implement missing trait functions".into(),
+            indoc! {"
+                trait Shape<T> {
+                    fn area(self: @T) -> u32;
+                    fn scale(ref self: T, factor: u32);
+                }
+                impl U32Shape of Shape<u32> {
+                    fn area(self: @u32) -> u32 {
+                        *self
+                    }
+
+                    fn scale(ref self: core::integer::u32, factor: core::integer::u32) {
+                        core::panic_with_felt252('Not implemented')
+                    }
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_missing_impl_items_fix_on_one_line() {
+    let content = indoc! {"
+        trait Shape<T> {
+            fn area(self: @T) -> u32;
+        }
+        impl U32Shape of Shape<u32> { }
+    "};
+    assert_eq!(
+        fixes(content),
+        vec![(
+            "Implement missing trait functions".into(),
+            indoc! {"
+                trait Shape<T> {
+                    fn area(self: @T) -> u32;
+                }
+                impl U32Shape of Shape<u32> {
+                    fn area(self: @core::integer::u32) -> core::integer::u32 {
+                        core::panic_with_felt252('Not implemented')
+                    }
+                }
+            "}
+            .into()
+        )]
+    );
+}
+
+#[test]
+fn test_fixes_in_range() {
+    let content = indoc! {"
+        fn foo() -> felt252 {
+            let x = 1;
+            x = 2;
+            let y = 1;
+            y = 2;
+            x + y
+        }
+    "};
+    let line_of_y = Range::new(position_of(content, "y = 2", 0), position_of(content, "x + y", 0));
+    assert_eq!(
+        fixes_in_range(content, line_of_y).into_iter().map(|(title, _)| title).collect::<Vec<_>>(),
+        vec!["Make `y` mutable".to_string()]
+    );
+}
@@ -0,0 +1,132 @@
+//! Inlay hints - the inferred types of the variables of `let` statements, and the names of the
+//! parameters of the arguments of function calls.
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::ids::FunctionWithBodyId;
+use cairo_lang_filesystem::ids::FileId;
+use cairo_lang_parser::db::ParserGroup;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::items::function_with_body::SemanticExprLookup;
+use cairo_lang_semantic::Pattern;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, TypedSyntaxNode};
+use lsp::{InlayHint, InlayHintKind, InlayHintLabel, Position, Range};
+
+use crate::references::function_with_body_id;
+use crate::signature_help::semantic_call;
+use crate::{from_pos, get_lookup_items};
+
+#[cfg(test)]
+#[path = "inlay_hints_test.rs"]
+mod test;
+
+/// Returns the inlay hints of a file, which are in the given range.
+pub fn inlay_hints(db: &RootDatabase, file: FileId, range: Range) -> Vec<InlayHint> {
+    let Ok(syntax) = db.file_syntax(file) else { return vec![] };
+    let mut hints = vec![];
+    for node in syntax.descendants(db) {
+        let kind = node.kind(db);
+        if kind != SyntaxKind::StatementLet && kind != SyntaxKind::ExprFunctionCall {
+            continue;
+        }
+        let Some(function_id) = get_lookup_items(db, file, node.clone())
+            .and_then(|lookup_items| lookup_items.first().copied())
+            .and_then(function_with_body_id)
+        else {
+            continue;
+        };
+        if kind == SyntaxKind::StatementLet {
+            let statement = ast::StatementLet::from_syntax_node(db, node);
+            // Variables with explicit types need no hints.
+            if matches!(statement.type_clause(db), ast::OptionTypeClause::Empty(_)) {
+                hints.extend(variable_type_hints(db, file, function_id, statement.pattern(db)));
+            }
+        } else {
+            let call = ast::ExprFunctionCall::from_syntax_node(db, node);
+            hints.extend(parameter_name_hints(db, file, function_id, &call));
+        }
+    }
+    hints.retain(|hint| range.start <= hint.position && hint.position <= range.end);
+    hints
+}
+
+/// Returns hints of the types of the variables bound by a pattern, after their names.
+fn variable_type_hints(
+    db: &RootDatabase,
+    file: FileId,
+    function_id: FunctionWithBodyId,
+    pattern: ast::Pattern,
+) -> Vec<InlayHint> {
+    let mut hints = vec![];
+    for node in pattern.as_syntax_node().descendants(db) {
+        if !ast::Pattern::is_variant(node.kind(db)) {
+            continue;
+        }
+        let pattern_ptr = ast::Pattern::from_syntax_node(db, node.clone()).stable_ptr();
+        let Ok(pattern_id) = db.lookup_pattern_by_ptr(function_id, pattern_ptr) else { continue };
+        let Pattern::Variable(variable) = db.pattern_semantic(function_id, pattern_id) else {
+            continue;
+        };
+        let ty = variable.var.ty;
+        // Types which could not be inferred are reported by diagnostics instead.
+        if ty.is_missing(db) {
+            continue;
+        }
+        let Some(position) = node_end(db, file, &node) else { continue };
+        hints.push(hint(position, format!(": {}", ty.format(db)), InlayHintKind::TYPE));
+    }
+    hints
+}
+
+/// Returns hints of the names of the parameters of the arguments of a function call, before the
+/// arguments. Arguments which are named, or are variables of the same names as their parameters,
+/// need no hints.
+fn parameter_name_hints(
+    db: &RootDatabase,
+    file: FileId,
+    function_id: FunctionWithBodyId,
+    call: &ast::ExprFunctionCall,
+) -> Vec<InlayHint> {
+    let Some((called_function, is_method)) = semantic_call(db, function_id, call) else {
+        return vec![];
+    };
+    let Ok(signature) = db.concrete_function_signature(called_function) else { return vec![] };
+    // The receiver of a method is not in the arguments.
+    let params = signature.params.iter().skip(usize::from(is_method));
+    let mut hints = vec![];
+    for (param, arg) in params.zip(call.arguments(db).args(db).elements(db)) {
+        let ast::ArgClause::Unnamed(arg_clause) = arg.arg_clause(db) else { continue };
+        let value = arg_clause.value(db).as_syntax_node();
+        if param.name.starts_with('_') || value.get_text_without_trivia(db) == param.name {
+            continue;
+        }
+        // The hint is before the modifiers of the argument, e.g. `ref`.
+        let Some(position) = node_start(db, file, &arg.as_syntax_node()) else { continue };
+        hints.push(hint(position, format!("{}: ", param.name), InlayHintKind::PARAMETER));
+    }
+    hints
+}
+
+/// Returns an inlay hint with the given label.
+fn hint(position: Position, label: String, kind: InlayHintKind) -> InlayHint {
+    InlayHint {
+        position,
+        label: InlayHintLabel::String(label),
+        kind: Some(kind),
+        text_edits: None,
+        tooltip: None,
+        padding_left: None,
+        padding_right: None,
+        data: None,
+    }
+}
+
+/// Returns the position of the start of a node, without its trivia.
+fn node_start(db: &RootDatabase, file: FileId, node: &SyntaxNode) -> Option<Position> {
+    Some(from_pos(node.span_without_trivia(db).start.position_in_file(db, file)?))
+}
+
+/// Returns the position of the end of a node, without its trivia.
+fn node_end(db: &RootDatabase, file: FileId, node: &SyntaxNode) -> Option<Position> {
+    Some(from_pos(node.span_without_trivia(db).end.position_in_file(db, file)?))
+}
@@ -0,0 +1,59 @@
+use indoc::indoc;
+use lsp::{InlayHintKind, InlayHintLabel, Position, Range};
+
+use super::inlay_hints;
+use crate::test_utils::{position_of, setup_test_crate};
+
+const CONTENT: &str = indoc! {"
+    fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+    fn foo(b: u32) {
+        let x = add(1, b);
+        let (y, z): (u32, u32) = (x, x);
+        let (p, _q) = (y, z == 0);
+        let _named = add(a: p, b: z);
+    }
+"};
+
+/// Returns the positions, labels and kinds of the inlay hints of the test crate in the range.
+fn hints(range: Range) -> Vec<(Position, String, InlayHintKind)> {
+    let (db, files) = setup_test_crate(&[("lib.cairo", CONTENT)]);
+    inlay_hints(&db, files[0], range)
+        .into_iter()
+        .map(|hint| {
+            let InlayHintLabel::String(label) = hint.label else { panic!("Expected a string.") };
+            (hint.position, label, hint.kind.unwrap())
+        })
+        .collect()
+}
+
+/// Returns the position right after the `nth` occurrence of `pattern` in the content.
+fn position_after(pattern: &str, nth: usize) -> Position {
+    let start = position_of(CONTENT, pattern, nth);
+    Position { character: start.character + pattern.len() as u32, ..start }
+}
+
+#[test]
+fn test_inlay_hints() {
+    let whole_file = Range { start: Position::new(0, 0), end: Position::new(9, 0) };
+    // Variables with explicit types and arguments named as their parameters need no hints.
+    assert_eq!(
+        hints(whole_file),
+        vec![
+            (position_after("let x", 0), ": core::integer::u32".into(), InlayHintKind::TYPE),
+            (position_of(CONTENT, "1", 0), "a: ".into(), InlayHintKind::PARAMETER),
+            (position_after("(p", 0), ": core::integer::u32".into(), InlayHintKind::TYPE),
+            (position_after("_q", 0), ": core::bool".into(), InlayHintKind::TYPE),
+            (position_after("_named", 0), ": core::integer::u32".into(), InlayHintKind::TYPE),
+        ]
+    );
+    let line_of_p = Range { start: Position::new(6, 0), end: Position::new(7, 0) };
+    assert_eq!(
+        hints(line_of_p),
+        vec![
+            (position_after("(p", 0), ": core::integer::u32".into(), InlayHintKind::TYPE),
+            (position_after("_q", 0), ": core::bool".into(), InlayHintKind::TYPE),
+        ]
+    );
+}
@@ -24,7 +24,7 @@ use cairo_lang_filesystem::db::{
 };
 use cairo_lang_filesystem::detect::detect_corelib;
 use cairo_lang_filesystem::ids::{CrateId, CrateLongId, Directory, FileId, FileLongId};
-use cairo_lang_filesystem::span::{FileSummary, TextOffset, TextPosition, TextWidth};
+use cairo_lang_filesystem::span::{FileSummary, TextOffset, TextPosition, TextSpan, TextWidth};
 use cairo_lang_formatter::{get_formatted_file, FormatterConfig};
 use cairo_lang_lowering::db::LoweringGroup;
 use cairo_lang_lowering::diagnostic::LoweringDiagnostic;
@@ -61,11 +61,25 @@ use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 use vfs::{ProvideVirtualFileRequest, ProvideVirtualFileResponse};
 
+use crate::code_actions::code_actions;
 use crate::completions::{colon_colon_completions, dot_completions, generic_completions};
+use crate::inlay_hints::inlay_hints;
+use crate::references::{find_references, symbol_at, workspace_files};
+use crate::rename::{prepare_rename, rename};
 use crate::scarb_service::{is_scarb_manifest_path, ScarbService};
+use crate::signature_help::signature_help;
+use crate::symbols::{document_symbols, workspace_symbols};
 
+mod code_actions;
+mod inlay_hints;
+mod references;
+mod rename;
 mod scarb_service;
 mod semantic_highlighting;
+mod signature_help;
+mod symbols;
+#[cfg(test)]
+mod test_utils;
 
 pub mod completions;
 pub mod vfs;
@@ -467,6 +481,27 @@ impl LanguageServer for Backend {
                 document_formatting_provider: Some(OneOf::Left(true)),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                document_highlight_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        work_done_progress_options: Default::default(),
+                        resolve_provider: None,
+                    },
+                )),
                 ..ServerCapabilities::default()
             },
         })
@@ -782,6 +817,124 @@ impl LanguageServer for Backend {
         })
         .await
     }
+
+    async fn references(&self, params: ReferenceParams) -> LSPResult<Option<Vec<Location>>> {
+        eprintln!("References");
+        self.with_db(|db| {
+            let file_uri = params.text_document_position.text_document.uri;
+            let file = file(db, file_uri);
+            let position = params.text_document_position.position;
+            let symbol = symbol_at(db, file, position)?;
+            let references = find_references(db, &symbol, workspace_files(db))
+                .into_iter()
+                .filter(|reference| params.context.include_declaration || !reference.is_declaration)
+                .map(|reference| Location {
+                    uri: get_uri(db, reference.file),
+                    range: reference.range,
+                })
+                .collect();
+            Some(references)
+        })
+        .await
+    }
+
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> LSPResult<Option<Vec<DocumentHighlight>>> {
+        self.with_db(|db| {
+            let file_uri = params.text_document_position_params.text_document.uri;
+            let file = file(db, file_uri);
+            let position = params.text_document_position_params.position;
+            let symbol = symbol_at(db, file, position)?;
+            // The references in a file generated by a plugin are in the code it was generated
+            // from.
+            let highlights = find_references(db, &symbol, [file])
+                .into_iter()
+                .filter(|reference| reference.file == file)
+                .map(|reference| DocumentHighlight {
+                    range: reference.range,
+                    kind: Some(if reference.is_declaration {
+                        DocumentHighlightKind::WRITE
+                    } else {
+                        DocumentHighlightKind::READ
+                    }),
+                })
+                .collect();
+            Some(highlights)
+        })
+        .await
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> LSPResult<Option<DocumentSymbolResponse>> {
+        self.with_db(|db| {
+            let file = file(db, params.text_document.uri);
+            Some(DocumentSymbolResponse::Nested(document_symbols(db, file)))
+        })
+        .await
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> LSPResult<Option<Vec<SymbolInformation>>> {
+        self.with_db(|db| Some(workspace_symbols(db, &params.query))).await
+    }
+
+    async fn signature_help(
+        &self,
+        params: SignatureHelpParams,
+    ) -> LSPResult<Option<SignatureHelp>> {
+        self.with_db(|db| {
+            let file_uri = params.text_document_position_params.text_document.uri;
+            let file = file(db, file_uri);
+            signature_help(db, file, params.text_document_position_params.position)
+        })
+        .await
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> LSPResult<Option<Vec<InlayHint>>> {
+        self.with_db(|db| {
+            let file = file(db, params.text_document.uri);
+            Some(inlay_hints(db, file, params.range))
+        })
+        .await
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> LSPResult<Option<CodeActionResponse>> {
+        eprintln!("Code action");
+        self.with_db(|db| {
+            let file = file(db, params.text_document.uri);
+            Some(code_actions(db, file, params.range))
+        })
+        .await
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> LSPResult<Option<PrepareRenameResponse>> {
+        self.with_db(|db| {
+            let file = file(db, params.text_document.uri);
+            let range = prepare_rename(db, file, params.position)?;
+            Ok(range.map(PrepareRenameResponse::Range))
+        })
+        .await?
+    }
+
+    async fn rename(&self, params: RenameParams) -> LSPResult<Option<WorkspaceEdit>> {
+        eprintln!("Rename");
+        self.with_db(|db| {
+            let file_uri = params.text_document_position.text_document.uri;
+            let file = file(db, file_uri);
+            let position = params.text_document_position.position;
+            rename(db, file, position, &params.new_name)
+        })
+        .await?
+    }
 }
 
 fn find_definition(
@@ -1095,7 +1248,6 @@ fn get_node_and_lookup_items(
     file: FileId,
     position: Position,
 ) -> Option<(SyntaxNode, Vec<LookupItemId>)> {
-    let mut res = Vec::new();
     let syntax_db = db.upcast();
     let filename = file.file_name(db.upcast());
 
@@ -1115,6 +1267,17 @@ fn get_node_and_lookup_items(
     // Find offset for position.
     let offset = position_to_offset(file_summary, position, &content)?;
     let node = syntax.lookup_offset(syntax_db, offset);
+    let lookup_items = get_lookup_items(db, file, node.clone())?;
+    Some((node, lookup_items))
+}
+
+/// Returns all the lookup items above the given syntax node, innermost first.
+fn get_lookup_items(
+    db: &(dyn SemanticGroup + 'static),
+    file: FileId,
+    node: SyntaxNode,
+) -> Option<Vec<LookupItemId>> {
+    let mut res = Vec::new();
 
     // Find module.
     let module_id = find_node_module(db, file, node.clone()).on_none(|| {
@@ -1124,7 +1287,7 @@ fn get_node_and_lookup_items(
     let module_file_id = ModuleFileId(module_id, file_index);
 
     // Find containing function.
-    let mut item_node = node.clone();
+    let mut item_node = node;
     loop {
         for item in lookup_item_from_ast(db, module_file_id, item_node.clone()) {
             res.push(item);
@@ -1133,7 +1296,7 @@ fn get_node_and_lookup_items(
             Some(next_node) => {
                 item_node = next_node;
             }
-            None => return Some((node, res)),
+            None => return Some(res),
         }
     }
 }
@@ -1359,6 +1522,53 @@ fn get_range(db: &dyn FilesGroup, location: &DiagnosticLocation) -> Range {
     Range { start, end }
 }
 
+/// Returns the span of the name in a definition, or of the whole definition if its name is not
+/// found.
+fn name_span(db: &RootDatabase, ptr: SyntaxStablePtrId) -> TextSpan {
+    let node = ptr.lookup(db);
+    let name = find_name(db, &node).unwrap_or(node);
+    name.span_without_trivia(db)
+}
+
+/// Returns the name identifier of a definition - its first identifier child, or the name in its
+/// function declaration.
+fn find_name(db: &RootDatabase, node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.children(db).find_map(|child| match child.kind(db) {
+        SyntaxKind::TerminalIdentifier => Some(child),
+        SyntaxKind::FunctionDeclaration => find_name(db, &child),
+        _ => None,
+    })
+}
+
+/// Returns the file and the span a span in the file of the given syntax originates from, following
+/// the code generated by plugins back to the code it was generated from.
+fn origin_span(
+    db: &RootDatabase,
+    ptr: SyntaxStablePtrId,
+    mut span: TextSpan,
+) -> Option<(FileId, TextSpan)> {
+    let mut file = ptr.file_id(db);
+    while let FileLongId::Virtual(virtual_file) = db.lookup_intern_file(file) {
+        span =
+            virtual_file.diagnostics_mappings.iter().find_map(|mapping| mapping.translate(span))?;
+        file = virtual_file.parent?;
+    }
+    Some((file, span))
+}
+
+/// Converts a span in a file to an LSP range.
+fn span_range(db: &RootDatabase, file: FileId, span: TextSpan) -> Option<Range> {
+    let start = from_pos(span.start.position_in_file(db, file)?);
+    let end = from_pos(span.end.position_in_file(db, file)?);
+    Some(Range { start, end })
+}
+
+/// Returns the file and the LSP range a syntax node, without its trivia, originates from.
+fn get_node_origin_range(db: &RootDatabase, node: &SyntaxNode) -> Option<(FileId, Range)> {
+    let (file, span) = origin_span(db, node.stable_ptr(), node.span_without_trivia(db))?;
+    Some((file, span_range(db, file, span)?))
+}
+
 /// Converts internal diagnostics to LSP format.
 fn get_diagnostics<T: DiagnosticEntry>(
     db: &T::DbType,
@@ -1366,31 +1576,36 @@ fn get_diagnostics<T: DiagnosticEntry>(
     diagnostics: &Diagnostics<T>,
 ) {
     for diagnostic in diagnostics.get_all() {
-        let mut message = diagnostic.format(db);
-        let mut related_information = vec![];
-        for note in diagnostic.notes(db) {
-            if let Some(location) = &note.location {
-                related_information.push(DiagnosticRelatedInformation {
-                    location: Location {
-                        uri: get_uri(db.upcast(), location.file_id),
-                        range: get_range(db.upcast(), location),
-                    },
-                    message: note.text.clone(),
-                });
-            } else {
-                message += &format!("\nnote: {}", note.text);
-            }
+        diags.push(get_diagnostic(db, &diagnostic));
+    }
+}
+
+/// Converts a compiler diagnostic to an LSP diagnostic.
+fn get_diagnostic<T: DiagnosticEntry>(db: &T::DbType, diagnostic: &T) -> Diagnostic {
+    let mut message = diagnostic.format(db);
+    let mut related_information = vec![];
+    for note in diagnostic.notes(db) {
+        if let Some(location) = &note.location {
+            related_information.push(DiagnosticRelatedInformation {
+                location: Location {
+                    uri: get_uri(db.upcast(), location.file_id),
+                    range: get_range(db.upcast(), location),
+                },
+                message: note.text.clone(),
+            });
+        } else {
+            message += &format!("\nnote: {}", note.text);
         }
+    }
 
-        diags.push(Diagnostic {
-            range: get_range(db.upcast(), &diagnostic.location(db)),
-            message,
-            related_information: if related_information.is_empty() {
-                None
-            } else {
-                Some(related_information)
-            },
-            ..Diagnostic::default()
-        });
+    Diagnostic {
+        range: get_range(db.upcast(), &diagnostic.location(db)),
+        message,
+        related_information: if related_information.is_empty() {
+            None
+        } else {
+            Some(related_information)
+        },
+        ..Diagnostic::default()
     }
 }
@@ -0,0 +1,305 @@
+//! Finding the references of symbols - the occurrences of their names which declare them or resolve
+//! to them.
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{FunctionWithBodyId, LookupItemId, ModuleItemId};
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::FileId;
+use cairo_lang_filesystem::span::TextSpan;
+use cairo_lang_parser::db::ParserGroup;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::items::function_with_body::SemanticExprLookup;
+use cairo_lang_semantic::{Expr, Pattern};
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
+use cairo_lang_utils::ordered_hash_set::OrderedHashSet;
+use lsp::{Position, Range};
+use smol_str::SmolStr;
+
+use crate::{
+    find_definition, get_lookup_items, get_node_and_lookup_items, get_node_origin_range, name_span,
+    origin_span,
+};
+
+#[cfg(test)]
+#[path = "references_test.rs"]
+mod test;
+
+/// A symbol, identified by the syntax its references resolve to. The definitions generated by
+/// plugins from a single name (e.g. the member of the storage of a contract and the member of its
+/// state) are references to the same symbol, as renaming the name renames all of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    /// The syntax of the definition of the symbol, as returned for goto-definition.
+    pub definition: SyntaxStablePtrId,
+    /// The name of the symbol.
+    pub name: SmolStr,
+}
+
+/// A reference to a symbol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolReference {
+    /// The file of the reference, or of the code it was generated from if it is in code generated
+    /// by a plugin.
+    pub file: FileId,
+    /// The name of the symbol in the reference, which may be in code generated by a plugin.
+    pub identifier: ast::TerminalIdentifier,
+    /// The range of the name of the symbol.
+    pub range: Range,
+    /// Whether this is the name in the declaration of the symbol, rather than a use of it.
+    pub is_declaration: bool,
+}
+
+/// Returns the symbol named by the identifier at the given position, if any.
+pub fn symbol_at(db: &RootDatabase, file: FileId, position: Position) -> Option<Symbol> {
+    let (node, lookup_items) = get_node_and_lookup_items(db, file, position)?;
+    if node.kind(db) != SyntaxKind::TokenIdentifier {
+        return None;
+    }
+    let identifier = ast::TerminalIdentifier::from_syntax_node(db, node.parent()?);
+    let (definition, _) =
+        identifier_definitions(db, file, &identifier, &lookup_items).into_iter().next()?;
+    Some(Symbol { definition, name: identifier.text(db) })
+}
+
+/// Returns the references to the symbol in the given files. The references in code generated by
+/// plugins are mapped to the code they were generated from, and dropped if there is none.
+pub fn find_references(
+    db: &RootDatabase,
+    symbol: &Symbol,
+    files: impl IntoIterator<Item = FileId>,
+) -> Vec<SymbolReference> {
+    let origin = definition_origin(db, symbol.definition);
+    let is_symbol_definition = |definition: SyntaxStablePtrId| {
+        definition == symbol.definition
+            || (origin.is_some() && definition_origin(db, definition) == origin)
+    };
+    let mut references: Vec<SymbolReference> = vec![];
+    for file in files {
+        // Skip the files not mentioning the name of the symbol without parsing them.
+        let Some(content) = db.file_content(file) else { continue };
+        if !content.contains(symbol.name.as_str()) {
+            continue;
+        }
+        let Ok(syntax) = db.file_syntax(file) else { continue };
+        for node in syntax.descendants(db) {
+            if node.kind(db) != SyntaxKind::TerminalIdentifier {
+                continue;
+            }
+            let identifier = ast::TerminalIdentifier::from_syntax_node(db, node.clone());
+            if identifier.text(db) != symbol.name {
+                continue;
+            }
+            let Some((origin_file, range)) = get_node_origin_range(db, &node) else { continue };
+            // The code of the user may be found in a few files, as plugins copy it to the code
+            // they generate.
+            if references
+                .iter()
+                .any(|reference| reference.file == origin_file && reference.range == range)
+            {
+                continue;
+            }
+            let Some(lookup_items) = get_lookup_items(db, file, node.clone()) else { continue };
+            let Some((_, is_declaration)) =
+                identifier_definitions(db, file, &identifier, &lookup_items)
+                    .into_iter()
+                    .find(|(definition, _)| is_symbol_definition(*definition))
+            else {
+                continue;
+            };
+            references.push(SymbolReference {
+                file: origin_file,
+                identifier,
+                range,
+                is_declaration,
+            });
+        }
+    }
+    references
+}
+
+/// Returns the files of the modules of all the crates known to the server, including the files
+/// generated by plugins.
+pub fn workspace_files(db: &RootDatabase) -> OrderedHashSet<FileId> {
+    let mut files = OrderedHashSet::default();
+    for crate_id in db.crates() {
+        for module_id in db.crate_modules(crate_id).iter() {
+            files.extend(db.module_files(*module_id).unwrap_or_default().iter().copied());
+        }
+    }
+    files
+}
+
+/// Returns the file and the span the name in a definition originates from, following the code
+/// generated by plugins back to the code it was generated from.
+pub fn definition_origin(
+    db: &RootDatabase,
+    definition: SyntaxStablePtrId,
+) -> Option<(FileId, TextSpan)> {
+    origin_span(db, definition, name_span(db, definition))
+}
+
+/// Returns the definitions of the symbols the identifier declares or refers to, and whether it
+/// declares each of them. An identifier may name a few symbols - e.g. `x` in `Point { x }` names
+/// both a member and a variable.
+fn identifier_definitions(
+    db: &RootDatabase,
+    file: FileId,
+    identifier: &ast::TerminalIdentifier,
+    lookup_items: &[LookupItemId],
+) -> Vec<(SyntaxStablePtrId, bool)> {
+    let mut definitions = vec![];
+    if let Some(definition) = declared_definition(db, identifier) {
+        definitions.push((definition, true));
+    } else if is_path_pattern(db, identifier, lookup_items) {
+        // Local variables are defined by their names.
+        definitions.push((identifier.stable_ptr().untyped(), true));
+    } else if let Some(definition) = find_definition(db, file, identifier, lookup_items) {
+        // The names of modules are resolved by `find_definition` in their declarations as well.
+        let is_declaration = identifier
+            .as_syntax_node()
+            .parent()
+            .is_some_and(|parent| parent.kind(db) == SyntaxKind::ItemModule);
+        definitions.push((definition, is_declaration));
+    }
+    if let Some(definition) = member_definition(db, identifier, lookup_items) {
+        definitions.push((definition, false));
+    }
+    definitions
+}
+
+/// If the identifier is the name in the declaration of a symbol (other than a module), returns the
+/// definition the references to the symbol resolve to.
+fn declared_definition(
+    db: &RootDatabase,
+    identifier: &ast::TerminalIdentifier,
+) -> Option<SyntaxStablePtrId> {
+    let node = identifier.as_syntax_node();
+    let parent = node.parent()?;
+    match parent.kind(db) {
+        // The declaration of a function is a part of its definition.
+        SyntaxKind::FunctionDeclaration => Some(parent.parent()?.stable_ptr()),
+        // Local variables are defined by their names.
+        SyntaxKind::PatternIdentifier => Some(node.stable_ptr()),
+        SyntaxKind::ItemConstant
+        | SyntaxKind::ItemStruct
+        | SyntaxKind::Member
+        | SyntaxKind::ItemEnum
+        | SyntaxKind::Variant
+        | SyntaxKind::ItemTrait
+        | SyntaxKind::ItemImpl
+        | SyntaxKind::ItemTypeAlias
+        | SyntaxKind::ItemImplAlias
+        | SyntaxKind::ItemExternType
+        | SyntaxKind::Param
+        | SyntaxKind::GenericParamType
+        | SyntaxKind::GenericParamConst
+        | SyntaxKind::GenericParamImplNamed => Some(parent.stable_ptr()),
+        _ => None,
+    }
+}
+
+/// Returns whether the identifier is a path pattern, declaring a local variable (e.g. `x` in
+/// `let x = 1;`). These are parsed as paths, so they are told apart from expressions by their
+/// semantic model.
+fn is_path_pattern(
+    db: &RootDatabase,
+    identifier: &ast::TerminalIdentifier,
+    lookup_items: &[LookupItemId],
+) -> bool {
+    let Some(path) = identifier.as_syntax_node().parent().and_then(|segment| segment.parent())
+    else {
+        return false;
+    };
+    if path.kind(db) != SyntaxKind::ExprPath {
+        return false;
+    }
+    let Some(function_id) = lookup_items.first().copied().and_then(function_with_body_id) else {
+        return false;
+    };
+    let pattern_ptr = ast::Pattern::from_syntax_node(db, path).stable_ptr();
+    db.lookup_pattern_by_ptr(function_id, pattern_ptr).is_ok()
+}
+
+/// If the identifier names a member of a struct in a member access, a struct constructor or a
+/// struct pattern, returns the definition of the member.
+fn member_definition(
+    db: &RootDatabase,
+    identifier: &ast::TerminalIdentifier,
+    lookup_items: &[LookupItemId],
+) -> Option<SyntaxStablePtrId> {
+    let function_id = lookup_items.first().copied().and_then(function_with_body_id)?;
+    let name = identifier.text(db);
+    let parent = identifier.as_syntax_node().parent()?;
+    let member_id = match parent.kind(db) {
+        // `expr.member`.
+        SyntaxKind::PathSegmentSimple => {
+            let path = parent.parent()?;
+            let binary = path.parent()?;
+            if binary.kind(db) != SyntaxKind::ExprBinary
+                || ast::ExprBinary::from_syntax_node(db, binary.clone()).rhs(db).as_syntax_node()
+                    != path
+            {
+                return None;
+            }
+            let expr_ptr = ast::Expr::from_syntax_node(db, binary).stable_ptr();
+            let mut expr =
+                db.expr_semantic(function_id, db.lookup_expr_by_ptr(function_id, expr_ptr).ok()?);
+            // The receiver of a method taking a snapshot is replaced by a snapshot of it.
+            if let Expr::Snapshot(snapshot) = expr {
+                expr = db.expr_semantic(function_id, snapshot.inner);
+            }
+            let Expr::MemberAccess(member_access) = expr else {
+                return None;
+            };
+            member_access.member
+        }
+        // `Struct { member: expr }` and `Struct { member }`.
+        SyntaxKind::StructArgSingle => {
+            let ctor = parent.parent()?.parent()?.parent()?;
+            let expr_ptr = ast::Expr::from_syntax_node(db, ctor).stable_ptr();
+            let expr_id = db.lookup_expr_by_ptr(function_id, expr_ptr).ok()?;
+            let Expr::StructCtor(ctor) = db.expr_semantic(function_id, expr_id) else {
+                return None;
+            };
+            ctor.members
+                .into_iter()
+                .map(|(member_id, _)| member_id)
+                .find(|member_id| member_id.name(db) == name)?
+        }
+        // `Struct { member: pattern }` and `Struct { member }` patterns.
+        SyntaxKind::PatternStructParamWithExpr | SyntaxKind::PatternIdentifier => {
+            let pattern_struct = parent.parent()?.parent()?;
+            if pattern_struct.kind(db) != SyntaxKind::PatternStruct {
+                return None;
+            }
+            let pattern_ptr = ast::Pattern::from_syntax_node(db, pattern_struct).stable_ptr();
+            let pattern_id = db.lookup_pattern_by_ptr(function_id, pattern_ptr).ok()?;
+            let Pattern::Struct(pattern) = db.pattern_semantic(function_id, pattern_id) else {
+                return None;
+            };
+            pattern
+                .field_patterns
+                .into_iter()
+                .map(|(member, _)| member.id)
+                .find(|member_id| member_id.name(db) == name)?
+        }
+        _ => return None,
+    };
+    Some(member_id.stable_ptr(db).untyped())
+}
+
+/// Returns the function with a body of a lookup item, if it is one.
+pub fn function_with_body_id(lookup_item_id: LookupItemId) -> Option<FunctionWithBodyId> {
+    match lookup_item_id {
+        LookupItemId::ModuleItem(ModuleItemId::FreeFunction(free_function_id)) => {
+            Some(FunctionWithBodyId::Free(free_function_id))
+        }
+        LookupItemId::ImplFunction(impl_function_id) => {
+            Some(FunctionWithBodyId::Impl(impl_function_id))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,147 @@
+use indoc::indoc;
+use lsp::Range;
+
+use super::{find_references, symbol_at, workspace_files};
+use crate::test_utils::{position_of, range_of, setup_test_crate};
+
+/// Returns the ranges of the references to the symbol at the `nth` occurrence of `pattern` in the
+/// only file of a test crate with the given content, and whether each of them is a declaration.
+fn references(content: &str, pattern: &str, nth: usize) -> Vec<(Range, bool)> {
+    let (db, files) = setup_test_crate(&[("lib.cairo", content)]);
+    let symbol = symbol_at(&db, files[0], position_of(content, pattern, nth)).unwrap();
+    find_references(&db, &symbol, workspace_files(&db))
+        .into_iter()
+        .map(|reference| {
+            assert_eq!(reference.file, files[0]);
+            (reference.range, reference.is_declaration)
+        })
+        .collect()
+}
+
+#[test]
+fn test_local_variable_references() {
+    let content = indoc! {"
+        fn foo(x: felt252) -> felt252 {
+            let y = x + 1;
+            let x = y * 2;
+            x + y
+        }
+    "};
+    assert_eq!(
+        references(content, "y", 1),
+        vec![
+            (range_of(content, "y", 0), true),
+            (range_of(content, "y", 1), false),
+            (range_of(content, "y", 2), false),
+        ]
+    );
+    // The shadowing variable is a different symbol than the parameter.
+    assert_eq!(
+        references(content, "x", 3),
+        vec![(range_of(content, "x", 2), true), (range_of(content, "x", 3), false)]
+    );
+    assert_eq!(
+        references(content, "x", 0),
+        vec![(range_of(content, "x", 0), true), (range_of(content, "x", 1), false)]
+    );
+}
+
+#[test]
+fn test_member_references() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        struct Point {
+            x: felt252,
+            y: felt252,
+        }
+        fn foo(p: Point) -> Point {
+            let Point { x, y: other } = p;
+            Point { x, y: x + other }
+        }
+    "};
+    // The shorthands name both the member and the variables.
+    assert_eq!(
+        references(content, "x", 0),
+        vec![
+            (range_of(content, "x", 0), true),
+            (range_of(content, "x", 1), false),
+            (range_of(content, "x", 2), false),
+        ]
+    );
+    assert_eq!(
+        references(content, "x", 2),
+        vec![
+            (range_of(content, "x", 1), true),
+            (range_of(content, "x", 2), false),
+            (range_of(content, "x", 3), false),
+        ]
+    );
+}
+
+#[test]
+fn test_member_access_references() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        struct Wrapper {
+            values: Array<felt252>,
+        }
+        fn foo(ref w: Wrapper) -> usize {
+            w.values.append(1);
+            w.values.len()
+        }
+    "};
+    // The receiver of `len` is a snapshot of the member.
+    assert_eq!(
+        references(content, "values", 2),
+        vec![
+            (range_of(content, "values", 0), true),
+            (range_of(content, "values", 1), false),
+            (range_of(content, "values", 2), false),
+        ]
+    );
+}
+
+#[test]
+fn test_item_references_across_files() {
+    let lib_content = indoc! {"
+        mod utils;
+        use utils::double;
+        fn foo() -> felt252 {
+            double(utils::double(1))
+        }
+    "};
+    let utils_content = indoc! {"
+        fn double(x: felt252) -> felt252 {
+            x * 2
+        }
+    "};
+    let (db, files) =
+        setup_test_crate(&[("lib.cairo", lib_content), ("utils.cairo", utils_content)]);
+    let symbol = symbol_at(&db, files[0], position_of(lib_content, "double", 1)).unwrap();
+    let references: Vec<_> = find_references(&db, &symbol, workspace_files(&db))
+        .into_iter()
+        .map(|reference| (reference.file, reference.range, reference.is_declaration))
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            (files[0], range_of(lib_content, "double", 0), false),
+            (files[0], range_of(lib_content, "double", 1), false),
+            (files[0], range_of(lib_content, "double", 2), false),
+            (files[1], range_of(utils_content, "double", 0), true),
+        ]
+    );
+    let symbol = symbol_at(&db, files[0], position_of(lib_content, "utils", 2)).unwrap();
+    let references: Vec<_> = find_references(&db, &symbol, workspace_files(&db))
+        .into_iter()
+        .map(|reference| (reference.range, reference.is_declaration))
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            (range_of(lib_content, "utils", 0), true),
+            (range_of(lib_content, "utils", 1), false),
+            (range_of(lib_content, "utils", 2), false),
+        ]
+    );
+}
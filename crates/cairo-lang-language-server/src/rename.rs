@@ -0,0 +1,204 @@
+//! Renaming of symbols, along with all their references in the workspace.
+
+use std::collections::HashMap;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::ModuleId;
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::{FileId, FileLongId};
+use cairo_lang_parser::lexer::Lexer;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::utils::is_grandparent_of_kind;
+use cairo_lang_syntax::node::{SyntaxNode, TypedSyntaxNode};
+use lsp::{
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    Position, Range, RenameFile, ResourceOp, TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+use tower_lsp::jsonrpc::{Error as LSPError, Result as LSPResult};
+
+use crate::references::{
+    definition_origin, find_references, symbol_at, workspace_files, Symbol, SymbolReference,
+};
+use crate::{get_node_origin_range, get_uri};
+
+#[cfg(test)]
+#[path = "rename_test.rs"]
+mod test;
+
+/// Returns the range of the name of the symbol at the given position, if the symbol can be
+/// renamed. Fails with the reason if it cannot.
+pub fn prepare_rename(
+    db: &RootDatabase,
+    file: FileId,
+    position: Position,
+) -> LSPResult<Option<Range>> {
+    let Some(symbol) = symbol_at(db, file, position) else { return Ok(None) };
+    check_renamable(db, &symbol)?;
+    Ok(find_references(db, &symbol, [file])
+        .into_iter()
+        .map(|reference| reference.range)
+        .find(|range| range.start <= position && position <= range.end))
+}
+
+/// Returns the edits renaming the symbol at the given position, and all its references in the
+/// workspace, to `new_name`.
+pub fn rename(
+    db: &RootDatabase,
+    file: FileId,
+    position: Position,
+    new_name: &str,
+) -> LSPResult<Option<WorkspaceEdit>> {
+    let Some(symbol) = symbol_at(db, file, position) else { return Ok(None) };
+    check_renamable(db, &symbol)?;
+    if !is_identifier(db, file, new_name) {
+        return Err(LSPError::invalid_params(format!("`{new_name}` is not a valid identifier.")));
+    }
+    let definition = symbol.definition.lookup(db);
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for reference in find_references(db, &symbol, workspace_files(db)) {
+        let edit = reference_edit(db, &symbol, &definition, &reference, new_name);
+        changes.entry(get_uri(db, reference.file)).or_default().extend(edit);
+    }
+    let file_renames = if definition.kind(db) == SyntaxKind::SyntaxFile {
+        module_file_renames(db, definition.stable_ptr().file_id(db), new_name)
+    } else {
+        vec![]
+    };
+    if file_renames.is_empty() {
+        return Ok(Some(WorkspaceEdit { changes: Some(changes), ..WorkspaceEdit::default() }));
+    }
+    // Renaming files requires the edits to be given as document changes.
+    let edits = changes.into_iter().map(|(uri, edits)| {
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+            edits: edits.into_iter().map(OneOf::Left).collect(),
+        })
+    });
+    let renames = file_renames.into_iter().map(|(old_uri, new_uri)| {
+        DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+            old_uri,
+            new_uri,
+            options: None,
+            annotation_id: None,
+        }))
+    });
+    Ok(Some(WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(edits.chain(renames).collect())),
+        ..WorkspaceEdit::default()
+    }))
+}
+
+/// Fails with the reason if the symbol cannot be renamed.
+fn check_renamable(db: &RootDatabase, symbol: &Symbol) -> LSPResult<()> {
+    let definition = symbol.definition.lookup(db);
+    // Symbols defined in code generated by plugins are renamed in the code they were generated
+    // from, if there is one.
+    let Some((file, _)) = definition_origin(db, symbol.definition) else {
+        return Err(LSPError::invalid_params("Symbols generated by plugins cannot be renamed."));
+    };
+    let modules = db.file_modules(file).unwrap_or_default();
+    if modules.iter().any(|module_id| module_id.owning_crate(db) == db.core_crate()) {
+        return Err(LSPError::invalid_params("Symbols of the core library cannot be renamed."));
+    }
+    let renamable = match definition.kind(db) {
+        // Local variables are defined by their names.
+        SyntaxKind::TerminalIdentifier
+        | SyntaxKind::Param
+        | SyntaxKind::ItemStruct
+        | SyntaxKind::Member
+        | SyntaxKind::ItemEnum
+        | SyntaxKind::Variant
+        | SyntaxKind::ItemTrait
+        | SyntaxKind::ItemImpl
+        // The body of an inline module.
+        | SyntaxKind::ModuleBody => true,
+        // References to the functions of impls resolve to the functions of their traits.
+        SyntaxKind::FunctionWithBody => {
+            !is_grandparent_of_kind(db, &definition, SyntaxKind::ImplBody)
+        }
+        // The file of a module.
+        SyntaxKind::SyntaxFile => {
+            if modules.iter().any(|module_id| matches!(module_id, ModuleId::CrateRoot(_))) {
+                return Err(LSPError::invalid_params("Crates cannot be renamed."));
+            }
+            true
+        }
+        _ => false,
+    };
+    if !renamable {
+        return Err(LSPError::invalid_params("Renaming this kind of symbol is not supported."));
+    }
+    Ok(())
+}
+
+/// Returns whether `name` is a valid identifier, rather than a keyword or any other text.
+fn is_identifier(db: &RootDatabase, file: FileId, name: &str) -> bool {
+    let mut lexer = Lexer::from_text(db, file, name);
+    matches!(
+        (lexer.next(), lexer.next()),
+        (Some(identifier), Some(end)) if identifier.kind == SyntaxKind::TerminalIdentifier
+            && identifier.text == name
+            && end.kind == SyntaxKind::TerminalEndOfFile
+            && end.leading_trivia.is_empty()
+    )
+}
+
+/// Returns the edits renaming a single reference to the symbol.
+fn reference_edit(
+    db: &RootDatabase,
+    symbol: &Symbol,
+    definition: &SyntaxNode,
+    reference: &SymbolReference,
+    new_name: &str,
+) -> Vec<TextEdit> {
+    let rename = TextEdit { range: reference.range, new_text: new_name.into() };
+    // A shorthand of a member (e.g. `x` in `Point { x }`, or `mut x` in a pattern) names both the
+    // member and a variable, so it is expanded to keep naming the symbol which is not renamed.
+    let Some(shorthand) =
+        reference.identifier.as_syntax_node().parent().filter(|parent| match parent.kind(db) {
+            SyntaxKind::StructArgSingle => parent
+                .children(db)
+                .nth(1)
+                .is_some_and(|arg_expr| arg_expr.kind(db) == SyntaxKind::OptionStructArgExprEmpty),
+            SyntaxKind::PatternIdentifier => parent
+                .parent()
+                .is_some_and(|list| list.kind(db) == SyntaxKind::PatternStructParamList),
+            _ => false,
+        })
+    else {
+        return vec![rename];
+    };
+    let Some((_, shorthand_range)) = get_node_origin_range(db, &shorthand)
+        .filter(|(shorthand_file, _)| *shorthand_file == reference.file)
+    else {
+        return vec![rename];
+    };
+    let shorthand_start = Range { start: shorthand_range.start, end: shorthand_range.start };
+    if definition.kind(db) == SyntaxKind::Member {
+        vec![TextEdit { range: shorthand_start, new_text: format!("{new_name}: ") }]
+    } else if shorthand_range.start == reference.range.start {
+        vec![TextEdit { range: reference.range, new_text: format!("{}: {new_name}", symbol.name) }]
+    } else {
+        vec![TextEdit { range: shorthand_start, new_text: format!("{}: ", symbol.name) }, rename]
+    }
+}
+
+/// Returns the renames of the files of a module defined in its own file - the file of the module,
+/// and the directory of its submodules, if it exists.
+fn module_file_renames(db: &RootDatabase, file: FileId, new_name: &str) -> Vec<(Url, Url)> {
+    let FileLongId::OnDisk(path) = db.lookup_intern_file(file) else { return vec![] };
+    let Some(old_name) = path.file_stem().map(|stem| stem.to_owned()) else { return vec![] };
+    let module_dir = path.with_file_name(old_name);
+    let mut renames = vec![(path.clone(), path.with_file_name(format!("{new_name}.cairo")))];
+    if module_dir.is_dir() {
+        renames.push((module_dir, path.with_file_name(new_name)));
+    }
+    renames
+        .into_iter()
+        .filter_map(|(old_path, new_path)| {
+            Some((Url::from_file_path(old_path).ok()?, Url::from_file_path(new_path).ok()?))
+        })
+        .collect()
+}
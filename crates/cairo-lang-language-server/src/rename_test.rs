@@ -0,0 +1,183 @@
+use indoc::indoc;
+use lsp::{
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    Range, RenameFile, ResourceOp, TextDocumentEdit, TextEdit,
+};
+
+use super::{is_identifier, prepare_rename, rename};
+use crate::get_uri;
+use crate::test_utils::{position_of, range_of, setup_test_crate};
+
+/// Returns the edits renaming the symbol at the `nth` occurrence of `pattern` in the only file of a
+/// test crate with the given content to `new_name`, ordered by their positions.
+fn rename_edits(content: &str, pattern: &str, nth: usize, new_name: &str) -> Vec<TextEdit> {
+    let (db, files) = setup_test_crate(&[("lib.cairo", content)]);
+    let edit =
+        rename(&db, files[0], position_of(content, pattern, nth), new_name).unwrap().unwrap();
+    let mut changes = edit.changes.unwrap();
+    assert_eq!(changes.len(), 1);
+    let mut edits = changes.remove(&get_uri(&db, files[0])).unwrap();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+    edits
+}
+
+/// Returns an edit replacing the `nth` occurrence of `pattern` in `content` with `new_text`.
+fn edit_of(content: &str, pattern: &str, nth: usize, new_text: &str) -> TextEdit {
+    TextEdit { range: range_of(content, pattern, nth), new_text: new_text.into() }
+}
+
+/// Returns an edit inserting `new_text` before the `nth` occurrence of `pattern` in `content`.
+fn insertion_of(content: &str, pattern: &str, nth: usize, new_text: &str) -> TextEdit {
+    let start = position_of(content, pattern, nth);
+    TextEdit { range: Range { start, end: start }, new_text: new_text.into() }
+}
+
+#[test]
+fn test_rename_storage_member() {
+    let content = indoc! {"
+        #[starknet::contract]
+        mod counter_contract {
+            #[storage]
+            struct Storage {
+                counter: u128,
+            }
+
+            #[external(v0)]
+            fn increase(ref self: ContractState, amount: u128) {
+                self.counter.write(self.counter.read() + amount);
+            }
+        }
+    "};
+    assert_eq!(
+        rename_edits(content, "counter", 2, "value"),
+        vec![
+            edit_of(content, "counter", 1, "value"),
+            edit_of(content, "counter", 2, "value"),
+            edit_of(content, "counter", 3, "value"),
+        ]
+    );
+}
+
+#[test]
+fn test_rename_member_shorthands() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        struct Point {
+            x: felt252,
+        }
+        fn foo(p: Point) -> Point {
+            let Point { x } = p;
+            Point { x }
+        }
+    "};
+    // The shorthands keep naming the variable.
+    assert_eq!(
+        rename_edits(content, "x", 0, "a"),
+        vec![
+            edit_of(content, "x", 0, "a"),
+            insertion_of(content, "x", 1, "a: "),
+            insertion_of(content, "x", 2, "a: "),
+        ]
+    );
+    // The shorthands keep naming the member.
+    assert_eq!(
+        rename_edits(content, "x", 2, "b"),
+        vec![edit_of(content, "x", 1, "x: b"), edit_of(content, "x", 2, "x: b")]
+    );
+}
+
+#[test]
+fn test_rename_mutable_shorthand() {
+    let content = indoc! {"
+        #[derive(Drop)]
+        struct Point {
+            x: felt252,
+        }
+        fn foo(p: Point) -> felt252 {
+            let Point { mut x } = p;
+            x += 1;
+            x
+        }
+    "};
+    assert_eq!(
+        rename_edits(content, "x", 2, "b"),
+        vec![
+            insertion_of(content, "mut", 0, "x: "),
+            edit_of(content, "x", 1, "b"),
+            edit_of(content, "x", 2, "b"),
+            edit_of(content, "x", 3, "b"),
+        ]
+    );
+}
+
+#[test]
+fn test_rename_module_file() {
+    let lib_content = indoc! {"
+        mod utils;
+        fn foo() -> felt252 {
+            utils::one()
+        }
+    "};
+    let (db, files) = setup_test_crate(&[
+        ("lib.cairo", lib_content),
+        ("utils.cairo", "fn one() -> felt252 { 1 }"),
+    ]);
+    let edit =
+        rename(&db, files[0], position_of(lib_content, "utils", 1), "helpers").unwrap().unwrap();
+    let lib_uri = get_uri(&db, files[0]);
+    let utils_uri = get_uri(&db, files[1]);
+    assert_eq!(edit.changes, None);
+    assert_eq!(
+        edit.document_changes,
+        Some(DocumentChanges::Operations(vec![
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier {
+                    uri: lib_uri,
+                    version: None
+                },
+                edits: vec![
+                    OneOf::Left(edit_of(lib_content, "utils", 0, "helpers")),
+                    OneOf::Left(edit_of(lib_content, "utils", 1, "helpers")),
+                ],
+            }),
+            DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+                old_uri: utils_uri.clone(),
+                new_uri: utils_uri.join("helpers.cairo").unwrap(),
+                options: None,
+                annotation_id: None,
+            })),
+        ]))
+    );
+}
+
+#[test]
+fn test_rename_errors() {
+    let content = indoc! {"
+        fn foo(values: Array<felt252>) -> felt252 {
+            let x = 1;
+            x
+        }
+    "};
+    let (db, files) = setup_test_crate(&[("lib.cairo", content)]);
+    let rename_error = |pattern, new_name| {
+        rename(&db, files[0], position_of(content, pattern, 0), new_name).unwrap_err().message
+    };
+    assert_eq!(rename_error("Array", "List"), "Symbols of the core library cannot be renamed.");
+    assert_eq!(rename_error("x", "let"), "`let` is not a valid identifier.");
+    assert_eq!(
+        prepare_rename(&db, files[0], position_of(content, "x", 1)).unwrap(),
+        Some(range_of(content, "x", 1))
+    );
+    assert_eq!(prepare_rename(&db, files[0], position_of(content, "{", 0)).unwrap(), None);
+}
+
+#[test]
+fn test_is_identifier() {
+    let (db, files) = setup_test_crate(&[("lib.cairo", "")]);
+    for name in ["x", "_x", "snake_case", "CamelCase", "x1"] {
+        assert!(is_identifier(&db, files[0], name), "`{name}` should be an identifier.");
+    }
+    for name in ["", "1x", "fn", "let", "x y", " x", "x::y", "x // y", "'x'"] {
+        assert!(!is_identifier(&db, files[0], name), "`{name}` should not be an identifier.");
+    }
+}
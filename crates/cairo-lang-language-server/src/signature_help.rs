@@ -0,0 +1,156 @@
+//! Help with the signatures of the functions called at a position.
+
+use std::iter::successors;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::ids::{FunctionWithBodyId, LookupItemId};
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::FileId;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::items::function_with_body::SemanticExprLookup;
+use cairo_lang_semantic::resolve::ResolvedConcreteItem;
+use cairo_lang_semantic::{Expr, FunctionId, Mutability, Parameter, Signature};
+use cairo_lang_syntax::node::helpers::PathSegmentEx;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, TypedSyntaxNode};
+use lsp::{ParameterInformation, ParameterLabel, Position, SignatureHelp, SignatureInformation};
+
+use crate::references::function_with_body_id;
+use crate::{get_node_and_lookup_items, position_to_offset};
+
+#[cfg(test)]
+#[path = "signature_help_test.rs"]
+mod test;
+
+/// Returns the signature of the function called by the innermost call whose arguments contain the
+/// given position, with the parameter of the argument at the position as the active one.
+pub fn signature_help(
+    db: &RootDatabase,
+    file: FileId,
+    position: Position,
+) -> Option<SignatureHelp> {
+    let offset = position_to_offset(db.file_summary(file)?, position, &db.file_content(file)?)?;
+    let (node, lookup_items) = get_node_and_lookup_items(db, file, position)?;
+    let lookup_item_id = lookup_items.first().copied()?;
+    let function_id = function_with_body_id(lookup_item_id)?;
+    let call = successors(Some(node), SyntaxNode::parent).find_map(|ancestor| {
+        if ancestor.kind(db) != SyntaxKind::ArgListParenthesized
+            || ancestor.parent()?.kind(db) != SyntaxKind::ExprFunctionCall
+        {
+            return None;
+        }
+        let args = ast::ArgListParenthesized::from_syntax_node(db, ancestor.clone());
+        let lparen_start = args.lparen(db).as_syntax_node().span_without_trivia(db).start;
+        let rparen_start = args.rparen(db).as_syntax_node().span_without_trivia(db).start;
+        if offset <= lparen_start || rparen_start < offset {
+            return None;
+        }
+        Some(ast::ExprFunctionCall::from_syntax_node(db, ancestor.parent()?))
+    })?;
+    let (called_function, is_method) = match semantic_call(db, function_id, &call) {
+        Some(semantic_call) => semantic_call,
+        // The arguments may not be complete yet, so the call itself may not be valid.
+        None => (resolved_call(db, lookup_item_id, &call)?, false),
+    };
+    let signature = db.concrete_function_signature(called_function).ok()?;
+    let preceding_commas = call
+        .arguments(db)
+        .args(db)
+        .as_syntax_node()
+        .children(db)
+        .filter(|child| {
+            child.kind(db) == SyntaxKind::TerminalComma
+                && child.span_without_trivia(db).start < offset
+        })
+        .count();
+    // The first parameter of a method is its receiver, which is not in the arguments.
+    let active_parameter = preceding_commas + usize::from(is_method);
+    Some(SignatureHelp {
+        signatures: vec![signature_information(db, called_function, &signature)],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter as u32),
+    })
+}
+
+/// Returns the function called by a call expression, as inferred by the semantic model, and
+/// whether it is called as a method (e.g. `x.foo(y)`).
+pub fn semantic_call(
+    db: &RootDatabase,
+    function_id: FunctionWithBodyId,
+    call: &ast::ExprFunctionCall,
+) -> Option<(FunctionId, bool)> {
+    let call_node = call.as_syntax_node();
+    // The expression of a method call is the whole binary expression with its receiver.
+    let method_call = call_node.parent().filter(|parent| {
+        parent.kind(db) == SyntaxKind::ExprBinary && {
+            let binary = ast::ExprBinary::from_syntax_node(db, parent.clone());
+            matches!(binary.op(db), ast::BinaryOperator::Dot(_))
+                && binary.rhs(db).as_syntax_node() == call_node
+        }
+    });
+    let is_method = method_call.is_some();
+    let expr_node = method_call.unwrap_or(call_node);
+    let expr_ptr = ast::Expr::from_syntax_node(db, expr_node).stable_ptr();
+    let expr_id = db.lookup_expr_by_ptr(function_id, expr_ptr).ok()?;
+    let Expr::FunctionCall(expr) = db.expr_semantic(function_id, expr_id) else { return None };
+    Some((expr.function, is_method))
+}
+
+/// Returns the function a call expression calls by its path, as resolved by the semantic model.
+fn resolved_call(
+    db: &RootDatabase,
+    lookup_item_id: LookupItemId,
+    call: &ast::ExprFunctionCall,
+) -> Option<FunctionId> {
+    let identifier = call.path(db).elements(db).last()?.identifier_ast(db);
+    match db.lookup_resolved_concrete_item_by_ptr(lookup_item_id, identifier.stable_ptr())? {
+        ResolvedConcreteItem::Function(function_id) => Some(function_id),
+        _ => None,
+    }
+}
+
+/// Returns the information of the signature of a function, e.g. `fn foo(ref x: u32) -> u32`.
+fn signature_information(
+    db: &RootDatabase,
+    function_id: FunctionId,
+    signature: &Signature,
+) -> SignatureInformation {
+    let mut label = format!("fn {}(", function_id.get_concrete(db).generic_function.name(db));
+    let mut parameters = vec![];
+    for (i, param) in signature.params.iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        // The offsets of the labels of parameters are in UTF-16 code units.
+        let start = label.encode_utf16().count() as u32;
+        label.push_str(&parameter_label(db, param));
+        let end = label.encode_utf16().count() as u32;
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        });
+    }
+    label.push(')');
+    if !signature.return_type.is_unit(db) {
+        label.push_str(&format!(" -> {}", signature.return_type.format(db)));
+    }
+    if !signature.panicable {
+        label.push_str(" nopanic");
+    }
+    SignatureInformation {
+        label,
+        documentation: None,
+        parameters: Some(parameters),
+        active_parameter: None,
+    }
+}
+
+/// Returns the label of a parameter in a signature, e.g. `ref x: u32`.
+pub fn parameter_label(db: &RootDatabase, param: &Parameter) -> String {
+    let modifier = match param.mutability {
+        Mutability::Immutable => "",
+        Mutability::Mutable => "mut ",
+        Mutability::Reference => "ref ",
+    };
+    format!("{modifier}{}: {}", param.name, param.ty.format(db))
+}
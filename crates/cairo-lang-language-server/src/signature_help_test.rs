@@ -0,0 +1,82 @@
+use indoc::indoc;
+use lsp::{ParameterLabel, Position};
+
+use super::signature_help;
+use crate::test_utils::{position_of, setup_test_crate};
+
+const CONTENT: &str = indoc! {"
+    trait ShapeTrait<T> {
+        fn scale(ref self: T, factor: u32);
+    }
+    #[derive(Drop)]
+    struct Square {
+        side: u32,
+    }
+    impl SquareShape of ShapeTrait<Square> {
+        fn scale(ref self: Square, factor: u32) {
+            self.side *= factor;
+        }
+    }
+    fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+    fn foo(ref s: Square) {
+        let x = add(1, add(2, 3));
+        s.scale(x);
+        add(4, );
+    }
+"};
+
+/// Returns the label of the signature at the position, the labels of its parameters and the index
+/// of the active parameter.
+fn signature_at(position: Position) -> Option<(String, Vec<String>, u32)> {
+    let (db, files) = setup_test_crate(&[("lib.cairo", CONTENT)]);
+    let help = signature_help(&db, files[0], position)?;
+    let [signature] = &help.signatures[..] else { panic!("Expected a single signature.") };
+    let label: Vec<u16> = signature.label.encode_utf16().collect();
+    let parameters = signature
+        .parameters
+        .iter()
+        .flatten()
+        .map(|parameter| {
+            let ParameterLabel::LabelOffsets([start, end]) = parameter.label else {
+                panic!("Expected label offsets.")
+            };
+            String::from_utf16(&label[start as usize..end as usize]).unwrap()
+        })
+        .collect();
+    Some((signature.label.clone(), parameters, help.active_parameter.unwrap()))
+}
+
+#[test]
+fn test_signature_help() {
+    let add_label = "fn add(a: core::integer::u32, b: core::integer::u32) -> core::integer::u32";
+    let add_parameters = vec!["a: core::integer::u32".into(), "b: core::integer::u32".into()];
+    assert_eq!(
+        signature_at(position_of(CONTENT, "1", 0)),
+        Some((add_label.into(), add_parameters.clone(), 0))
+    );
+    // The innermost call is the one helped with.
+    assert_eq!(
+        signature_at(position_of(CONTENT, "3))", 0)),
+        Some((add_label.into(), add_parameters.clone(), 1))
+    );
+    // The arguments of a call which is not valid yet, before its closing parenthesis.
+    let incomplete_args = position_of(CONTENT, "4, )", 0);
+    assert_eq!(
+        signature_at(Position { character: incomplete_args.character + 3, ..incomplete_args }),
+        Some((add_label.into(), add_parameters, 1))
+    );
+    // The receiver of a method is its first parameter, and its impl is a part of its name.
+    assert_eq!(
+        signature_at(position_of(CONTENT, "x);", 0)),
+        Some((
+            "fn SquareShape::scale(ref self: test::Square, factor: core::integer::u32)".into(),
+            vec!["ref self: test::Square".into(), "factor: core::integer::u32".into()],
+            1
+        ))
+    );
+    // Not in the arguments of a call.
+    assert_eq!(signature_at(position_of(CONTENT, "add(1", 0)), None);
+    assert_eq!(signature_at(position_of(CONTENT, "let", 0)), None);
+}
@@ -0,0 +1,206 @@
+//! Listing of the symbols defined in files (the outline of a file) and in the whole workspace.
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{LanguageElementId, ModuleId, ModuleItemId, TopLevelLanguageElementId};
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::FileId;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_syntax::node::ast;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use lsp::{DocumentSymbol, Location, SymbolInformation, SymbolKind};
+
+use crate::{get_uri, name_span, origin_span, span_range};
+
+#[cfg(test)]
+#[path = "symbols_test.rs"]
+mod test;
+
+/// The maximal number of symbols returned for a workspace symbols query.
+const MAX_WORKSPACE_SYMBOLS: usize = 128;
+
+/// Returns the symbols defined in a file, with the members of structs, the variants of enums, the
+/// functions of traits and impls and the items of inline modules as their children.
+pub fn document_symbols(db: &RootDatabase, file: FileId) -> Vec<DocumentSymbol> {
+    let modules = db.file_modules(file).unwrap_or_default();
+    // The modules of the file which are not inline modules of other modules in it.
+    let file_modules = modules.iter().filter(|module_id| match module_id {
+        ModuleId::CrateRoot(_) => true,
+        ModuleId::Submodule(submodule_id) => submodule_id.stable_ptr(db).0.file_id(db) != file,
+    });
+    file_modules.flat_map(|module_id| module_symbols(db, file, *module_id)).collect()
+}
+
+/// Returns the symbols of the items of all the modules of all the crates known to the server,
+/// whose names match the query.
+pub fn workspace_symbols(db: &RootDatabase, query: &str) -> Vec<SymbolInformation> {
+    let mut symbols = vec![];
+    for crate_id in db.crates() {
+        for module_id in db.crate_modules(crate_id).iter() {
+            let container_name = module_id.full_path(db);
+            for item in db.module_items(*module_id).unwrap_or_default().iter() {
+                let Some(kind) = item_kind(item) else { continue };
+                let item_symbol = (item.name(db).to_string(), kind, item.untyped_stable_ptr(db));
+                let functions = item_functions(db, item)
+                    .into_iter()
+                    .map(|(name, ptr)| (name, SymbolKind::METHOD, ptr));
+                for (name, kind, ptr) in [item_symbol].into_iter().chain(functions) {
+                    let Some(score) = fuzzy_match_score(query, &name) else { continue };
+                    let Some((file, span)) = origin_span(db, ptr, name_span(db, ptr)) else {
+                        continue;
+                    };
+                    let Some(range) = span_range(db, file, span) else { continue };
+                    #[allow(deprecated)]
+                    symbols.push((
+                        score,
+                        SymbolInformation {
+                            name,
+                            kind,
+                            tags: None,
+                            deprecated: None,
+                            location: Location { uri: get_uri(db, file), range },
+                            container_name: Some(container_name.clone()),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+    symbols.sort_by_key(|(score, symbol)| (*score, symbol.name.len()));
+    symbols.into_iter().map(|(_, symbol)| symbol).take(MAX_WORKSPACE_SYMBOLS).collect()
+}
+
+/// Returns the symbols of the items of a module, which are defined in the given file.
+fn module_symbols(db: &RootDatabase, file: FileId, module_id: ModuleId) -> Vec<DocumentSymbol> {
+    let mut symbols = vec![];
+    for item in db.module_items(module_id).unwrap_or_default().iter() {
+        let Some(kind) = item_kind(item) else { continue };
+        let Some(mut symbol) =
+            document_symbol(db, file, item.name(db).into(), kind, item.untyped_stable_ptr(db))
+        else {
+            continue;
+        };
+        let children: Vec<_> = match item {
+            ModuleItemId::Submodule(submodule_id) => {
+                let is_inline = matches!(
+                    submodule_id.stable_ptr(db).lookup(db).body(db),
+                    ast::MaybeModuleBody::Some(_)
+                );
+                if is_inline {
+                    module_symbols(db, file, ModuleId::Submodule(*submodule_id))
+                } else {
+                    vec![]
+                }
+            }
+            ModuleItemId::Struct(struct_id) => db
+                .struct_members(*struct_id)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|(name, member)| {
+                    let ptr = member.id.untyped_stable_ptr(db);
+                    document_symbol(db, file, name.to_string(), SymbolKind::FIELD, ptr)
+                })
+                .collect(),
+            ModuleItemId::Enum(enum_id) => db
+                .enum_variants(*enum_id)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|(name, variant_id)| {
+                    let ptr = variant_id.untyped_stable_ptr(db);
+                    document_symbol(db, file, name.to_string(), SymbolKind::ENUM_MEMBER, ptr)
+                })
+                .collect(),
+            _ => item_functions(db, item)
+                .into_iter()
+                .filter_map(|(name, ptr)| document_symbol(db, file, name, SymbolKind::METHOD, ptr))
+                .collect(),
+        };
+        if !children.is_empty() {
+            symbol.children = Some(children);
+        }
+        symbols.push(symbol);
+    }
+    symbols
+}
+
+/// Returns the symbol of a definition, if its name is in the given file - either directly or in
+/// code generated by plugins from the file.
+fn document_symbol(
+    db: &RootDatabase,
+    file: FileId,
+    name: String,
+    kind: SymbolKind,
+    ptr: SyntaxStablePtrId,
+) -> Option<DocumentSymbol> {
+    let (name_file, name_span) = origin_span(db, ptr, name_span(db, ptr))?;
+    if name_file != file {
+        return None;
+    }
+    let selection_range = span_range(db, file, name_span)?;
+    // The whole definition may not originate from the file, e.g. if it is partially generated.
+    let range = origin_span(db, ptr, ptr.lookup(db).span_without_trivia(db))
+        .filter(|(definition_file, _)| *definition_file == file)
+        .and_then(|(_, span)| span_range(db, file, span))
+        .filter(|range| range.start <= selection_range.start && selection_range.end <= range.end)
+        .unwrap_or(selection_range);
+    #[allow(deprecated)]
+    Some(DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: None,
+    })
+}
+
+/// Returns the kind of the symbol of a module item, or None if the item does not define a symbol.
+fn item_kind(item: &ModuleItemId) -> Option<SymbolKind> {
+    Some(match item {
+        ModuleItemId::Constant(_) => SymbolKind::CONSTANT,
+        ModuleItemId::Submodule(_) => SymbolKind::MODULE,
+        ModuleItemId::Use(_) => return None,
+        ModuleItemId::FreeFunction(_) | ModuleItemId::ExternFunction(_) => SymbolKind::FUNCTION,
+        ModuleItemId::Struct(_) | ModuleItemId::ExternType(_) => SymbolKind::STRUCT,
+        ModuleItemId::Enum(_) => SymbolKind::ENUM,
+        ModuleItemId::TypeAlias(_) => SymbolKind::TYPE_PARAMETER,
+        ModuleItemId::Trait(_) => SymbolKind::INTERFACE,
+        ModuleItemId::Impl(_) | ModuleItemId::ImplAlias(_) => SymbolKind::OBJECT,
+    })
+}
+
+/// Returns the names and the definitions of the functions of a trait or an impl item.
+fn item_functions(db: &RootDatabase, item: &ModuleItemId) -> Vec<(String, SyntaxStablePtrId)> {
+    match item {
+        ModuleItemId::Trait(trait_id) => db
+            .trait_functions(*trait_id)
+            .unwrap_or_default()
+            .iter()
+            .map(|(name, function_id)| (name.to_string(), function_id.untyped_stable_ptr(db)))
+            .collect(),
+        ModuleItemId::Impl(impl_def_id) => db
+            .impl_functions(*impl_def_id)
+            .unwrap_or_default()
+            .iter()
+            .map(|(name, function_id)| (name.to_string(), function_id.untyped_stable_ptr(db)))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns a score of a fuzzy match of the query in a name (lower is better), or None if the name
+/// does not match. A name matches if it contains the characters of the query in order, ignoring
+/// case, and the score is the number of gaps before and between the matched characters.
+fn fuzzy_match_score(query: &str, name: &str) -> Option<usize> {
+    let mut name_chars = name.chars().map(|c| c.to_ascii_lowercase());
+    let mut gaps = 0;
+    for query_char in query.chars().map(|c| c.to_ascii_lowercase()) {
+        if name_chars.next()? != query_char {
+            gaps += 1;
+            name_chars.find(|name_char| *name_char == query_char)?;
+        }
+    }
+    Some(gaps)
+}
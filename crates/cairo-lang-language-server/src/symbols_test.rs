@@ -0,0 +1,107 @@
+use indoc::indoc;
+use lsp::{DocumentSymbol, SymbolKind};
+
+use super::{document_symbols, fuzzy_match_score, workspace_symbols};
+use crate::get_uri;
+use crate::test_utils::{range_of, setup_test_crate};
+
+const CONTENT: &str = indoc! {"
+    mod inner {
+        fn helper() {}
+    }
+    #[derive(Drop)]
+    struct Point {
+        x: felt252,
+    }
+    enum Shape {
+        Dot,
+    }
+    trait Area<T> {
+        fn area(self: @T) -> u32;
+    }
+    impl ShapeArea of Area<Shape> {
+        fn area(self: @Shape) -> u32 {
+            0
+        }
+    }
+    const ZERO: felt252 = 0;
+"};
+
+/// Returns the kinds and names of the symbols and their children, indented by their depths.
+fn symbol_lines(symbols: &[DocumentSymbol], depth: usize) -> Vec<String> {
+    symbols
+        .iter()
+        .flat_map(|symbol| {
+            let line = format!("{}{:?} {}", "  ".repeat(depth), symbol.kind, symbol.name);
+            let children = symbol_lines(symbol.children.as_deref().unwrap_or_default(), depth + 1);
+            [line].into_iter().chain(children)
+        })
+        .collect()
+}
+
+#[test]
+fn test_document_symbols() {
+    let (db, files) = setup_test_crate(&[("lib.cairo", CONTENT)]);
+    let symbols = document_symbols(&db, files[0]);
+    // The impl generated for `Drop` is not in the file.
+    assert_eq!(
+        symbol_lines(&symbols, 0),
+        vec![
+            "Module inner",
+            "  Function helper",
+            "Struct Point",
+            "  Field x",
+            "Enum Shape",
+            "  EnumMember Dot",
+            "Interface Area",
+            "  Method area",
+            "Object ShapeArea",
+            "  Method area",
+            "Constant ZERO",
+        ]
+    );
+    let point = &symbols[1];
+    assert_eq!(point.selection_range, range_of(CONTENT, "Point", 0));
+    // The range of a definition includes its attributes.
+    assert_eq!(point.range.start, range_of(CONTENT, "#[derive(Drop)]", 0).start);
+    assert_eq!(point.range.end, range_of(CONTENT, "}", 2).end);
+}
+
+#[test]
+fn test_workspace_symbols() {
+    let (db, files) = setup_test_crate(&[("lib.cairo", CONTENT)]);
+    let uri = get_uri(&db, files[0]);
+    let symbols: Vec<_> = workspace_symbols(&db, "area")
+        .into_iter()
+        .filter(|symbol| symbol.location.uri == uri)
+        .map(|symbol| (symbol.name, symbol.kind, symbol.container_name.unwrap()))
+        .collect();
+    // The closest matches come first, and the shortest names among them.
+    assert_eq!(
+        symbols,
+        vec![
+            ("Area".into(), SymbolKind::INTERFACE, "test".into()),
+            ("area".into(), SymbolKind::METHOD, "test".into()),
+            ("area".into(), SymbolKind::METHOD, "test".into()),
+            ("ShapeArea".into(), SymbolKind::OBJECT, "test".into()),
+        ]
+    );
+    // The symbols of all the crates are searched.
+    let symbols = workspace_symbols(&db, "hlp");
+    assert!(symbols.len() > 1);
+    assert_eq!(symbols[0].name, "helper");
+    assert_eq!(symbols[0].container_name, Some("test::inner".into()));
+}
+
+#[test]
+fn test_fuzzy_match_score() {
+    assert_eq!(fuzzy_match_score("", "abc"), Some(0));
+    assert_eq!(fuzzy_match_score("abc", "abc"), Some(0));
+    assert_eq!(fuzzy_match_score("ABc", "aBC"), Some(0));
+    assert_eq!(fuzzy_match_score("ab", "abc"), Some(0));
+    assert_eq!(fuzzy_match_score("ac", "abc"), Some(1));
+    assert_eq!(fuzzy_match_score("bc", "abc"), Some(1));
+    assert_eq!(fuzzy_match_score("sa", "ShapeArea"), Some(1));
+    assert_eq!(fuzzy_match_score("ca", "abc"), None);
+    assert_eq!(fuzzy_match_score("abcd", "abc"), None);
+}
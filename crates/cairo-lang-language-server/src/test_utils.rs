@@ -0,0 +1,59 @@
+//! Utilities for testing the features of the server on crates which are not on the disk.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
+use cairo_lang_filesystem::db::{FilesGroup, FilesGroupEx};
+use cairo_lang_filesystem::ids::{CrateLongId, Directory, FileId};
+use cairo_lang_starknet::inline_macros::selector::SelectorMacro;
+use cairo_lang_starknet::plugin::StarkNetPlugin;
+use cairo_lang_test_plugin::TestPlugin;
+use lsp::{Position, Range};
+
+/// The root directory of the test crate. Nothing is read from it - the contents of the files of
+/// the crate are overridden, as the contents of the documents opened in the editor are.
+const TEST_CRATE_ROOT: &str = "/test_crate/src";
+
+/// Sets up a database with the plugins of the server and a crate named `test`, whose files have the
+/// given paths (relative to the root of the crate) and contents. The first file should be
+/// `lib.cairo`. Returns the database and the ids of the files, in the given order.
+pub fn setup_test_crate(files: &[(&str, &str)]) -> (RootDatabase, Vec<FileId>) {
+    let mut db = RootDatabase::builder()
+        .detect_corelib()
+        .with_cfg(CfgSet::from_iter([Cfg::name("test")]))
+        .with_macro_plugin(Arc::new(StarkNetPlugin::default()))
+        .with_macro_plugin(Arc::new(TestPlugin::default()))
+        .with_inline_macro_plugin(SelectorMacro::NAME, Arc::new(SelectorMacro))
+        .build()
+        .unwrap();
+    let root = PathBuf::from(TEST_CRATE_ROOT);
+    let crate_id = db.intern_crate(CrateLongId::Real("test".into()));
+    db.set_crate_root(crate_id, Some(Directory::Real(root.clone())));
+    let file_ids = files
+        .iter()
+        .map(|(path, content)| {
+            let file_id = FileId::new(&db, root.join(path));
+            db.override_file_content(file_id, Some(Arc::new(content.to_string())));
+            file_id
+        })
+        .collect();
+    (db, file_ids)
+}
+
+/// Returns the position of the `nth` occurrence (counting from 0) of `pattern` in `content`.
+pub fn position_of(content: &str, pattern: &str, nth: usize) -> Position {
+    let (offset, _) = content.match_indices(pattern).nth(nth).unwrap();
+    let before = &content[..offset];
+    let line = before.matches('\n').count();
+    let character = before.len() - before.rfind('\n').map_or(0, |newline| newline + 1);
+    Position { line: line as u32, character: character as u32 }
+}
+
+/// Returns the range of the `nth` occurrence (counting from 0) of `pattern` in `content`, which is
+/// expected not to span several lines.
+pub fn range_of(content: &str, pattern: &str, nth: usize) -> Range {
+    let start = position_of(content, pattern, nth);
+    Range { start, end: Position { character: start.character + pattern.len() as u32, ..start } }
+}
@@ -0,0 +1,74 @@
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use indent::indent_by;
+use indoc::formatdoc;
+use itertools::Itertools;
+
+use super::{unsupported_for_extern_diagnostic, DeriveInfo, DeriveResult};
+use crate::plugins::derive::TypeVariantInfo;
+
+/// Adds derive result for the `Debug` trait.
+pub fn handle_debug(info: &DeriveInfo, stable_ptr: SyntaxStablePtrId, result: &mut DeriveResult) {
+    let full_typename = info.full_typename();
+    let ty = &info.name;
+    let body = indent_by(
+        8,
+        match &info.specific_info {
+            TypeVariantInfo::Enum(variants) => {
+                formatdoc! {"
+                    match self {{
+                        {}
+                    }};
+                    Result::Ok(())",
+                    indent_by(4,
+                    variants.iter().map(|variant| if variant.ty == "()" {
+                        format!(
+                            "{ty}::{variant}(_) => {{ f.buffer.append(@\"{ty}::{variant}\"); }},",
+                            variant=variant.name,
+                        )
+                    } else {
+                        formatdoc!{"
+                            {ty}::{variant}(x) => {{
+                                f.buffer.append(@\"{ty}::{variant}(\");
+                                core::fmt::Debug::fmt(x, ref f)?;
+                                f.buffer.append(@\")\");
+                            }},",
+                            variant=variant.name,
+                        }
+                    }).join("\n"))
+                }
+            }
+            TypeVariantInfo::Struct(members) => {
+                if members.is_empty() {
+                    format!("f.buffer.append(@\"{ty}\");\nResult::Ok(())")
+                } else {
+                    formatdoc! {"
+                        {}
+                        f.buffer.append(@\" }}\");
+                        Result::Ok(())",
+                        members.iter().enumerate().map(|(idx, member)| {
+                            let prefix = if idx == 0 { format!("{ty} {{ ") } else { ", ".into() };
+                            formatdoc! {"
+                                f.buffer.append(@\"{prefix}{member}: \");
+                                core::fmt::Debug::fmt(self.{member}, ref f)?;",
+                                member=member.name,
+                            }
+                        }).join("\n")
+                    }
+                }
+            }
+            TypeVariantInfo::Extern => {
+                return result.diagnostics.push(unsupported_for_extern_diagnostic(stable_ptr));
+            }
+        },
+    );
+    let generics = info
+        .generics
+        .format_generics_with_trait(|t| vec![format!("impl {t}Debug: core::fmt::Debug<{t}>")]);
+    result.impls.push(formatdoc! {"
+        impl {ty}Debug{generics} of core::fmt::Debug::<{full_typename}> {{
+            fn fmt(self: @{full_typename}, ref f: core::fmt::Formatter) -> Result<(), core::fmt::Error> {{
+                {body}
+            }}
+        }}
+    "});
+}
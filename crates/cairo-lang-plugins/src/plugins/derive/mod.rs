@@ -13,6 +13,7 @@ use itertools::{chain, Itertools};
 use smol_str::SmolStr;
 
 mod clone;
+mod debug;
 mod default;
 mod destruct;
 mod hash;
@@ -65,7 +66,7 @@ impl MacroPlugin for DerivePlugin {
 /// Information on struct members or enum variants.
 struct MemberInfo {
     name: SmolStr,
-    _ty: String,
+    ty: String,
     attributes: AttributeList,
 }
 
@@ -192,7 +193,7 @@ fn extract_members(db: &dyn SyntaxGroup, members: MemberList) -> Vec<MemberInfo>
         .into_iter()
         .map(|member| MemberInfo {
             name: member.name(db).text(db),
-            _ty: member.type_clause(db).ty(db).as_syntax_node().get_text_without_trivia(db),
+            ty: member.type_clause(db).ty(db).as_syntax_node().get_text_without_trivia(db),
             attributes: member.attributes(db),
         })
         .collect()
@@ -205,7 +206,7 @@ fn extract_variants(db: &dyn SyntaxGroup, variants: VariantList) -> Vec<MemberIn
         .into_iter()
         .map(|variant| MemberInfo {
             name: variant.name(db).text(db),
-            _ty: match variant.type_clause(db) {
+            ty: match variant.type_clause(db) {
                 ast::OptionTypeClause::Empty(_) => "()".to_string(),
                 ast::OptionTypeClause::TypeClause(t) => {
                     t.ty(db).as_syntax_node().get_text_without_trivia(db)
@@ -261,6 +262,7 @@ fn generate_derive_code_for_type(db: &dyn SyntaxGroup, info: DeriveInfo) -> Plug
             match derived.as_str() {
                 "Copy" | "Drop" => result.impls.push(get_empty_impl(&derived, &info)),
                 "Clone" => clone::handle_clone(&info, stable_ptr, &mut result),
+                "Debug" => debug::handle_debug(&info, stable_ptr, &mut result),
                 "Default" => default::handle_default(db, &info, stable_ptr, &mut result),
                 "Destruct" => destruct::handle_destruct(&info, stable_ptr, &mut result),
                 "Hash" => hash::handle_hash(&info, stable_ptr, &mut result),
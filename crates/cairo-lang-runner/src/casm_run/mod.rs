@@ -1,7 +1,9 @@
 use std::any::Any;
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
 use std::ops::{Deref, Shl};
+use std::time::Instant;
 
 use ark_ff::fields::{Fp256, MontBackend, MontConfig};
 use ark_ff::{BigInteger, Field, PrimeField};
@@ -31,14 +33,16 @@ use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::cairo_runner::{CairoRunner, ResourceTracker, RunResources};
 use cairo_vm::vm::vm_core::VirtualMachine;
 use dict_manager::DictManagerExecScope;
+use itertools::Itertools;
 use num_bigint::BigUint;
 use num_integer::Integer;
 use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use {ark_secp256k1 as secp256k1, ark_secp256r1 as secp256r1};
 
 use self::dict_manager::DictSquashExecScope;
 use crate::short_string::as_cairo_short_string;
-use crate::{Arg, RunResultValue, SierraCasmRunner};
+use crate::{Arg, RunLimits, RunResultValue, RunTrace, RunnerError, SierraCasmRunner};
 
 #[cfg(test)]
 mod test;
@@ -96,6 +100,45 @@ pub struct CairoHintProcessor<'a> {
     pub starknet_state: StarknetState,
     // Maintains the resources of the run.
     pub run_resources: RunResources,
+    /// The time at which the run is stopped, if it has a timeout.
+    pub deadline: Option<RunDeadline>,
+    /// The step-level traces of the contract calls made by the run, if the runner collects
+    /// traces.
+    pub call_traces: Vec<RunTrace>,
+}
+
+/// The time at which a run is stopped. The time is checked once every
+/// [RunDeadline::CHECK_INTERVAL] steps, as reading the clock on every step slows the run down.
+pub struct RunDeadline {
+    /// The time at which the run is stopped.
+    pub instant: Instant,
+    /// The number of steps until the next check of the time.
+    steps_until_check: usize,
+    /// Whether the time was found to have passed by the last check.
+    expired: bool,
+}
+impl RunDeadline {
+    /// The number of steps between checks of the time.
+    const CHECK_INTERVAL: usize = 1000;
+
+    pub fn new(instant: Instant) -> Self {
+        Self { instant, steps_until_check: 0, expired: false }
+    }
+
+    /// Returns whether the deadline has passed.
+    pub fn passed(&self) -> bool {
+        Instant::now() >= self.instant
+    }
+
+    /// Registers a step of the run, checking the time if it is due.
+    fn consume_step(&mut self) {
+        if self.steps_until_check == 0 {
+            self.expired = self.passed();
+            self.steps_until_check = Self::CHECK_INTERVAL;
+        } else {
+            self.steps_until_check -= 1;
+        }
+    }
 }
 
 pub fn cell_ref_to_relocatable(cell_ref: &CellRef, vm: &VirtualMachine) -> Relocatable {
@@ -122,17 +165,62 @@ type L2ToL1Message = (Felt252, Vec<Felt252>);
 
 /// Execution scope for starknet related data.
 /// All values will be 0 and by default if not setup by the test.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct StarknetState {
     /// The values of addresses in the simulated storage per contract.
+    #[serde(serialize_with = "serialize_storage", deserialize_with = "deserialize_storage")]
     storage: HashMap<Felt252, HashMap<Felt252, Felt252>>,
     /// A mapping from contract address to class hash.
     #[allow(dead_code)]
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
     deployed_contracts: HashMap<Felt252, Felt252>,
     /// A mapping from contract address to logs.
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
     logs: HashMap<Felt252, ContractLogs>,
+    /// The events collected by each event spy, by the order of their emission, along with the
+    /// address of the emitting contract. The id of a spy is its index in the vector.
+    event_spies: Vec<VecDeque<(Felt252, Log)>>,
     /// The simulated execution info.
     exec_info: ExecutionInfo,
+    /// A mapping from block number to block hash, as returned by the `get_block_hash` syscall.
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
+    block_hash: HashMap<u64, Felt252>,
+    /// A mapping from contract address to the execution info values cheated for it.
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
+    cheats: HashMap<Felt252, ContractCheats>,
+    /// A mapping from account contract address to its nonce, overriding the nonce of the
+    /// transaction for it.
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
+    nonces: HashMap<Felt252, Felt252>,
+    /// A mapping from contract address and entry point selector to the mocked result of calling
+    /// it.
+    #[serde(
+        serialize_with = "serialize_hash_map_vec",
+        deserialize_with = "deserialize_hash_map_vec"
+    )]
+    mocked_calls: HashMap<(Felt252, Felt252), MockedCall>,
+    /// The states saved by the `snapshot` cheatcode. The id of a snapshot is its index in the
+    /// vector.
+    #[serde(skip)]
+    snapshots: Vec<StarknetState>,
+    /// Whether the run is of a contract entry point, rather than of the test itself.
+    #[serde(skip)]
+    in_entry_point: bool,
     next_id: Felt252,
 }
 impl StarknetState {
@@ -161,10 +249,100 @@ impl StarknetState {
         self.exec_info.contract_address = old_contract_address;
         self.exec_info.caller_address = old_caller_address;
     }
+
+    /// Returns the execution info as seen by the currently executing contract, with the values
+    /// cheated for it applied.
+    fn contract_exec_info(&self) -> ExecutionInfo {
+        let mut exec_info = self.exec_info.clone();
+        if let Some(cheats) = self.cheats.get(&exec_info.contract_address) {
+            if let Some(cheated) = &cheats.caller_address {
+                exec_info.caller_address = cheated.value.clone();
+            }
+            if let Some(cheated) = &cheats.block_number {
+                exec_info.block_info.block_number = cheated.value.clone();
+            }
+            if let Some(cheated) = &cheats.block_timestamp {
+                exec_info.block_info.block_timestamp = cheated.value.clone();
+            }
+        }
+        exec_info
+    }
+
+    /// Registers a call into the given contract for the values cheated for it.
+    fn consume_cheated_call(&mut self, contract_address: &Felt252) {
+        if let Some(cheats) = self.cheats.get_mut(contract_address) {
+            cheats.consume_call();
+        }
+    }
+
+    /// Saves a snapshot of the state, returning its id.
+    fn snapshot(&mut self) -> usize {
+        let snapshot = StarknetState { snapshots: vec![], ..self.clone() };
+        self.snapshots.push(snapshot);
+        self.snapshots.len() - 1
+    }
+
+    /// Reverts the state to the snapshot with the given id. Returns false if there is no such
+    /// snapshot. The snapshot is kept, so the state may be reverted to it again.
+    fn revert_to_snapshot(&mut self, snapshot_id: usize) -> bool {
+        let Some(snapshot) = self.snapshots.get(snapshot_id).cloned() else {
+            return false;
+        };
+        let snapshots = std::mem::take(&mut self.snapshots);
+        *self = StarknetState { snapshots, in_entry_point: self.in_entry_point, ..snapshot };
+        true
+    }
+
+    /// Returns the nonce of the given account contract, which is the nonce of the transaction
+    /// unless one was set for the account.
+    fn nonce(&self, account_contract_address: &Felt252) -> Felt252 {
+        self.nonces.get(account_contract_address).unwrap_or(&self.exec_info.tx_info.nonce).clone()
+    }
+}
+
+/// The mocked result of a contract call.
+#[derive(Clone, Serialize, Deserialize)]
+enum MockedCall {
+    /// The call succeeds, returning the given data.
+    Success(Vec<Felt252>),
+    /// The call fails, with the given revert reason.
+    Revert(Vec<Felt252>),
+}
+
+/// An execution info value cheated for a specific contract.
+#[derive(Clone, Serialize, Deserialize)]
+struct CheatedValue {
+    /// The value seen by the contract.
+    value: Felt252,
+    /// The number of further calls into the contract the value applies to, or `None` if it
+    /// applies until stopped. Library calls made by the contract are counted as calls into it.
+    remaining_calls: Option<usize>,
+}
+
+/// The execution info values cheated for a specific contract.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct ContractCheats {
+    caller_address: Option<CheatedValue>,
+    block_number: Option<CheatedValue>,
+    block_timestamp: Option<CheatedValue>,
+}
+impl ContractCheats {
+    /// Registers a call into the contract, dropping the cheated values that no longer apply.
+    fn consume_call(&mut self) {
+        for cheat in [&mut self.caller_address, &mut self.block_number, &mut self.block_timestamp] {
+            if let Some(CheatedValue { remaining_calls: Some(remaining_calls), .. }) = cheat {
+                if *remaining_calls == 0 {
+                    *cheat = None;
+                } else {
+                    *remaining_calls -= 1;
+                }
+            }
+        }
+    }
 }
 
 /// Object storing logs for a contract.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct ContractLogs {
     /// Events.
     events: VecDeque<Log>,
@@ -173,7 +351,7 @@ struct ContractLogs {
 }
 
 /// Copy of the cairo `ExecutionInfo` struct.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct ExecutionInfo {
     block_info: BlockInfo,
     tx_info: TxInfo,
@@ -182,7 +360,7 @@ struct ExecutionInfo {
 }
 
 /// Copy of the cairo `BlockInfo` struct.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct BlockInfo {
     block_number: Felt252,
     block_timestamp: Felt252,
@@ -190,7 +368,7 @@ struct BlockInfo {
 }
 
 /// Copy of the cairo `TxInfo` struct.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct TxInfo {
     version: Felt252,
     account_contract_address: Felt252,
@@ -200,6 +378,45 @@ struct TxInfo {
     chain_id: Felt252,
     nonce: Felt252,
 }
+/// Serializes a `HashMap` as a vector of its entries, as its keys may not be strings. The entries
+/// are sorted by their keys, so the serialization is deterministic.
+fn serialize_hash_map_vec<K: Serialize + Ord, V: Serialize, S: Serializer>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(map.iter().sorted_by_key(|(key, _)| *key))
+}
+
+/// Deserializes a `HashMap` serialized by [serialize_hash_map_vec].
+fn deserialize_hash_map_vec<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+where
+    K: Deserialize<'de> + Hash + Eq,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<(K, V)>::deserialize(deserializer)?.into_iter().collect())
+}
+
+/// Serializes the storage of all contracts as nested vectors of entries, sorted by their keys.
+fn serialize_storage<S: Serializer>(
+    storage: &HashMap<Felt252, HashMap<Felt252, Felt252>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(storage.iter().sorted_by_key(|(contract, _)| *contract).map(
+        |(contract, values)| (contract, values.iter().sorted_by_key(|(key, _)| *key).collect_vec()),
+    ))
+}
+
+/// Deserializes the storage of all contracts serialized by [serialize_storage].
+fn deserialize_storage<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<Felt252, HashMap<Felt252, Felt252>>, D::Error> {
+    Ok(Vec::<(Felt252, Vec<(Felt252, Felt252)>)>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(contract, values)| (contract, values.into_iter().collect()))
+        .collect())
+}
+
 /// Execution scope for constant memory allocation.
 struct MemoryExecScope {
     /// The first free address in the segment.
@@ -344,6 +561,10 @@ mod gas_costs {
     pub const STORAGE_WRITE: usize = 50 * STEP;
 }
 
+/// The number of most recent blocks whose hashes are not yet available to the `get_block_hash`
+/// syscall.
+const STORED_BLOCK_HASH_BUFFER: u64 = 10;
+
 /// Deducts gas from the given gas counter, or fails the syscall if there is not enough gas.
 macro_rules! deduct_gas {
     ($gas:ident, $amount:ident) => {
@@ -435,13 +656,24 @@ impl HintProcessorLogic for CairoHintProcessor<'_> {
     }
 }
 
+impl CairoHintProcessor<'_> {
+    /// Returns whether the run has a timeout, and it has passed.
+    pub fn deadline_passed(&self) -> bool {
+        self.deadline.as_ref().is_some_and(RunDeadline::passed)
+    }
+}
+
 impl ResourceTracker for CairoHintProcessor<'_> {
     fn consumed(&self) -> bool {
         self.run_resources.consumed()
+            || self.deadline.as_ref().is_some_and(|deadline| deadline.expired)
     }
 
     fn consume_step(&mut self) {
-        self.run_resources.consume_step()
+        self.run_resources.consume_step();
+        if let Some(deadline) = &mut self.deadline {
+            deadline.consume_step();
+        }
     }
 
     fn get_n_steps(&self) -> Option<usize> {
@@ -815,13 +1047,25 @@ impl<'a> CairoHintProcessor<'a> {
     fn get_block_hash(
         &mut self,
         gas_counter: &mut usize,
-        _block_number: u64,
+        block_number: u64,
     ) -> Result<SyscallResult, HintError> {
         deduct_gas!(gas_counter, GET_BLOCK_HASH);
-        // TODO(Arni, 28/5/2023): Replace the temporary return value with the required value.
-        //      One design suggestion - to preform a storage read. Have an arbitrary, hardcoded
-        //      (For example, addr=1) contain the mapping from block number to block hash.
-        fail_syscall!(b"GET_BLOCK_HASH_UNIMPLEMENTED");
+        // Only the hashes of blocks that are at least `STORED_BLOCK_HASH_BUFFER` blocks older than
+        // the current block are available, as in the sequencer.
+        let Some(current_block_number) =
+            self.starknet_state.contract_exec_info().block_info.block_number.to_u64()
+        else {
+            fail_syscall!(b"Invalid block number");
+        };
+        if current_block_number < STORED_BLOCK_HASH_BUFFER
+            || block_number > current_block_number - STORED_BLOCK_HASH_BUFFER
+        {
+            fail_syscall!(b"Block number out of range");
+        }
+        let Some(block_hash) = self.starknet_state.block_hash.get(&block_number) else {
+            fail_syscall!(b"Block number out of range");
+        };
+        Ok(SyscallResult::Success(vec![block_hash.clone().into()]))
     }
 
     /// Executes the `get_execution_info_syscall` syscall.
@@ -831,7 +1075,7 @@ impl<'a> CairoHintProcessor<'a> {
         vm: &mut dyn VMWrapper,
     ) -> Result<SyscallResult, HintError> {
         deduct_gas!(gas_counter, GET_EXECUTION_INFO);
-        let exec_info = &self.starknet_state.exec_info;
+        let exec_info = &self.starknet_state.contract_exec_info();
         let block_info = &exec_info.block_info;
         let tx_info = &exec_info.tx_info;
         let mut res_segment = MemBuffer::new_segment(vm);
@@ -846,7 +1090,7 @@ impl<'a> CairoHintProcessor<'a> {
         res_segment.write(signature_end)?;
         res_segment.write(tx_info.transaction_hash.clone())?;
         res_segment.write(tx_info.chain_id.clone())?;
-        res_segment.write(tx_info.nonce.clone())?;
+        res_segment.write(self.starknet_state.nonce(&tx_info.account_contract_address))?;
         let block_info_ptr = res_segment.ptr;
         res_segment.write(block_info.block_number.clone())?;
         res_segment.write(block_info.block_timestamp.clone())?;
@@ -868,6 +1112,9 @@ impl<'a> CairoHintProcessor<'a> {
     ) -> Result<SyscallResult, HintError> {
         deduct_gas!(gas_counter, EMIT_EVENT);
         let contract = self.starknet_state.exec_info.contract_address.clone();
+        for spied_events in self.starknet_state.event_spies.iter_mut() {
+            spied_events.push_back((contract.clone(), (keys.clone(), data.clone())));
+        }
         self.starknet_state.logs.entry(contract).or_default().events.push_back((keys, data));
         Ok(SyscallResult::Success(vec![]))
     }
@@ -957,6 +1204,22 @@ impl<'a> CairoHintProcessor<'a> {
     ) -> Result<SyscallResult, HintError> {
         deduct_gas!(gas_counter, CALL_CONTRACT);
 
+        // Mocked calls take precedence over the deployed contracts.
+        if let Some(mocked_call) =
+            self.starknet_state.mocked_calls.get(&(contract_address.clone(), selector.clone()))
+        {
+            return match mocked_call.clone() {
+                MockedCall::Success(ret_data) => {
+                    let (res_data_start, res_data_end) =
+                        segment_with_data(vm, ret_data.into_iter())?;
+                    Ok(SyscallResult::Success(vec![res_data_start.into(), res_data_end.into()]))
+                }
+                MockedCall::Revert(mut revert_reason) => {
+                    fail_syscall!(revert_reason, b"ENTRYPOINT_FAILED");
+                }
+            };
+        }
+
         // Get the class hash of the contract.
         let Some(class_hash) = self.starknet_state.deployed_contracts.get(&contract_address) else {
             fail_syscall!(b"CONTRACT_NOT_DEPLOYED");
@@ -974,6 +1237,7 @@ impl<'a> CairoHintProcessor<'a> {
             fail_syscall!(b"ENTRYPOINT_NOT_FOUND");
         };
 
+        self.starknet_state.consume_cheated_call(&contract_address);
         let old_addrs = self.starknet_state.open_caller_context((
             contract_address.clone(),
             self.starknet_state.exec_info.contract_address.clone(),
@@ -1011,6 +1275,13 @@ impl<'a> CairoHintProcessor<'a> {
         let Some(entry_point) = contract_info.externals.get(&selector) else {
             fail_syscall!(b"ENTRYPOINT_NOT_FOUND");
         };
+        // The library code runs in the context of the calling contract. A library call made by the
+        // test is counted as a call into that contract, while one made by an entry point is a part
+        // of the call into it, which was already counted.
+        if !self.starknet_state.in_entry_point {
+            let contract_address = self.starknet_state.exec_info.contract_address.clone();
+            self.starknet_state.consume_cheated_call(&contract_address);
+        }
         match self.call_entry_point(gas_counter, runner, entry_point, calldata, vm) {
             Ok((res_data_start, res_data_end)) => {
                 Ok(SyscallResult::Success(vec![res_data_start.into(), res_data_end.into()]))
@@ -1046,19 +1317,43 @@ impl<'a> CairoHintProcessor<'a> {
             .sierra_program_registry
             .get_function(entry_point)
             .expect("Entrypoint exists, but not found.");
-        let mut res = runner
-            .run_function_with_starknet_context(
-                function,
-                &[Arg::Array(calldata)],
-                Some(*gas_counter),
-                self.starknet_state.clone(),
-            )
-            .expect("Internal runner error.");
+        // The called entry point shares the remaining resources of the calling run.
+        let limits = RunLimits {
+            max_steps: self.run_resources.get_n_steps(),
+            timeout: self
+                .deadline
+                .as_ref()
+                .map(|deadline| deadline.instant.saturating_duration_since(Instant::now())),
+        };
+        let in_entry_point = self.starknet_state.in_entry_point;
+        let res = runner.run_function_with_starknet_context(
+            function,
+            &[Arg::Array(calldata)],
+            Some(*gas_counter),
+            StarknetState { in_entry_point: true, ..self.starknet_state.clone() },
+            limits,
+        );
+        let mut res = match res {
+            Ok(res) => res,
+            Err(RunnerError::StepLimitExceeded { .. } | RunnerError::TimeoutExceeded { .. }) => {
+                // Stops the calling run as well - the limits are reported by its runner.
+                if limits.max_steps.is_some() {
+                    self.run_resources = RunResources::new(0);
+                }
+                return Err(vec![]);
+            }
+            Err(err) => panic!("Internal runner error: {err}"),
+        };
+        // The remaining resources of the called run account for the contract calls it made.
+        self.run_resources = std::mem::take(&mut res.run_resources);
 
         *gas_counter = res.gas_counter.unwrap().to_usize().unwrap();
+        self.call_traces.extend(res.trace.take());
+        self.call_traces.append(&mut res.call_traces);
         match res.value {
             RunResultValue::Success(value) => {
-                self.starknet_state = std::mem::take(&mut res.starknet_state);
+                self.starknet_state =
+                    StarknetState { in_entry_point, ..std::mem::take(&mut res.starknet_state) };
                 Ok(segment_with_data(vm, read_array_result_as_vec(&res.memory, &value).into_iter())
                     .expect("failed to allocate segment"))
             }
@@ -1136,9 +1431,121 @@ impl<'a> CairoHintProcessor<'a> {
             "set_nonce" => {
                 self.starknet_state.exec_info.tx_info.nonce = as_single_input(inputs)?;
             }
+            "set_account_nonce" => {
+                let [account_contract_address, nonce] = extract_cheatcode_inputs(selector, inputs)?;
+                self.starknet_state.nonces.insert(account_contract_address, nonce);
+            }
+            "start_prank" => {
+                let (target, cheated) = extract_cheated_value(selector, inputs)?;
+                self.starknet_state.cheats.entry(target).or_default().caller_address =
+                    Some(cheated);
+            }
+            "stop_prank" => {
+                if let Some(cheats) = self.starknet_state.cheats.get_mut(&as_single_input(inputs)?)
+                {
+                    cheats.caller_address = None;
+                }
+            }
+            "start_roll" => {
+                let (target, cheated) = extract_cheated_value(selector, inputs)?;
+                self.starknet_state.cheats.entry(target).or_default().block_number = Some(cheated);
+            }
+            "stop_roll" => {
+                if let Some(cheats) = self.starknet_state.cheats.get_mut(&as_single_input(inputs)?)
+                {
+                    cheats.block_number = None;
+                }
+            }
+            "start_warp" => {
+                let (target, cheated) = extract_cheated_value(selector, inputs)?;
+                self.starknet_state.cheats.entry(target).or_default().block_timestamp =
+                    Some(cheated);
+            }
+            "stop_warp" => {
+                if let Some(cheats) = self.starknet_state.cheats.get_mut(&as_single_input(inputs)?)
+                {
+                    cheats.block_timestamp = None;
+                }
+            }
             "set_signature" => {
                 self.starknet_state.exec_info.tx_info.signature = inputs;
             }
+            "set_block_hash" => {
+                let [block_number, block_hash] = extract_cheatcode_inputs(selector, inputs)?;
+                let block_number = block_number.to_u64().ok_or_else(|| {
+                    HintError::CustomHint(Box::from(format!(
+                        "`{selector}` cheatcode invalid args: block number must fit in a u64",
+                    )))
+                })?;
+                self.starknet_state.block_hash.insert(block_number, block_hash);
+            }
+            "load" => {
+                let [contract_address, key, size] = extract_cheatcode_inputs(selector, inputs)?;
+                let size = size.to_usize().ok_or_else(|| {
+                    HintError::CustomHint(Box::from(format!(
+                        "`{selector}` cheatcode invalid args: size must fit in a usize",
+                    )))
+                })?;
+                let contract_storage = self.starknet_state.storage.get(&contract_address);
+                for offset in 0..size {
+                    let addr = key.clone() + Felt252::from(offset);
+                    res_segment.write(
+                        contract_storage
+                            .and_then(|contract_storage| contract_storage.get(&addr))
+                            .cloned()
+                            .unwrap_or_else(|| Felt252::from(0)),
+                    )?;
+                }
+            }
+            "store" => {
+                let ([contract_address, key], values) =
+                    extract_cheatcode_inputs_with_span(selector, inputs)?;
+                let contract_storage =
+                    self.starknet_state.storage.entry(contract_address).or_default();
+                for (offset, value) in values.into_iter().enumerate() {
+                    contract_storage.insert(key.clone() + Felt252::from(offset), value);
+                }
+            }
+            "mock_call" => {
+                let ([contract_address, entry_point_selector], ret_data) =
+                    extract_cheatcode_inputs_with_span(selector, inputs)?;
+                self.starknet_state.mocked_calls.insert(
+                    (contract_address, entry_point_selector),
+                    MockedCall::Success(ret_data),
+                );
+            }
+            "mock_call_revert" => {
+                let ([contract_address, entry_point_selector], revert_reason) =
+                    extract_cheatcode_inputs_with_span(selector, inputs)?;
+                self.starknet_state.mocked_calls.insert(
+                    (contract_address, entry_point_selector),
+                    MockedCall::Revert(revert_reason),
+                );
+            }
+            "stop_mock_call" => {
+                let [contract_address, entry_point_selector] =
+                    extract_cheatcode_inputs(selector, inputs)?;
+                self.starknet_state.mocked_calls.remove(&(contract_address, entry_point_selector));
+            }
+            "snapshot" => {
+                if !inputs.is_empty() {
+                    Err(HintError::CustomHint(Box::from(format!(
+                        "`{selector}` cheatcode invalid args: pass span of an empty array",
+                    ))))?
+                }
+                res_segment.write(self.starknet_state.snapshot())?;
+            }
+            "revert_to_snapshot" => {
+                let snapshot_id = as_single_input(inputs)?;
+                if !snapshot_id
+                    .to_usize()
+                    .is_some_and(|snapshot_id| self.starknet_state.revert_to_snapshot(snapshot_id))
+                {
+                    Err(HintError::CustomHint(Box::from(format!(
+                        "`{selector}` cheatcode invalid args: unknown snapshot id {snapshot_id}",
+                    ))))?
+                }
+            }
             "pop_log" => {
                 let contract_logs = self.starknet_state.logs.get_mut(&as_single_input(inputs)?);
                 if let Some((keys, data)) =
@@ -1150,6 +1557,33 @@ impl<'a> CairoHintProcessor<'a> {
                     res_segment.write_data(data.iter())?;
                 }
             }
+            "spy_events" => {
+                if !inputs.is_empty() {
+                    Err(HintError::CustomHint(Box::from(format!(
+                        "`{selector}` cheatcode invalid args: pass span of an empty array",
+                    ))))?
+                }
+                res_segment.write(self.starknet_state.event_spies.len())?;
+                self.starknet_state.event_spies.push(VecDeque::new());
+            }
+            "pop_spied_event" => {
+                let spy_id = as_single_input(inputs)?;
+                let spied_events = spy_id
+                    .to_usize()
+                    .and_then(|spy_id| self.starknet_state.event_spies.get_mut(spy_id))
+                    .ok_or_else(|| {
+                        HintError::CustomHint(Box::from(format!(
+                            "`{selector}` cheatcode invalid args: unknown spy id {spy_id}",
+                        )))
+                    })?;
+                if let Some((contract_address, (keys, data))) = spied_events.pop_front() {
+                    res_segment.write(contract_address)?;
+                    res_segment.write(keys.len())?;
+                    res_segment.write_data(keys.iter())?;
+                    res_segment.write(data.len())?;
+                    res_segment.write_data(data.iter())?;
+                }
+            }
             "pop_l2_to_l1_message" => {
                 let contract_logs = self.starknet_state.logs.get_mut(&as_single_input(inputs)?);
                 if let Some((to_address, payload)) = contract_logs
@@ -1171,6 +1605,64 @@ impl<'a> CairoHintProcessor<'a> {
     }
 }
 
+/// Extracts exactly `N` inputs of the cheatcode with the given selector.
+fn extract_cheatcode_inputs<const N: usize>(
+    selector: &str,
+    inputs: Vec<Felt252>,
+) -> Result<[Felt252; N], HintError> {
+    <[Felt252; N]>::try_from(inputs).map_err(|_| {
+        HintError::CustomHint(Box::from(format!(
+            "`{selector}` cheatcode invalid args: pass span of an array with exactly {N} elements",
+        )))
+    })
+}
+
+/// Extracts exactly `N` inputs of the cheatcode with the given selector, followed by a serialized
+/// span of values.
+fn extract_cheatcode_inputs_with_span<const N: usize>(
+    selector: &str,
+    mut inputs: Vec<Felt252>,
+) -> Result<([Felt252; N], Vec<Felt252>), HintError> {
+    let invalid_args = || {
+        HintError::CustomHint(Box::from(format!(
+            "`{selector}` cheatcode invalid args: pass span of an array with {N} elements \
+             followed by a serialized span",
+        )))
+    };
+    if inputs.len() <= N {
+        return Err(invalid_args());
+    }
+    let mut values = inputs.split_off(N);
+    if values.remove(0).to_usize() != Some(values.len()) {
+        return Err(invalid_args());
+    }
+    Ok((extract_cheatcode_inputs(selector, inputs)?, values))
+}
+
+/// Extracts the target contract address and the cheated value of a scoped cheatcode.
+/// The inputs are the target and the value, optionally followed by the number of calls into the
+/// target the value applies to.
+fn extract_cheated_value(
+    selector: &str,
+    inputs: Vec<Felt252>,
+) -> Result<(Felt252, CheatedValue), HintError> {
+    let (target, value, remaining_calls) = match <[Felt252; 3]>::try_from(inputs) {
+        Ok([target, value, calls]) => {
+            let calls = calls.to_usize().ok_or_else(|| {
+                HintError::CustomHint(Box::from(format!(
+                    "`{selector}` cheatcode invalid args: the number of calls is out of range."
+                )))
+            })?;
+            (target, value, Some(calls))
+        }
+        Err(inputs) => {
+            let [target, value] = extract_cheatcode_inputs(selector, inputs)?;
+            (target, value, None)
+        }
+    };
+    Ok((target, CheatedValue { value, remaining_calls }))
+}
+
 /// Executes the `keccak_syscall` syscall.
 fn keccak(gas_counter: &mut usize, data: Vec<Felt252>) -> Result<SyscallResult, HintError> {
     deduct_gas!(gas_counter, KECCAK);
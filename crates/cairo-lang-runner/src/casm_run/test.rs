@@ -5,6 +5,7 @@ use cairo_vm::vm::runners::cairo_runner::RunResources;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use itertools::Itertools;
 use num_traits::ToPrimitive;
+use serde_json::json;
 use test_case::test_case;
 
 use crate::casm_run::run_function;
@@ -110,6 +111,8 @@ fn test_runner(function: CasmContext, n_returns: usize, expected: &[i128]) {
         string_to_hint,
         starknet_state: StarknetState::default(),
         run_resources: RunResources::default(),
+        deadline: None,
+        call_traces: vec![],
     };
 
     let (cells, ap) = run_function(
@@ -143,6 +146,8 @@ fn test_allocate_segment() {
         string_to_hint,
         starknet_state: StarknetState::default(),
         run_resources: RunResources::default(),
+        deadline: None,
+        call_traces: vec![],
     };
 
     let (memory, ap) = run_function(
@@ -161,3 +166,72 @@ fn test_allocate_segment() {
         .expect("Number not in index range.");
     assert_eq!(memory[ptr], Some(Felt252::from(1337)));
 }
+
+/// Returns the JSON of a serialized felt252.
+fn felt252_json(value: u32) -> serde_json::Value {
+    let digits = if value == 0 { vec![] } else { vec![value] };
+    json!({ "value": { "val": digits } })
+}
+
+#[test]
+fn test_starknet_state_serialization() {
+    let mut state = StarknetState::default();
+    for contract in [2, 1] {
+        let contract_storage = state.storage.entry(Felt252::from(contract)).or_default();
+        for key in [11, 10] {
+            contract_storage.insert(Felt252::from(key), Felt252::from(contract * key));
+        }
+        state.deployed_contracts.insert(Felt252::from(contract), Felt252::from(contract + 3));
+    }
+    state
+        .logs
+        .entry(Felt252::from(1))
+        .or_default()
+        .events
+        .push_back((vec![Felt252::from(5)], vec![Felt252::from(6), Felt252::from(7)]));
+    state.exec_info.block_info.block_number = Felt252::from(8);
+    state.block_hash.insert(9, Felt252::from(10));
+    state.block_hash.insert(7, Felt252::from(11));
+    state.get_next_id();
+
+    let serialized = serde_json::to_string(&state).unwrap();
+    // The entries of the maps are sorted by their keys.
+    let felt = felt252_json;
+    let expected = json!({
+        "storage": [
+            [felt(1), [[felt(10), felt(10)], [felt(11), felt(11)]]],
+            [felt(2), [[felt(10), felt(20)], [felt(11), felt(22)]]],
+        ],
+        "deployed_contracts": [[felt(1), felt(4)], [felt(2), felt(5)]],
+        "logs": [
+            [felt(1), { "events": [[[felt(5)], [felt(6), felt(7)]]], "l2_to_l1_messages": [] }],
+        ],
+        "event_spies": [],
+        "exec_info": {
+            "block_info": {
+                "block_number": felt(8),
+                "block_timestamp": felt(0),
+                "sequencer_address": felt(0),
+            },
+            "tx_info": {
+                "version": felt(0),
+                "account_contract_address": felt(0),
+                "max_fee": felt(0),
+                "signature": [],
+                "transaction_hash": felt(0),
+                "chain_id": felt(0),
+                "nonce": felt(0),
+            },
+            "caller_address": felt(0),
+            "contract_address": felt(0),
+        },
+        "block_hash": [[7, felt(11)], [9, felt(10)]],
+        "cheats": [],
+        "nonces": [],
+        "mocked_calls": [],
+        "next_id": felt(1),
+    });
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+    let deserialized: StarknetState = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+}
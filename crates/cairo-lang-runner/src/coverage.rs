@@ -0,0 +1,120 @@
+//! Statement-level coverage of runs, based on their step-level traces.
+
+use cairo_lang_sierra::program::{BranchTarget, Statement, StatementIdx};
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
+
+use crate::{RunTrace, SierraCasmRunner};
+
+/// The number of executions of the statements of a Sierra program, and of the branches of its
+/// branching statements. Statements with no CASM code are not included, as they are never
+/// executed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatementCoverage {
+    /// The number of executions of each statement, by statement index.
+    pub statements: OrderedHashMap<usize, usize>,
+    /// The number of times each branch of each branching statement was taken, by statement
+    /// index.
+    pub branches: OrderedHashMap<usize, Vec<usize>>,
+}
+impl StatementCoverage {
+    /// Adds the executions of `other`, a coverage of the same program, to this coverage.
+    pub fn merge(&mut self, other: &StatementCoverage) {
+        for (idx, hits) in other.statements.iter() {
+            *self.statements.entry(*idx).or_default() += hits;
+        }
+        for (idx, branch_hits) in other.branches.iter() {
+            let self_branch_hits =
+                self.branches.entry(*idx).or_insert_with(|| vec![0; branch_hits.len()]);
+            for (self_hits, hits) in self_branch_hits.iter_mut().zip(branch_hits) {
+                *self_hits += hits;
+            }
+        }
+    }
+}
+
+impl SierraCasmRunner {
+    /// Returns the coverage of no runs - with all the statements and branches never executed.
+    pub fn empty_coverage(&self) -> StatementCoverage {
+        let mut coverage = StatementCoverage::default();
+        for (idx, statement) in self.sierra_program.statements.iter().enumerate() {
+            if self.statement_code_size(idx) == 0 {
+                continue;
+            }
+            coverage.statements.insert(idx, 0);
+            if let Statement::Invocation(invocation) = statement {
+                if invocation.branches.len() > 1 {
+                    coverage.branches.insert(idx, vec![0; invocation.branches.len()]);
+                }
+            }
+        }
+        coverage
+    }
+
+    /// Adds the statements and branches executed by a run to `coverage`, given the trace of the
+    /// run.
+    pub fn add_coverage(&self, coverage: &mut StatementCoverage, trace: &RunTrace) {
+        let statement_infos = &self.casm_program.debug_info.sierra_statement_info;
+        // The statements with code, by the offset of their code.
+        let statement_at_offset: UnorderedHashMap<usize, usize> = (0..statement_infos.len())
+            .filter(|idx| self.statement_code_size(*idx) > 0)
+            .map(|idx| (statement_infos[idx].code_offset, idx))
+            .collect();
+        // The last executed branching statement, whose taken branch is not known yet.
+        let mut pending_branch = None;
+        for entry in &trace.entries {
+            let Some(code_offset) = entry.pc.checked_sub(trace.program_start) else { continue };
+            // Only the first instruction of a statement marks its execution.
+            let Some(idx) = statement_at_offset.get(&code_offset) else { continue };
+            *coverage.statements.entry(*idx).or_default() += 1;
+            if let Some(branching_idx) = pending_branch.take() {
+                let targets = self.branch_targets(branching_idx);
+                if let Some(branch) = targets.iter().position(|target| target == idx) {
+                    let branch_hits = coverage
+                        .branches
+                        .entry(branching_idx)
+                        .or_insert_with(|| vec![0; targets.len()]);
+                    branch_hits[branch] += 1;
+                }
+            }
+            if let Statement::Invocation(invocation) = &self.sierra_program.statements[*idx] {
+                if invocation.branches.len() > 1 {
+                    pending_branch = Some(*idx);
+                }
+            }
+        }
+    }
+
+    /// Returns the statements with code that each of the branches of the statement at `idx`
+    /// leads to.
+    fn branch_targets(&self, idx: usize) -> Vec<usize> {
+        let Statement::Invocation(invocation) = &self.sierra_program.statements[idx] else {
+            return vec![];
+        };
+        invocation
+            .branches
+            .iter()
+            .map(|branch| {
+                let mut target = match branch.target {
+                    BranchTarget::Fallthrough => idx + 1,
+                    BranchTarget::Statement(StatementIdx(target)) => target,
+                };
+                // Statements with no code are skipped.
+                while target < self.sierra_program.statements.len()
+                    && self.statement_code_size(target) == 0
+                {
+                    target += 1;
+                }
+                target
+            })
+            .collect()
+    }
+
+    /// Returns the size of the CASM code of the statement at `idx`.
+    fn statement_code_size(&self, idx: usize) -> usize {
+        let statement_infos = &self.casm_program.debug_info.sierra_statement_info;
+        let end =
+            statement_infos.get(idx + 1).map_or(self.casm_program_size(), |info| info.code_offset);
+        end - statement_infos[idx].code_offset
+    }
+}
@@ -1,10 +1,12 @@
 //! Basic runner for running a Sierra program on the vm.
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use cairo_felt::Felt252;
 use cairo_lang_casm::hints::Hint;
 use cairo_lang_casm::instructions::Instruction;
 use cairo_lang_casm::{casm, casm_extend};
+use cairo_lang_sierra::extensions::array::ArrayType;
 use cairo_lang_sierra::extensions::bitwise::BitwiseType;
 use cairo_lang_sierra::extensions::core::{CoreLibfunc, CoreType};
 use cairo_lang_sierra::extensions::ec::EcOpType;
@@ -15,6 +17,7 @@ use cairo_lang_sierra::extensions::poseidon::PoseidonType;
 use cairo_lang_sierra::extensions::range_check::RangeCheckType;
 use cairo_lang_sierra::extensions::segment_arena::SegmentArenaType;
 use cairo_lang_sierra::extensions::starknet::syscalls::SystemType;
+use cairo_lang_sierra::extensions::structure::StructType;
 use cairo_lang_sierra::extensions::{ConcreteType, NamedType};
 use cairo_lang_sierra::program::{Function, GenericArg};
 use cairo_lang_sierra::program_registry::{ProgramRegistry, ProgramRegistryError};
@@ -32,16 +35,27 @@ use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_vm::hint_processor::hint_processor_definition::HintProcessor;
 use cairo_vm::serde::deserialize_program::{BuiltinName, HintParams};
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::cairo_runner::RunResources;
 use cairo_vm::vm::vm_core::VirtualMachine;
 use casm_run::hint_to_hint_params;
-pub use casm_run::{CairoHintProcessor, StarknetState};
+pub use casm_run::{CairoHintProcessor, RunDeadline, StarknetState};
+pub use coverage::StatementCoverage;
 use itertools::chain;
 use num_traits::ToPrimitive;
+pub use profiling::{FunctionProfile, ProfilingInfo, ResourceUsage};
 use thiserror::Error;
+pub use trace::{
+    collect_statement_locations, RunTrace, SierraTraceEntry, StatementLocations, TraceEntry,
+};
+pub use values::{collect_enum_variant_names, EnumVariantNames, Value};
 
 pub mod casm_run;
+mod coverage;
+mod profiling;
 pub mod short_string;
+mod trace;
+mod values;
 
 #[derive(Debug, Error)]
 pub enum RunnerError {
@@ -57,6 +71,8 @@ pub enum RunnerError {
     MissingFunction { suffix: String },
     #[error("Function expects arguments of size {expected} and received {actual} instead.")]
     ArgumentsSizeMismatch { expected: usize, actual: usize },
+    #[error("Parameter #{index} of type `{ty}` expects {expected}.")]
+    ArgumentTypeMismatch { index: usize, ty: String, expected: String },
     #[error(transparent)]
     ProgramRegistryError(#[from] Box<ProgramRegistryError>),
     #[error(transparent)]
@@ -65,6 +81,19 @@ pub enum RunnerError {
     ApChangeError(#[from] ApChangeError),
     #[error(transparent)]
     CairoRunError(#[from] Box<CairoRunError>),
+    #[error("Run exceeded the step limit of {max_steps} steps.")]
+    StepLimitExceeded { max_steps: usize },
+    #[error("Run exceeded the timeout of {timeout:?}.")]
+    TimeoutExceeded { timeout: Duration },
+}
+
+/// Limits on the resources of a run. A run exceeding them is stopped with an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunLimits {
+    /// The maximal number of steps of the run, including the steps of the contract calls it makes.
+    pub max_steps: Option<usize>,
+    /// The maximal wall-clock duration of the run.
+    pub timeout: Option<Duration>,
 }
 
 /// The full result of a run with Starknet state.
@@ -73,6 +102,15 @@ pub struct RunResultStarknet {
     pub memory: Vec<Option<Felt252>>,
     pub value: RunResultValue,
     pub starknet_state: StarknetState,
+    /// The number of steps of the run.
+    pub steps: usize,
+    /// The resources left at the end of the run, after the steps of the run and of the contract
+    /// calls it made.
+    pub run_resources: RunResources,
+    pub trace: Option<RunTrace>,
+    /// The step-level traces of the contract calls made by the run (including nested calls), if
+    /// the runner collects traces.
+    pub call_traces: Vec<RunTrace>,
 }
 
 /// The full result of a run.
@@ -81,6 +119,10 @@ pub struct RunResult {
     pub gas_counter: Option<Felt252>,
     pub memory: Vec<Option<Felt252>>,
     pub value: RunResultValue,
+    /// The number of steps of the run.
+    pub steps: usize,
+    /// The step-level trace of the run, if the runner collects traces.
+    pub trace: Option<RunTrace>,
 }
 
 /// The ran function return value.
@@ -154,6 +196,8 @@ pub struct SierraCasmRunner {
     #[allow(dead_code)]
     // Mapping from class_hash to contract info.
     starknet_contracts_info: OrderedHashMap<Felt252, ContractInfo>,
+    /// Whether to collect the step-level trace of runs.
+    collect_trace: bool,
 }
 impl SierraCasmRunner {
     pub fn new(
@@ -180,9 +224,16 @@ impl SierraCasmRunner {
             type_sizes,
             casm_program,
             starknet_contracts_info,
+            collect_trace: false,
         })
     }
 
+    /// Sets whether to collect the step-level trace of runs. Not collected by default.
+    pub fn with_trace_collection(mut self, collect_trace: bool) -> Self {
+        self.collect_trace = collect_trace;
+        self
+    }
+
     /// Runs the vm starting from a function in the context of a given starknet state.
     pub fn run_function_with_starknet_context(
         &self,
@@ -190,6 +241,7 @@ impl SierraCasmRunner {
         args: &[Arg],
         available_gas: Option<usize>,
         starknet_state: StarknetState,
+        limits: RunLimits,
     ) -> Result<RunResultStarknet, RunnerError> {
         let initial_gas = self.get_initial_available_gas(func, available_gas)?;
         let (entry_code, builtins) = self.create_entry_code(func, args, initial_gas)?;
@@ -201,23 +253,47 @@ impl SierraCasmRunner {
             runner: Some(self),
             starknet_state,
             string_to_hint,
-            run_resources: RunResources::default(),
+            run_resources: limits.max_steps.map(RunResources::new).unwrap_or_default(),
+            deadline: limits.timeout.map(|timeout| RunDeadline::new(Instant::now() + timeout)),
+            call_traces: vec![],
         };
-        self.run_function(func, &mut hint_processor, hints_dict, instructions, builtins).map(|v| {
-            RunResultStarknet {
+        let result =
+            self.run_function(func, &mut hint_processor, hints_dict, instructions, builtins);
+        match result {
+            Ok(v) => Ok(RunResultStarknet {
                 gas_counter: v.gas_counter,
                 memory: v.memory,
                 value: v.value,
                 starknet_state: hint_processor.starknet_state,
+                steps: v.steps,
+                run_resources: hint_processor.run_resources,
+                trace: v.trace,
+                call_traces: hint_processor.call_traces,
+            }),
+            // The run was stopped before its end, since it ran out of its time or steps.
+            Err(RunnerError::CairoRunError(err))
+                if matches!(
+                    *err,
+                    CairoRunError::VirtualMachine(VirtualMachineError::UnfinishedExecution)
+                ) =>
+            {
+                Err(match (limits.timeout, hint_processor.deadline_passed()) {
+                    (Some(timeout), true) => RunnerError::TimeoutExceeded { timeout },
+                    _ => {
+                        RunnerError::StepLimitExceeded { max_steps: limits.max_steps.unwrap_or(0) }
+                    }
+                })
             }
-        })
+            Err(err) => Err(err),
+        }
     }
 
     /// Runs the vm starting from a function with custom hint processor. Function may have
     /// implicits, but no other ref params. The cost of the function is deducted from
     /// available_gas before the execution begins.
     ///
-    /// Allows injecting Cairo VirtualMachine
+    /// Allows injecting Cairo VirtualMachine. The instructions are expected to be the entry code of
+    /// the run, followed by the CASM code of the program and the code footer.
     pub fn run_function_with_vm<'a, Instructions>(
         &self,
         func: &Function,
@@ -230,6 +306,9 @@ impl SierraCasmRunner {
     where
         Instructions: Iterator<Item = &'a Instruction> + Clone,
     {
+        let code_size: usize = instructions.clone().map(|inst| inst.body.op_size()).sum();
+        let footer_size: usize =
+            self.create_code_footer().iter().map(|inst| inst.body.op_size()).sum();
         let (cells, ap) = casm_run::run_function(
             vm,
             instructions,
@@ -266,13 +345,7 @@ impl SierraCasmRunner {
                 assert!(values.is_empty());
                 false
             } else {
-                *generic_ty != RangeCheckType::ID
-                    && *generic_ty != BitwiseType::ID
-                    && *generic_ty != EcOpType::ID
-                    && *generic_ty != PedersenType::ID
-                    && *generic_ty != PoseidonType::ID
-                    && *generic_ty != SystemType::ID
-                    && *generic_ty != SegmentArenaType::ID
+                !self.is_implicit_type(ty)
             }
         });
         assert!(results_data.len() <= 1);
@@ -283,7 +356,18 @@ impl SierraCasmRunner {
             let [(ty, values)] = <[_; 1]>::try_from(results_data).ok().unwrap();
             self.handle_main_return_value(ty, values, &cells)?
         };
-        Ok(RunResult { gas_counter, memory: cells, value })
+        // The trace of the VM is always enabled.
+        let vm_trace = vm.get_relocated_trace().unwrap();
+        let steps = vm_trace.len();
+        let trace = self.collect_trace.then(|| RunTrace {
+            entries: vm_trace
+                .iter()
+                .map(|entry| TraceEntry { pc: entry.pc, ap: entry.ap, fp: entry.fp })
+                .collect(),
+            // The code segment is relocated to start at address 1.
+            program_start: 1 + code_size - self.casm_program_size() - footer_size,
+        });
+        Ok(RunResult { gas_counter, memory: cells, value, steps, trace })
     }
 
     /// Runs the vm starting from a function with custom hint processor. Function may have
@@ -311,37 +395,90 @@ impl SierraCasmRunner {
         values: Vec<Felt252>,
         cells: &[Option<Felt252>],
     ) -> Result<RunResultValue, RunnerError> {
-        let info = self.get_info(&ty);
-        let long_id = &info.long_id;
-        Ok(
-            if long_id.generic_id == EnumType::ID
-                && matches!(&long_id.generic_args[0], GenericArg::UserType(ut)
-                if ut.debug_name.as_ref().unwrap().starts_with("core::panics::PanicResult::"))
-            {
-                // The function includes a panic wrapper.
-                if values[0] != Felt252::from(0) {
-                    // The run resulted in a panic, returning the error data.
-                    let err_data_start = values[values.len() - 2].to_usize().unwrap();
-                    let err_data_end = values[values.len() - 1].to_usize().unwrap();
-                    RunResultValue::Panic(
-                        cells[err_data_start..err_data_end]
-                            .iter()
-                            .cloned()
-                            .map(|cell| cell.unwrap())
-                            .collect(),
-                    )
-                } else {
-                    // The run resulted successfully, returning the inner value.
-                    let inner_ty = extract_matches!(&long_id.generic_args[1], GenericArg::Type);
-                    let inner_ty_size = self.type_sizes[inner_ty] as usize;
-                    let skip_size = values.len() - inner_ty_size;
-                    RunResultValue::Success(values.into_iter().skip(skip_size).collect())
-                }
+        Ok(if let Some(inner_ty) = self.panic_result_inner_type(&ty) {
+            // The function includes a panic wrapper.
+            if values[0] != Felt252::from(0) {
+                // The run resulted in a panic, returning the error data.
+                let err_data_start = values[values.len() - 2].to_usize().unwrap();
+                let err_data_end = values[values.len() - 1].to_usize().unwrap();
+                RunResultValue::Panic(
+                    cells[err_data_start..err_data_end]
+                        .iter()
+                        .cloned()
+                        .map(|cell| cell.unwrap())
+                        .collect(),
+                )
             } else {
-                // No panic wrap - so always successful.
-                RunResultValue::Success(values)
-            },
-        )
+                // The run resulted successfully, returning the inner value.
+                let inner_ty_size = self.type_sizes[inner_ty] as usize;
+                let skip_size = values.len() - inner_ty_size;
+                RunResultValue::Success(values.into_iter().skip(skip_size).collect())
+            }
+        } else {
+            // No panic wrap - so always successful.
+            RunResultValue::Success(values)
+        })
+    }
+
+    /// Returns the inner type of `ty` if it is a panic wrapper (`PanicResult`), or None otherwise.
+    fn panic_result_inner_type(
+        &self,
+        ty: &cairo_lang_sierra::ids::ConcreteTypeId,
+    ) -> Option<&cairo_lang_sierra::ids::ConcreteTypeId> {
+        let long_id = &self.get_info(ty).long_id;
+        if long_id.generic_id == EnumType::ID
+            && matches!(&long_id.generic_args[0], GenericArg::UserType(ut)
+                if ut.debug_name.as_ref().unwrap().starts_with("core::panics::PanicResult::"))
+        {
+            Some(extract_matches!(&long_id.generic_args[1], GenericArg::Type))
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether `ty` is an implicit type, i.e. a builtin or a system type.
+    fn is_implicit_type(&self, ty: &cairo_lang_sierra::ids::ConcreteTypeId) -> bool {
+        let generic_ty = &self.get_info(ty).long_id.generic_id;
+        *generic_ty == GasBuiltinType::ID
+            || *generic_ty == RangeCheckType::ID
+            || *generic_ty == BitwiseType::ID
+            || *generic_ty == EcOpType::ID
+            || *generic_ty == PedersenType::ID
+            || *generic_ty == PoseidonType::ID
+            || *generic_ty == SystemType::ID
+            || *generic_ty == SegmentArenaType::ID
+    }
+
+    /// Returns whether `ty` is an array or a span, which are given by `Arg::Array` arguments.
+    fn is_array_type(&self, ty: &cairo_lang_sierra::ids::ConcreteTypeId) -> bool {
+        let long_id = &self.get_info(ty).long_id;
+        long_id.generic_id == ArrayType::ID
+            || (long_id.generic_id == StructType::ID
+                && matches!(&long_id.generic_args[0], GenericArg::UserType(ut)
+                    if ut.debug_name.as_ref().is_some_and(|name| name.starts_with("core::array::Span::"))))
+    }
+
+    /// Returns the type of the value returned by `func` on success, ignoring implicits and the
+    /// panic wrapper, or None if it returns no such value.
+    pub fn find_return_value_type<'a>(
+        &'a self,
+        func: &'a Function,
+    ) -> Option<&'a cairo_lang_sierra::ids::ConcreteTypeId> {
+        let ty = func.signature.ret_types.iter().find(|ty| !self.is_implicit_type(ty))?;
+        let Some(inner_ty) = self.panic_result_inner_type(ty) else {
+            return Some(ty);
+        };
+        // The panic wrapper holds the returned value wrapped in a single member tuple.
+        Some(extract_matches!(&self.get_info(inner_ty).long_id.generic_args[1], GenericArg::Type))
+    }
+
+    /// Returns the types of the non-implicit parameters of `func`, which are given by the `args`
+    /// of its runs.
+    pub fn find_arg_types<'a>(
+        &'a self,
+        func: &'a Function,
+    ) -> impl Iterator<Item = &'a cairo_lang_sierra::ids::ConcreteTypeId> + 'a {
+        func.signature.param_types.iter().filter(|ty| !self.is_implicit_type(ty))
     }
 
     /// Returns the final values and type of all `func`s returning variables.
@@ -373,7 +510,8 @@ impl SierraCasmRunner {
             .ok_or_else(|| RunnerError::MissingFunction { suffix: name_suffix.to_owned() })
     }
 
-    fn get_info(
+    /// Returns the type info of `ty`.
+    pub fn get_info(
         &self,
         ty: &cairo_lang_sierra::ids::ConcreteTypeId,
     ) -> &cairo_lang_sierra::extensions::types::TypeInfo {
@@ -388,7 +526,9 @@ impl SierraCasmRunner {
         args: &[Arg],
         initial_gas: usize,
     ) -> Result<(Vec<Instruction>, Vec<BuiltinName>), RunnerError> {
-        let mut arg_iter = args.iter().peekable();
+        let mut arg_iter = args.iter();
+        // The index of the current non-implicit parameter, for reporting mismatching arguments.
+        let mut param_index = 0;
         let mut expected_arguments_size = 0;
         let mut ctx = casm! {};
         // The builtins in the formatting expected by the runner.
@@ -426,6 +566,8 @@ impl SierraCasmRunner {
             }
             ap_offset += (1 + values.len()) as i16;
         }
+        // The vecs are popped when passed to the function, which is in the order of the args.
+        vecs.reverse();
         let after_vecs_offset = ap_offset;
         if func
             .signature
@@ -469,20 +611,32 @@ impl SierraCasmRunner {
                 casm_extend! {ctx,
                     [ap + 0] = [ap + offset] + 3, ap++;
                 }
-            } else if let Some(Arg::Array(_)) = arg_iter.peek() {
-                let values = extract_matches!(arg_iter.next().unwrap(), Arg::Array);
-                let offset = -ap_offset + vecs.pop().unwrap();
-                expected_arguments_size += 1;
-                casm_extend! {ctx,
-                    [ap + 0] = [ap + (offset)], ap++;
-                    [ap + 0] = [ap - 1] + (values.len()), ap++;
-                }
             } else {
-                let arg_size = ty_size;
-                expected_arguments_size += arg_size as usize;
-                for _ in 0..arg_size {
-                    if let Some(value) = arg_iter.next() {
-                        let value = extract_matches!(value, Arg::Value);
+                let index = param_index;
+                param_index += 1;
+                let mismatch = |expected: String| RunnerError::ArgumentTypeMismatch {
+                    index,
+                    ty: ty.to_string(),
+                    expected,
+                };
+                if self.is_array_type(ty) {
+                    let Some(Arg::Array(values)) = arg_iter.next() else {
+                        return Err(mismatch("an array".into()));
+                    };
+                    let offset = -ap_offset + vecs.pop().unwrap();
+                    expected_arguments_size += 1;
+                    casm_extend! {ctx,
+                        [ap + 0] = [ap + (offset)], ap++;
+                        [ap + 0] = [ap - 1] + (values.len()), ap++;
+                    }
+                } else {
+                    let arg_size = ty_size as usize;
+                    expected_arguments_size += arg_size;
+                    for _ in 0..arg_size {
+                        let Some(Arg::Value(value)) = arg_iter.next() else {
+                            let values = if arg_size == 1 { "value" } else { "values" };
+                            return Err(mismatch(format!("{arg_size} {values}")));
+                        };
                         casm_extend! {ctx,
                             [ap + 0] = (value.to_bigint()), ap++;
                         }
@@ -0,0 +1,285 @@
+//! Function-level profiling of runs, based on their step-level traces.
+
+use std::fmt::{self, Display};
+use std::ops::AddAssign;
+
+use cairo_felt::Felt252;
+use cairo_lang_sierra::extensions::bitwise::BitwiseType;
+use cairo_lang_sierra::extensions::ec::EcOpType;
+use cairo_lang_sierra::extensions::pedersen::PedersenType;
+use cairo_lang_sierra::extensions::poseidon::PoseidonType;
+use cairo_lang_sierra::extensions::range_check::RangeCheckType;
+use cairo_lang_sierra::extensions::NamedType;
+use cairo_lang_sierra::ids::{ConcreteTypeId, GenericTypeId};
+use cairo_lang_sierra::program::Function;
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use itertools::Itertools;
+use num_traits::ToPrimitive;
+
+use crate::{RunTrace, SierraCasmRunner};
+
+/// The number of builtins tracked by the profiler.
+const N_PROFILED_BUILTINS: usize = 5;
+
+/// The builtins tracked by the profiler, given by their name, their type and the number of memory
+/// cells used by a single instance of the builtin.
+const PROFILED_BUILTINS: [(&str, GenericTypeId, usize); N_PROFILED_BUILTINS] = [
+    ("range_check", RangeCheckType::ID, 1),
+    ("pedersen", PedersenType::ID, 3),
+    ("poseidon", PoseidonType::ID, 6),
+    ("bitwise", BitwiseType::ID, 5),
+    ("ec_op", EcOpType::ID, 7),
+];
+
+/// The resources used by a part of a run.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResourceUsage {
+    /// The number of steps.
+    pub steps: usize,
+    /// The number of memory cells allocated and never written to.
+    pub memory_holes: usize,
+    /// The number of instances used of each of the builtins, ordered as `range_check`,
+    /// `pedersen`, `poseidon`, `bitwise` and `ec_op`.
+    pub builtins: [usize; N_PROFILED_BUILTINS],
+}
+impl AddAssign<&ResourceUsage> for ResourceUsage {
+    fn add_assign(&mut self, other: &ResourceUsage) {
+        self.steps += other.steps;
+        self.memory_holes += other.memory_holes;
+        for (builtin, other_builtin) in self.builtins.iter_mut().zip(other.builtins) {
+            *builtin += other_builtin;
+        }
+    }
+}
+
+/// The resources used by a Sierra user function during a run.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FunctionProfile {
+    /// The resources used by the function, including the functions it called.
+    pub inclusive: ResourceUsage,
+    /// The resources used by the function itself.
+    pub exclusive: ResourceUsage,
+}
+
+/// The profile of a run.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProfilingInfo {
+    /// The profile of each called Sierra user function, by function name.
+    pub functions: OrderedHashMap<String, FunctionProfile>,
+    /// The number of steps per call stack, where a stack is given by the names of its functions
+    /// separated by `;` (outermost first).
+    pub stacks: OrderedHashMap<String, usize>,
+}
+impl ProfilingInfo {
+    /// Returns the call stacks in the collapsed-stack format used by flamegraph tools.
+    pub fn collapsed_stacks(&self) -> String {
+        self.stacks.iter().map(|(stack, steps)| format!("{stack} {steps}\n")).collect()
+    }
+}
+impl Display for ProfilingInfo {
+    /// Formats the profile as a table, sorted by the inclusive number of steps. The builtins are
+    /// given as `inclusive/exclusive` pairs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self.functions.keys().map(|name| name.len()).max().unwrap_or(0).max(8);
+        write!(
+            f,
+            "{:<name_width$} {:>11} {:>11} {:>11} {:>11}",
+            "function", "steps(incl)", "steps(excl)", "holes(incl)", "holes(excl)"
+        )?;
+        for (builtin_name, _, _) in PROFILED_BUILTINS {
+            write!(f, " {builtin_name:>13}")?;
+        }
+        writeln!(f)?;
+        let sorted_functions = self
+            .functions
+            .iter()
+            .sorted_by_key(|(name, profile)| (std::cmp::Reverse(profile.inclusive.steps), *name));
+        for (name, FunctionProfile { inclusive, exclusive }) in sorted_functions {
+            write!(
+                f,
+                "{name:<name_width$} {:>11} {:>11} {:>11} {:>11}",
+                inclusive.steps, exclusive.steps, inclusive.memory_holes, exclusive.memory_holes
+            )?;
+            for (inclusive, exclusive) in inclusive.builtins.iter().zip(exclusive.builtins) {
+                write!(f, " {:>13}", format!("{inclusive}/{exclusive}"))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A call frame during the profiling of a run.
+struct Frame<'a> {
+    /// The called function, or None for the entry code of the run.
+    function: Option<&'a Function>,
+    /// The frame pointer of the call.
+    fp: usize,
+    /// The call stack of the frame, in the format of `ProfilingInfo::stacks`.
+    stack: String,
+    /// The builtin pointers passed to the function, where relevant.
+    entry_builtins: [Option<usize>; N_PROFILED_BUILTINS],
+    /// The resources used by the function itself.
+    exclusive: ResourceUsage,
+    /// The resources used by the functions called from the frame.
+    children: ResourceUsage,
+}
+
+impl SierraCasmRunner {
+    /// Profiles a run given its trace and its memory. Only the steps of the run's own VM are
+    /// profiled, so calls to other contracts are not included.
+    pub fn profile(&self, trace: &RunTrace, memory: &[Option<Felt252>]) -> ProfilingInfo {
+        let mut info = ProfilingInfo::default();
+        let Some(first_entry) = trace.entries.first() else {
+            return info;
+        };
+        let mut frames = vec![Frame {
+            function: None,
+            fp: first_entry.fp,
+            stack: String::new(),
+            entry_builtins: Default::default(),
+            exclusive: Default::default(),
+            children: Default::default(),
+        }];
+        for (i, entry) in trace.entries.iter().enumerate() {
+            let top_fp = frames.last().unwrap().fp;
+            if entry.fp != top_fp {
+                if frames.iter().any(|frame| frame.fp == entry.fp) {
+                    // Returning from calls - the previous step was a `ret` instruction.
+                    let ret_ap = trace.entries[i - 1].ap;
+                    while frames.last().unwrap().fp != entry.fp {
+                        let frame = frames.pop().unwrap();
+                        self.finish_frame(frame, ret_ap, memory, &mut frames, &mut info);
+                    }
+                } else {
+                    // Calling a function - the current step is the function's first instruction.
+                    let function = self
+                        .sierra_statement_at(entry.pc, trace.program_start)
+                        .and_then(|statement| self.function_of_statement(statement));
+                    let parent = frames.last().unwrap();
+                    // Calls outside of the Sierra program (e.g. to the footer of the run) are
+                    // accounted for as part of the calling function.
+                    let stack = match function {
+                        Some(function) if parent.stack.is_empty() => function.id.to_string(),
+                        Some(function) => format!("{};{}", parent.stack, function.id),
+                        None => parent.stack.clone(),
+                    };
+                    let entry_builtins = function
+                        .map(|function| self.entry_builtins(function, entry.fp, memory))
+                        .unwrap_or_default();
+                    frames.push(Frame {
+                        function,
+                        fp: entry.fp,
+                        stack,
+                        entry_builtins,
+                        exclusive: Default::default(),
+                        children: Default::default(),
+                    });
+                }
+            }
+            let frame = frames.last_mut().unwrap();
+            frame.exclusive.steps += 1;
+            if let Some(next_entry) = trace.entries.get(i + 1) {
+                if next_entry.ap > entry.ap {
+                    frame.exclusive.memory_holes += memory[entry.ap..next_entry.ap]
+                        .iter()
+                        .filter(|cell| cell.is_none())
+                        .count();
+                }
+            }
+            if !frame.stack.is_empty() {
+                *info.stacks.entry(frame.stack.clone()).or_default() += 1;
+            }
+        }
+        // Finish the frames of functions that did not return, e.g. if the run was stopped.
+        let last_ap = trace.entries.last().unwrap().ap;
+        while frames.len() > 1 {
+            let frame = frames.pop().unwrap();
+            self.finish_frame(frame, last_ap, memory, &mut frames, &mut info);
+        }
+        info
+    }
+
+    /// Accounts for a frame that returned with `ret_ap` as its final ap.
+    fn finish_frame(
+        &self,
+        frame: Frame<'_>,
+        ret_ap: usize,
+        memory: &[Option<Felt252>],
+        frames: &mut [Frame<'_>],
+        info: &mut ProfilingInfo,
+    ) {
+        let Some(function) = frame.function else {
+            let parent = frames.last_mut().unwrap();
+            parent.exclusive += &frame.exclusive;
+            parent.children += &frame.children;
+            return;
+        };
+        let mut inclusive = frame.exclusive.clone();
+        inclusive += &frame.children;
+        let mut exclusive = frame.exclusive;
+        let ret_builtins = self.return_builtins(function, ret_ap, memory);
+        for (k, (entry_ptr, ret_ptr)) in frame.entry_builtins.iter().zip(ret_builtins).enumerate() {
+            let (_, _, cells_per_instance) = &PROFILED_BUILTINS[k];
+            if let (Some(entry_ptr), Some(ret_ptr)) = (entry_ptr, ret_ptr) {
+                inclusive.builtins[k] = ret_ptr.saturating_sub(*entry_ptr) / cells_per_instance;
+                exclusive.builtins[k] =
+                    inclusive.builtins[k].saturating_sub(frame.children.builtins[k]);
+            }
+        }
+        frames.last_mut().unwrap().children += &inclusive;
+        // Recursive calls are already included in the outer call of the same function.
+        let is_recursive =
+            frames.iter().any(|outer| outer.function.map(|f| &f.id) == Some(&function.id));
+        let profile = info.functions.entry(function.id.to_string()).or_default();
+        profile.exclusive += &exclusive;
+        if !is_recursive {
+            profile.inclusive += &inclusive;
+        }
+    }
+
+    /// Returns the builtin pointers passed to `function` when called with frame pointer `fp`.
+    fn entry_builtins(
+        &self,
+        function: &Function,
+        fp: usize,
+        memory: &[Option<Felt252>],
+    ) -> [Option<usize>; N_PROFILED_BUILTINS] {
+        let params_size: usize =
+            function.signature.param_types.iter().map(|ty| self.type_sizes[ty] as usize).sum();
+        // The arguments are followed by the return fp and pc.
+        self.builtin_pointers(&function.signature.param_types, fp - 2 - params_size, memory)
+    }
+
+    /// Returns the builtin pointers returned by `function` with final ap `ret_ap`.
+    fn return_builtins(
+        &self,
+        function: &Function,
+        ret_ap: usize,
+        memory: &[Option<Felt252>],
+    ) -> [Option<usize>; N_PROFILED_BUILTINS] {
+        let rets_size: usize =
+            function.signature.ret_types.iter().map(|ty| self.type_sizes[ty] as usize).sum();
+        self.builtin_pointers(&function.signature.ret_types, ret_ap - rets_size, memory)
+    }
+
+    /// Returns the values of the builtin pointers among the values of types `types`, placed in
+    /// memory consecutively from address `start`.
+    fn builtin_pointers(
+        &self,
+        types: &[ConcreteTypeId],
+        start: usize,
+        memory: &[Option<Felt252>],
+    ) -> [Option<usize>; N_PROFILED_BUILTINS] {
+        let mut pointers = [None; N_PROFILED_BUILTINS];
+        let mut address = start;
+        for ty in types {
+            let generic_id = &self.get_info(ty).long_id.generic_id;
+            if let Some(k) = PROFILED_BUILTINS.iter().position(|(_, id, _)| id == generic_id) {
+                pointers[k] = memory[address].as_ref().and_then(|value| value.to_usize());
+            }
+            address += self.type_sizes[ty] as usize;
+        }
+        pointers
+    }
+}
@@ -1,4 +1,5 @@
 use cairo_felt::Felt252;
+use num_traits::ToPrimitive;
 
 /// Converts a bigint representing a felt252 to a Cairo short-string.
 pub fn as_cairo_short_string(value: &Felt252) -> Option<String> {
@@ -15,3 +16,40 @@ pub fn as_cairo_short_string(value: &Felt252) -> Option<String> {
     }
     Some(as_string)
 }
+
+/// The prefix of the panic data of a panic with a ByteArray message, followed by the serialized
+/// ByteArray. Matches `BYTE_ARRAY_MAGIC` of the corelib.
+pub const BYTE_ARRAY_MAGIC: &str =
+    "46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3";
+
+/// The number of bytes in each full word of a ByteArray.
+const BYTES_IN_WORD: usize = 31;
+
+/// Converts the data of a panic with a ByteArray message to the message. Returns None if the data
+/// is not of such a panic, or if the message is not a valid UTF-8 string.
+pub fn as_cairo_byte_array_panic(data: &[Felt252]) -> Option<String> {
+    let [magic, num_words, rest @ ..] = data else { return None };
+    if *magic != Felt252::parse_bytes(BYTE_ARRAY_MAGIC.as_bytes(), 16).unwrap() {
+        return None;
+    }
+    let [words @ .., pending_word, pending_word_len] = rest else { return None };
+    if num_words.to_usize()? != words.len() {
+        return None;
+    }
+    let pending_word_len = pending_word_len.to_usize().filter(|len| *len < BYTES_IN_WORD)?;
+    let mut bytes = vec![];
+    for word in words {
+        bytes.extend(word_bytes(word, BYTES_IN_WORD)?);
+    }
+    bytes.extend(word_bytes(pending_word, pending_word_len)?);
+    String::from_utf8(bytes).ok()
+}
+
+/// Returns the big-endian representation of `word` in exactly `len` bytes, or None if it does not
+/// fit.
+fn word_bytes(word: &Felt252, len: usize) -> Option<Vec<u8>> {
+    let bytes = word.to_bytes_be();
+    let significant = &bytes[bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len())..];
+    let padding = len.checked_sub(significant.len())?;
+    Some([vec![0; padding], significant.to_vec()].concat())
+}
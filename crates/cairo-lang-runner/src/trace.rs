@@ -0,0 +1,131 @@
+//! Step-level traces of runs, and their mapping back to the Sierra program and Cairo code.
+
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::diagnostic_utils::StableLocation;
+use cairo_lang_diagnostics::DiagnosticLocation;
+use cairo_lang_sierra::program::StatementIdx;
+use serde::{Deserialize, Serialize};
+
+use crate::SierraCasmRunner;
+
+/// The registers of the VM before a single step of a run. The addresses are relocated, with the
+/// code starting at address 1.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+}
+
+/// The step-level trace of a run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunTrace {
+    /// The steps of the run, in execution order.
+    pub entries: Vec<TraceEntry>,
+    /// The (relocated) address of the start of the Sierra program's CASM code, following the
+    /// entry code of the run.
+    pub program_start: usize,
+}
+
+/// A trace entry mapped back to the Sierra program and to the Cairo code.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SierraTraceEntry {
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+    /// The index of the Sierra statement being executed, or None if the step is part of the entry
+    /// code or the footer of the run.
+    pub sierra_statement: Option<usize>,
+    /// The Sierra function containing the statement.
+    pub function: Option<String>,
+    /// The location in the Cairo code of the statement, if known.
+    pub location: Option<String>,
+}
+
+/// The locations in the Cairo code of the statements of a Sierra program, by statement index, given
+/// as `path:line:column-line:column` spans. Statements with no known location are mapped to None.
+pub type StatementLocations = Vec<Option<String>>;
+
+/// Renders the locations of the statements of a Sierra program, as found in the program's debug
+/// info, as spans in the Cairo code of `db`.
+pub fn collect_statement_locations(
+    db: &dyn DefsGroup,
+    statements_locations: &[Option<StableLocation>],
+) -> StatementLocations {
+    statements_locations
+        .iter()
+        .map(|location| {
+            let DiagnosticLocation { file_id, span } = location.as_ref()?.diagnostic_location(db);
+            let start = span.start.position_in_file(db.upcast(), file_id)?;
+            let end = span.end.position_in_file(db.upcast(), file_id)?;
+            Some(format!(
+                "{}:{}:{}-{}:{}",
+                file_id.full_path(db.upcast()),
+                start.line + 1,
+                start.col + 1,
+                end.line + 1,
+                end.col + 1
+            ))
+        })
+        .collect()
+}
+
+impl SierraCasmRunner {
+    /// Returns the index of the Sierra statement being executed at `pc`, or None if `pc` is not
+    /// within the Sierra program's CASM code.
+    pub fn sierra_statement_at(&self, pc: usize, program_start: usize) -> Option<StatementIdx> {
+        let code_offset = pc.checked_sub(program_start)?;
+        if code_offset >= self.casm_program_size() {
+            return None;
+        }
+        let statements = &self.casm_program.debug_info.sierra_statement_info;
+        // The last statement starting at or before the offset, skipping statements with no code.
+        let index = statements.partition_point(|info| info.code_offset <= code_offset);
+        Some(StatementIdx(index - 1))
+    }
+
+    /// Returns the Sierra function containing the statement at `statement`.
+    pub fn function_of_statement(
+        &self,
+        statement: StatementIdx,
+    ) -> Option<&cairo_lang_sierra::program::Function> {
+        self.sierra_program
+            .funcs
+            .iter()
+            .filter(|func| func.entry_point.0 <= statement.0)
+            .max_by_key(|func| func.entry_point.0)
+    }
+
+    /// Maps the entries of a trace back to the Sierra program, and to the Cairo code using
+    /// `statement_locations`.
+    pub fn map_trace(
+        &self,
+        trace: &RunTrace,
+        statement_locations: &[Option<String>],
+    ) -> Vec<SierraTraceEntry> {
+        trace
+            .entries
+            .iter()
+            .map(|TraceEntry { pc, ap, fp }| {
+                let statement = self.sierra_statement_at(*pc, trace.program_start);
+                let function =
+                    statement.and_then(|statement| self.function_of_statement(statement));
+                SierraTraceEntry {
+                    pc: *pc,
+                    ap: *ap,
+                    fp: *fp,
+                    sierra_statement: statement.map(|statement| statement.0),
+                    function: function.map(|function| function.id.to_string()),
+                    location: statement.and_then(|statement| {
+                        statement_locations.get(statement.0).cloned().flatten()
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the size of the Sierra program's CASM code.
+    pub(crate) fn casm_program_size(&self) -> usize {
+        self.casm_program.instructions.iter().map(|inst| inst.body.op_size()).sum()
+    }
+}
@@ -0,0 +1,266 @@
+//! Decoding of run results into structured values according to their Sierra types.
+
+use std::fmt::{self, Display};
+
+use cairo_felt::Felt252;
+use cairo_lang_defs::ids::TopLevelLanguageElementId;
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_semantic::corelib::core_crate;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_sierra::extensions::array::ArrayType;
+use cairo_lang_sierra::extensions::boxing::BoxType;
+use cairo_lang_sierra::extensions::enm::EnumType;
+use cairo_lang_sierra::extensions::int::signed::{Sint16Type, Sint32Type, Sint64Type, Sint8Type};
+use cairo_lang_sierra::extensions::int::signed128::Sint128Type;
+use cairo_lang_sierra::extensions::non_zero::NonZeroType;
+use cairo_lang_sierra::extensions::nullable::NullableType;
+use cairo_lang_sierra::extensions::snapshot::SnapshotType;
+use cairo_lang_sierra::extensions::structure::StructType;
+use cairo_lang_sierra::extensions::NamedType;
+use cairo_lang_sierra::ids::{ConcreteTypeId, UserTypeId};
+use cairo_lang_sierra::program::{Function, GenericArg};
+use cairo_lang_utils::extract_matches;
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use itertools::{chain, Itertools};
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use smol_str::SmolStr;
+
+use crate::SierraCasmRunner;
+
+/// Mapping from the full path of an enum (without generic arguments) to the names of its variants.
+pub type EnumVariantNames = OrderedHashMap<String, Vec<SmolStr>>;
+
+/// Collects the variant names of all the enums in the given crates and in the corelib.
+pub fn collect_enum_variant_names(
+    db: &dyn SemanticGroup,
+    crate_ids: &[CrateId],
+) -> EnumVariantNames {
+    let mut enum_variant_names = EnumVariantNames::default();
+    for crate_id in chain!([core_crate(db)], crate_ids.iter().copied()).unique() {
+        for module_id in db.crate_modules(crate_id).iter() {
+            let Ok(enum_ids) = db.module_enums_ids(*module_id) else { continue };
+            for enum_id in enum_ids.iter() {
+                let Ok(variants) = db.enum_variants(*enum_id) else { continue };
+                enum_variant_names
+                    .insert(enum_id.full_path(db.upcast()), variants.keys().cloned().collect());
+            }
+        }
+    }
+    enum_variant_names
+}
+
+/// A value of a run, decoded according to its Sierra type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// A numeric value, such as a felt252, an integer (including u256) or an address.
+    Number(BigInt),
+    /// A boolean.
+    Bool(bool),
+    /// A `ByteArray`, given by its bytes.
+    ByteArray(Vec<u8>),
+    /// A struct, given by its type name and its members.
+    Struct { name: String, members: Vec<Value> },
+    /// A tuple.
+    Tuple(Vec<Value>),
+    /// An enum, given by its type name, its active variant name (or index, if its name is not
+    /// known) and the variant's value.
+    Enum { name: String, variant: String, value: Box<Value> },
+    /// An array or a span.
+    Array(Vec<Value>),
+    /// A null `Nullable`.
+    Null,
+    /// The raw cells of a value of a type with no known structure.
+    Cells(Vec<Felt252>),
+}
+impl Value {
+    /// Returns whether the value is the unit value `()`.
+    fn is_unit(&self) -> bool {
+        matches!(self, Value::Tuple(members) if members.is_empty())
+    }
+}
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(value) => write!(f, "{value}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::ByteArray(bytes) => write!(f, "{:?}", String::from_utf8_lossy(bytes)),
+            Value::Struct { name, members } if members.is_empty() => write!(f, "{name}"),
+            Value::Struct { name, members } => write!(f, "{name}({})", members.iter().join(", ")),
+            Value::Tuple(members) => match members.as_slice() {
+                [member] => write!(f, "({member},)"),
+                _ => write!(f, "({})", members.iter().join(", ")),
+            },
+            Value::Enum { name, variant, value } => {
+                // Omit the generic arguments, as they are implied by the variant's value.
+                let name = name.split("::<").next().unwrap();
+                if value.is_unit() {
+                    write!(f, "{name}::{variant}")
+                } else {
+                    write!(f, "{name}::{variant}({value})")
+                }
+            }
+            Value::Array(elements) => write!(f, "[{}]", elements.iter().join(", ")),
+            Value::Null => write!(f, "null"),
+            Value::Cells(cells) => write!(f, "[{}]", cells.iter().join(", ")),
+        }
+    }
+}
+
+impl SierraCasmRunner {
+    /// Decodes the value returned by a successful run of `func`, given the run's
+    /// `RunResultValue::Success` values and the memory of the run (used for values behind
+    /// pointers, such as array elements).
+    pub fn decode_return_value(
+        &self,
+        func: &Function,
+        values: &[Felt252],
+        memory: &[Option<Felt252>],
+        enum_variant_names: &EnumVariantNames,
+    ) -> Value {
+        let Some(ty) = self.find_return_value_type(func) else {
+            return Value::Tuple(vec![]);
+        };
+        self.decode_value(ty, values, memory, enum_variant_names)
+    }
+
+    /// Decodes a value of type `ty` from its cells `values`, given the memory of the run.
+    pub fn decode_value(
+        &self,
+        ty: &ConcreteTypeId,
+        values: &[Felt252],
+        memory: &[Option<Felt252>],
+        enum_variant_names: &EnumVariantNames,
+    ) -> Value {
+        ValueDecoder { runner: self, memory, enum_variant_names }.decode(ty, &mut values.iter())
+    }
+}
+
+/// Helper for decoding the values of a run.
+struct ValueDecoder<'a> {
+    runner: &'a SierraCasmRunner,
+    memory: &'a [Option<Felt252>],
+    enum_variant_names: &'a EnumVariantNames,
+}
+impl ValueDecoder<'_> {
+    /// Decodes a value of type `ty`, consuming its cells from `values`.
+    fn decode(&self, ty: &ConcreteTypeId, values: &mut std::slice::Iter<'_, Felt252>) -> Value {
+        let long_id = &self.runner.get_info(ty).long_id;
+        let generic_id = &long_id.generic_id;
+        let inner_types = long_id.generic_args.iter().filter_map(|arg| match arg {
+            GenericArg::Type(ty) => Some(ty),
+            _ => None,
+        });
+        if *generic_id == StructType::ID {
+            let name = user_type_name(&long_id.generic_args[0]);
+            if name == "core::integer::u256" {
+                let low = values.next().unwrap().to_biguint();
+                let high = values.next().unwrap().to_biguint();
+                return Value::Number(BigInt::from((high << 128) + low));
+            }
+            if name == "core::byte_array::ByteArray" {
+                return self.decode_byte_array(values);
+            }
+            let members = inner_types.map(|ty| self.decode(ty, values)).collect_vec();
+            if name == "Tuple" {
+                Value::Tuple(members)
+            } else if name.starts_with("core::array::Span::<") {
+                members.into_iter().exactly_one().ok().unwrap()
+            } else {
+                Value::Struct { name, members }
+            }
+        } else if *generic_id == EnumType::ID {
+            let name = user_type_name(&long_id.generic_args[0]);
+            let variants = inner_types.collect_vec();
+            let selector = values.next().unwrap().to_usize().unwrap();
+            let index = if variants.len() <= 2 {
+                selector
+            } else {
+                // See `build_enum_init` in `cairo-lang-sierra-to-casm` for the selector encoding.
+                variants.len() - (selector + 1) / 2
+            };
+            let variant_ty = variants[index];
+            let padding = self.runner.type_sizes[ty] - 1 - self.runner.type_sizes[variant_ty];
+            values.by_ref().take(padding as usize).for_each(drop);
+            let value = self.decode(variant_ty, values);
+            if name == "core::bool" {
+                return Value::Bool(index == 1);
+            }
+            let variant = self
+                .enum_variant_names
+                .get(name.split("::<").next().unwrap())
+                .and_then(|variant_names| variant_names.get(index))
+                .map_or_else(|| index.to_string(), |variant_name| variant_name.to_string());
+            Value::Enum { name, variant, value: Box::new(value) }
+        } else if *generic_id == ArrayType::ID {
+            let element_ty = inner_types.exactly_one().ok().unwrap();
+            let cells = self.read_array_cells(values);
+            let mut cells = cells.iter();
+            let mut elements = vec![];
+            while !cells.as_slice().is_empty() {
+                elements.push(self.decode(element_ty, &mut cells));
+            }
+            Value::Array(elements)
+        } else if *generic_id == SnapshotType::ID || *generic_id == NonZeroType::ID {
+            self.decode(inner_types.exactly_one().ok().unwrap(), values)
+        } else if *generic_id == BoxType::ID || *generic_id == NullableType::ID {
+            let inner_ty = inner_types.exactly_one().ok().unwrap();
+            let address = values.next().unwrap();
+            if address.is_zero() {
+                return Value::Null;
+            }
+            let start = address.to_usize().unwrap();
+            let end = start + self.runner.type_sizes[inner_ty] as usize;
+            self.decode(inner_ty, &mut self.read_memory(start, end).iter())
+        } else if [Sint8Type::ID, Sint16Type::ID, Sint32Type::ID, Sint64Type::ID, Sint128Type::ID]
+            .contains(generic_id)
+        {
+            Value::Number(values.next().unwrap().to_signed_felt())
+        } else {
+            let cells = values.take(self.runner.type_sizes[ty] as usize).cloned().collect_vec();
+            match <[_; 1]>::try_from(cells) {
+                Ok([cell]) => Value::Number(cell.to_bigint()),
+                Err(cells) => Value::Cells(cells),
+            }
+        }
+    }
+
+    /// Decodes a `ByteArray`, consuming its cells from `values`.
+    fn decode_byte_array(&self, values: &mut std::slice::Iter<'_, Felt252>) -> Value {
+        let mut bytes = vec![];
+        for word in self.read_array_cells(values) {
+            bytes.extend(felt252_bytes(&word, 31));
+        }
+        let pending_word = values.next().unwrap();
+        let pending_word_len = values.next().unwrap().to_usize().unwrap();
+        bytes.extend(felt252_bytes(pending_word, pending_word_len));
+        Value::ByteArray(bytes)
+    }
+
+    /// Returns the cells of the array whose start and end pointers are consumed from `values`.
+    fn read_array_cells(&self, values: &mut std::slice::Iter<'_, Felt252>) -> Vec<Felt252> {
+        let start = values.next().unwrap().to_usize().unwrap();
+        let end = values.next().unwrap().to_usize().unwrap();
+        self.read_memory(start, end)
+    }
+
+    /// Returns the values of the memory cells in the range `start..end`.
+    fn read_memory(&self, start: usize, end: usize) -> Vec<Felt252> {
+        self.memory[start..end].iter().map(|cell| cell.clone().unwrap()).collect()
+    }
+}
+
+/// Returns the `len` least significant bytes of `value`, in big-endian order.
+fn felt252_bytes(value: &Felt252, len: usize) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    bytes[bytes.len() - len..].to_vec()
+}
+
+/// Returns the name of a user type given as a generic argument.
+fn user_type_name(arg: &GenericArg) -> String {
+    let UserTypeId { id, debug_name } = extract_matches!(arg, GenericArg::UserType);
+    match debug_name {
+        Some(name) => name.to_string(),
+        None => format!("ut@[{id}]"),
+    }
+}
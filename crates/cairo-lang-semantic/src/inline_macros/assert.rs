@@ -0,0 +1,144 @@
+use cairo_lang_defs::patcher::{PatchBuilder, RewriteNode};
+use cairo_lang_defs::plugin::{
+    InlineMacroExprPlugin, InlinePluginResult, PluginDiagnostic, PluginGeneratedFile,
+};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
+
+use super::unsupported_bracket_diagnostic;
+
+/// A macro asserting a comparison of two values, e.g. `assert_eq!(a, b)`. If the comparison does
+/// not hold, panics with a ByteArray message showing both values, formatted using `Debug`, and the
+/// optional third argument of the macro, formatted using `Display`.
+#[derive(Debug)]
+pub struct CompareAssertMacro {
+    /// The name of the macro.
+    pub name: &'static str,
+    /// The comparison operator asserted to hold between the values.
+    pub operator: &'static str,
+}
+impl CompareAssertMacro {
+    /// Returns whether the comparison is of equality, which is done on snapshots of the values.
+    /// Ordering comparisons are done on the values themselves, so they require `Copy` values.
+    fn is_equality(&self) -> bool {
+        matches!(self.operator, "==" | "!=")
+    }
+}
+impl InlineMacroExprPlugin for CompareAssertMacro {
+    fn generate_code(
+        &self,
+        db: &dyn SyntaxGroup,
+        syntax: &ast::ExprInlineMacro,
+    ) -> InlinePluginResult {
+        let ast::WrappedArgList::ParenthesizedArgList(args) = syntax.arguments(db) else {
+            return unsupported_bracket_diagnostic(db, syntax);
+        };
+        let args = args
+            .args(db)
+            .elements(db)
+            .into_iter()
+            .map(|arg| match arg.arg_clause(db) {
+                ast::ArgClause::Unnamed(arg_clause)
+                    if arg.modifiers(db).elements(db).is_empty() =>
+                {
+                    Some(arg_clause.value(db))
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some((lhs, rhs, message)) = args.and_then(|args| match &args[..] {
+            [lhs, rhs] => Some((lhs.clone(), rhs.clone(), None)),
+            [lhs, rhs, message] => Some((lhs.clone(), rhs.clone(), Some(message.clone()))),
+            _ => None,
+        }) else {
+            return InlinePluginResult {
+                code: None,
+                diagnostics: vec![PluginDiagnostic {
+                    stable_ptr: syntax.stable_ptr().untyped(),
+                    message: format!(
+                        "Macro `{}` requires 2 unnamed arguments - the compared values, and an \
+                         optional message.",
+                        self.name
+                    ),
+                }],
+            };
+        };
+        let (lhs_var, rhs_var, formatter_var) =
+            ("__assert_macro_lhs__", "__assert_macro_rhs__", "__assert_macro_formatter__");
+        let mut builder = PatchBuilder::new(db);
+        builder.add_str("{\n");
+        for (var, value) in [(lhs_var, &lhs), (rhs_var, &rhs)] {
+            builder.add_str(&format!("    let {var} = @("));
+            builder.add_modified(RewriteNode::new_trimmed(value.as_syntax_node()));
+            builder.add_str(");\n");
+        }
+        let condition = if self.is_equality() {
+            format!("{lhs_var} {} {rhs_var}", self.operator)
+        } else {
+            format!("*{lhs_var} {} *{rhs_var}", self.operator)
+        };
+        let append = |text: &str| {
+            format!(
+                "        core::byte_array::ByteArrayTrait::append(ref {formatter_var}.buffer, \
+                 @{text:?});\n"
+            )
+        };
+        let format = |trait_name: &str, value: &str| {
+            format!(
+                "        core::result::ResultTrait::<(), \
+                 core::fmt::Error>::unwrap(core::fmt::{trait_name}::fmt({value}, ref \
+                 {formatter_var}));\n"
+            )
+        };
+        builder.add_str(&format!(
+            "    if !({condition}) {{\n        let mut {formatter_var}: core::fmt::Formatter = \
+             core::traits::Default::default();\n"
+        ));
+        builder.add_str(&append(&format!("assertion `lhs {} rhs` failed", self.operator)));
+        if let Some(message) = message {
+            builder.add_str(&append(": "));
+            // String literals are not otherwise inferred to be ByteArrays.
+            let display_trait = if matches!(message, ast::Expr::String(_)) {
+                "Display::<core::byte_array::ByteArray>"
+            } else {
+                "Display"
+            };
+            builder.add_str(&format!(
+                "        core::result::ResultTrait::<(), \
+                 core::fmt::Error>::unwrap(core::fmt::{display_trait}::fmt(@("
+            ));
+            builder.add_modified(RewriteNode::new_trimmed(message.as_syntax_node()));
+            builder.add_str(&format!("), ref {formatter_var}));\n"));
+        }
+        builder.add_str(&append(".\n  lhs: "));
+        builder.add_str(&format("Debug", lhs_var));
+        builder.add_str(&append("\n  rhs: "));
+        builder.add_str(&format("Debug", rhs_var));
+        builder.add_str(&format!(
+            "        core::panic_with_byte_array(@{formatter_var}.buffer)\n    }}\n}}"
+        ));
+        InlinePluginResult {
+            code: Some(PluginGeneratedFile {
+                name: format!("{}_inline_macro", self.name).into(),
+                content: builder.code,
+                diagnostics_mappings: builder.diagnostics_mappings,
+                aux_data: None,
+            }),
+            diagnostics: vec![],
+        }
+    }
+}
+
+/// Returns the comparing assertion macros, by name.
+pub fn compare_assert_macros() -> impl Iterator<Item = CompareAssertMacro> {
+    [
+        ("assert_eq", "=="),
+        ("assert_ne", "!="),
+        ("assert_lt", "<"),
+        ("assert_le", "<="),
+        ("assert_gt", ">"),
+        ("assert_ge", ">="),
+    ]
+    .into_iter()
+    .map(|(name, operator)| CompareAssertMacro { name, operator })
+}
@@ -1,4 +1,5 @@
 mod array;
+mod assert;
 mod consteval_int;
 
 use std::sync::Arc;
@@ -10,6 +11,7 @@ use cairo_lang_syntax::node::TypedSyntaxNode;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 
 use super::inline_macros::array::ArrayMacro;
+use super::inline_macros::assert::compare_assert_macros;
 use super::inline_macros::consteval_int::ConstevalIntMacro;
 
 /// Gets the default plugins to load into the Cairo compiler.
@@ -18,6 +20,9 @@ pub fn get_default_inline_macro_plugins() -> OrderedHashMap<String, Arc<dyn Inli
     let mut res = OrderedHashMap::<String, Arc<dyn InlineMacroExprPlugin>>::default();
     res.insert("array".to_string(), Arc::new(ArrayMacro));
     res.insert("consteval_int".to_string(), Arc::new(ConstevalIntMacro));
+    for assert_macro in compare_assert_macros() {
+        res.insert(assert_macro.name.to_string(), Arc::new(assert_macro));
+    }
     res
 }
 
@@ -31,7 +36,7 @@ pub fn unsupported_bracket_diagnostic(
             stable_ptr: macro_ast.stable_ptr().untyped(),
             message: format!(
                 "Macro {} does not support this bracket type",
-                macro_ast.path(db).as_syntax_node().get_text(db)
+                macro_ast.path(db).as_syntax_node().get_text_without_trivia(db)
             ),
         }],
     }
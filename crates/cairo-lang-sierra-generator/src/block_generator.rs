@@ -6,6 +6,7 @@ use cairo_lang_diagnostics::Maybe;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use itertools::{chain, enumerate, zip_eq, Itertools};
 use lowering::borrow_check::analysis::StatementLocation;
+use lowering::ids::LocationId;
 use lowering::{MatchArm, VarUsage};
 use sierra::extensions::lib_func::SierraApChange;
 use sierra::program;
@@ -54,6 +55,11 @@ pub fn generate_block_body_code(
     // Process the statements.
     for (i, statement) in block.statements.iter().enumerate() {
         let statement_location = (block_id, i);
+        let location = match statement {
+            lowering::Statement::Call(statement_call) => Some(statement_call.location),
+            _ => statement.outputs().first().map(|var_id| context.get_var_location(*var_id)),
+        };
+        statements.extend(location.and_then(|location| location_statement(context, location)));
         statements.extend(generate_statement_code(context, statement, &statement_location)?);
         let drop_location = &DropLocation::PostStatement(statement_location);
         add_drop_statements(context, drops, drop_location, &mut statements)?;
@@ -69,6 +75,17 @@ pub fn generate_block_body_code(
     Ok(statements)
 }
 
+/// Returns a statement marking the following statements as generated from `location`, if
+/// statement locations are requested.
+fn location_statement(
+    context: &ExprGeneratorContext<'_>,
+    location: LocationId,
+) -> Option<pre_sierra::Statement> {
+    context.should_add_statement_locations().then(|| {
+        pre_sierra::Statement::Location(location.get(context.get_db().upcast()).stable_location)
+    })
+}
+
 /// Adds calls to the `drop` libfunc for the given [DropLocation], according to the `drops`
 /// argument (computed by [find_variable_lifetime](crate::lifetime::find_variable_lifetime)).
 fn add_drop_statements(
@@ -107,6 +124,9 @@ pub fn generate_block_code(
 
     match &block.end {
         lowering::FlatBlockEnd::Return(returned_variables) => {
+            statements.extend(returned_variables.first().and_then(|returned_variable| {
+                location_statement(context, returned_variable.location)
+            }));
             statements.extend(generate_return_code(
                 context,
                 returned_variables,
@@ -141,6 +161,7 @@ pub fn generate_block_code(
         // Process the block end if it's a match.
         lowering::FlatBlockEnd::Match { info } => {
             let statement_location = (block_id, block.statements.len());
+            statements.extend(location_statement(context, *info.location()));
 
             if context.should_enable_ap_tracking(&block_id) {
                 context.set_ap_tracking(true);
@@ -113,12 +113,28 @@ pub trait SierraGenGroup: LoweringGroup + Upcast<dyn LoweringGroup> {
         requested_function_ids: Vec<ConcreteFunctionWithBodyId>,
     ) -> Maybe<Arc<cairo_lang_sierra::program::Program>>;
 
+    /// Returns the [cairo_lang_sierra::program::Program] object of the requested functions, along
+    /// with its debug info.
+    #[salsa::invoke(program_generator::get_sierra_program_with_debug_for_functions)]
+    fn get_sierra_program_with_debug_for_functions(
+        &self,
+        requested_function_ids: Vec<ConcreteFunctionWithBodyId>,
+    ) -> Maybe<Arc<program_generator::SierraProgramWithDebug>>;
+
     /// Returns the [cairo_lang_sierra::program::Program] object of the requested crates.
     #[salsa::invoke(program_generator::get_sierra_program)]
     fn get_sierra_program(
         &self,
         requested_crate_ids: Vec<CrateId>,
     ) -> Maybe<Arc<cairo_lang_sierra::program::Program>>;
+
+    /// Returns the [cairo_lang_sierra::program::Program] object of the requested crates, along
+    /// with its debug info.
+    #[salsa::invoke(program_generator::get_sierra_program_with_debug)]
+    fn get_sierra_program_with_debug(
+        &self,
+        requested_crate_ids: Vec<CrateId>,
+    ) -> Maybe<Arc<program_generator::SierraProgramWithDebug>>;
 }
 
 fn get_function_signature(
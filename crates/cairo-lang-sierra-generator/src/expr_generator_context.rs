@@ -1,12 +1,16 @@
 use cairo_lang_diagnostics::Maybe;
+use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_filesystem::ids::FlagId;
 use cairo_lang_lowering as lowering;
+use cairo_lang_lowering::db::LoweringGroup;
 use cairo_lang_semantic::TypeId;
 use cairo_lang_sierra::extensions::uninitialized::UninitializedType;
 use cairo_lang_sierra::extensions::NamedType;
 use cairo_lang_sierra::program::{ConcreteTypeLongId, GenericArg};
+use cairo_lang_utils::extract_matches;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
-use lowering::ids::ConcreteFunctionWithBodyId;
+use lowering::ids::{ConcreteFunctionWithBodyId, LocationId};
 use lowering::{BlockId, FlatLowered, VariableId};
 
 use crate::ap_tracking::ApTrackingConfiguration;
@@ -31,6 +35,8 @@ pub struct ExprGeneratorContext<'a> {
     ap_tracking_enabled: bool,
     /// Information about where AP tracking should be enabled and disabled.
     ap_tracking_configuration: ApTrackingConfiguration,
+    /// Whether to mark the generated statements with the locations they were generated from.
+    add_statement_locations: bool,
 }
 impl<'a> ExprGeneratorContext<'a> {
     /// Constructs an empty [ExprGeneratorContext].
@@ -41,6 +47,10 @@ impl<'a> ExprGeneratorContext<'a> {
         lifetime: &'a VariableLifetimeResult,
         ap_tracking_configuration: ApTrackingConfiguration,
     ) -> Self {
+        let lowering_db: &dyn LoweringGroup = db.upcast();
+        let add_statement_locations = lowering_db
+            .get_flag(FlagId::new(lowering_db.upcast(), "add_statement_locations"))
+            .map_or(false, |flag| extract_matches!(*flag, Flag::AddStatementLocations));
         ExprGeneratorContext {
             db,
             lowered,
@@ -52,6 +62,7 @@ impl<'a> ExprGeneratorContext<'a> {
             block_labels: OrderedHashMap::default(),
             ap_tracking_enabled: true,
             ap_tracking_configuration,
+            add_statement_locations,
         }
     }
 
@@ -155,6 +166,17 @@ impl<'a> ExprGeneratorContext<'a> {
         self.lowered.variables[var_id].ty
     }
 
+    /// Returns the location of the variable given by `var_id`.
+    pub fn get_var_location(&self, var_id: VariableId) -> LocationId {
+        self.lowered.variables[var_id].location
+    }
+
+    /// Returns whether the generated statements should be marked with the locations they were
+    /// generated from.
+    pub fn should_add_statement_locations(&self) -> bool {
+        self.add_statement_locations
+    }
+
     /// Gets the current ap tracking state.
     pub fn get_ap_tracking(&self) -> bool {
         self.ap_tracking_enabled
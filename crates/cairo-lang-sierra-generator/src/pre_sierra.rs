@@ -1,4 +1,8 @@
 use cairo_lang_debug::DebugWithDb;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::diagnostic_utils::StableLocation;
+use cairo_lang_diagnostics::DiagnosticLocation;
+use cairo_lang_lowering::db::LoweringGroup;
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
 use cairo_lang_sierra as sierra;
 use cairo_lang_sierra::ids::ConcreteTypeId;
@@ -65,6 +69,9 @@ pub enum Statement {
     /// Note that push values does not guarantee that new copies of the values will be pushed.
     /// If a prefix of the values is already on the stack, they will not be re-pushed.
     PushValues(Vec<PushValue>),
+    /// Marks the following statements, up to the next label or location, as generated from the
+    /// given location in the Cairo code. Generates no code.
+    Location(StableLocation),
 }
 impl Statement {
     pub fn to_string(&self, db: &dyn SierraGenGroup) -> String {
@@ -100,6 +107,16 @@ impl<'db> std::fmt::Display for StatementWithDb<'db> {
                 )?;
                 write!(f, ")")
             }
+            Statement::Location(location) => {
+                let lowering_db: &dyn LoweringGroup = self.db.upcast();
+                let defs_db: &dyn DefsGroup = lowering_db.upcast();
+                let DiagnosticLocation { file_id, span } = location.diagnostic_location(defs_db);
+                write!(f, "// {}", file_id.full_path(defs_db.upcast()))?;
+                match span.start.position_in_file(defs_db.upcast(), file_id) {
+                    Some(position) => write!(f, ":{}:{}", position.line + 1, position.col + 1),
+                    None => Ok(()),
+                }
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 use std::sync::Arc;
 
+use cairo_lang_defs::diagnostic_utils::StableLocation;
 use cairo_lang_diagnostics::Maybe;
 use cairo_lang_filesystem::ids::CrateId;
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
@@ -50,7 +51,8 @@ fn collect_used_libfuncs(
                 Some(invocation.libfunc_id.clone())
             }
             pre_sierra::Statement::Sierra(program::GenStatement::Return(_))
-            | pre_sierra::Statement::Label(_) => None,
+            | pre_sierra::Statement::Label(_)
+            | pre_sierra::Statement::Location(_) => None,
             pre_sierra::Statement::PushValues(_) => {
                 panic!("Unexpected pre_sierra::Statement::PushValues in collect_used_libfuncs().")
             }
@@ -159,10 +161,33 @@ fn collect_used_types(
         .collect()
 }
 
+/// A Sierra program along with debug info about it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SierraProgramWithDebug {
+    pub program: Arc<program::Program>,
+    pub debug_info: SierraProgramDebugInfo,
+}
+
+/// Debug info about a Sierra program.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SierraProgramDebugInfo {
+    /// The locations in the Cairo code that the statements of the program were generated from, by
+    /// statement index. Statements that are not attributed to any location are mapped to None, as
+    /// are all the statements if the `add_statement_locations` flag is not set.
+    pub statements_locations: Vec<Option<StableLocation>>,
+}
+
 pub fn get_sierra_program_for_functions(
     db: &dyn SierraGenGroup,
     requested_function_ids: Vec<ConcreteFunctionWithBodyId>,
 ) -> Maybe<Arc<cairo_lang_sierra::program::Program>> {
+    Ok(db.get_sierra_program_with_debug_for_functions(requested_function_ids)?.program.clone())
+}
+
+pub fn get_sierra_program_with_debug_for_functions(
+    db: &dyn SierraGenGroup,
+    requested_function_ids: Vec<ConcreteFunctionWithBodyId>,
+) -> Maybe<Arc<SierraProgramWithDebug>> {
     let mut functions: Vec<Arc<pre_sierra::Function>> = vec![];
     let mut statements: Vec<pre_sierra::Statement> = vec![];
     let mut processed_function_ids = UnorderedHashSet::<ConcreteFunctionWithBodyId>::default();
@@ -187,11 +212,12 @@ pub fn get_sierra_program_for_functions(
         generate_libfunc_declarations(db, collect_used_libfuncs(&statements).iter());
     let type_declarations =
         generate_type_declarations(db, collect_used_types(db, &libfunc_declarations));
+    let statements_locations = collect_statements_locations(&statements);
     // Resolve labels.
     let label_replacer = LabelReplacer::from_statements(&statements);
     let resolved_statements = resolve_labels(statements, &label_replacer);
 
-    Ok(Arc::new(program::Program {
+    let program = program::Program {
         type_declarations,
         libfunc_declarations,
         statements: resolved_statements,
@@ -207,9 +233,37 @@ pub fn get_sierra_program_for_functions(
                 )
             })
             .collect(),
+    };
+    Ok(Arc::new(SierraProgramWithDebug {
+        program: Arc::new(program),
+        debug_info: SierraProgramDebugInfo { statements_locations },
     }))
 }
 
+/// Collects the location of each of the Sierra statements in the given pre-Sierra statements, as
+/// marked by the [pre_sierra::Statement::Location] statements preceding it.
+fn collect_statements_locations(
+    statements: &[pre_sierra::Statement],
+) -> Vec<Option<StableLocation>> {
+    let mut locations = vec![];
+    let mut current_location = None;
+    for statement in statements {
+        match statement {
+            pre_sierra::Statement::Sierra(_) => locations.push(current_location),
+            // The code following a label may be reached from other locations.
+            pre_sierra::Statement::Label(_) => current_location = None,
+            pre_sierra::Statement::Location(location) => current_location = Some(*location),
+            pre_sierra::Statement::PushValues(_) => {
+                panic!(
+                    "Unexpected pre_sierra::Statement::PushValues in \
+                     collect_statements_locations()."
+                )
+            }
+        }
+    }
+    locations
+}
+
 /// Tries extracting a ConcreteFunctionWithBodyId from a pre-Sierra statement.
 fn try_get_function_with_body_id(
     db: &dyn SierraGenGroup,
@@ -238,6 +292,13 @@ pub fn get_sierra_program(
     db: &dyn SierraGenGroup,
     requested_crate_ids: Vec<CrateId>,
 ) -> Maybe<Arc<cairo_lang_sierra::program::Program>> {
+    Ok(db.get_sierra_program_with_debug(requested_crate_ids)?.program.clone())
+}
+
+pub fn get_sierra_program_with_debug(
+    db: &dyn SierraGenGroup,
+    requested_crate_ids: Vec<CrateId>,
+) -> Maybe<Arc<SierraProgramWithDebug>> {
     let mut requested_function_ids = vec![];
     for crate_id in requested_crate_ids {
         for module_id in db.crate_modules(crate_id).iter() {
@@ -251,5 +312,5 @@ pub fn get_sierra_program(
             }
         }
     }
-    db.get_sierra_program_for_functions(requested_function_ids)
+    db.get_sierra_program_with_debug_for_functions(requested_function_ids)
 }
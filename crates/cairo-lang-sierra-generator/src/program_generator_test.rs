@@ -1,17 +1,26 @@
+use std::sync::Arc;
+
 use cairo_lang_defs::db::DefsGroup;
 use cairo_lang_defs::ids::ModuleItemId;
+use cairo_lang_diagnostics::DiagnosticLocation;
+use cairo_lang_filesystem::db::FilesGroupEx;
+use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_filesystem::ids::FlagId;
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
 use cairo_lang_semantic::db::SemanticGroup;
-use cairo_lang_utils::try_extract_matches;
+use cairo_lang_semantic::test_utils::setup_test_crate;
+use cairo_lang_utils::{try_extract_matches, UpcastMut};
 use indoc::indoc;
-use itertools::Itertools;
+use itertools::{zip_eq, Itertools};
 use pretty_assertions::assert_eq;
 use test_case::test_case;
 use test_log::test;
 
 use crate::db::SierraGenGroup;
 use crate::replace_ids::replace_sierra_ids_in_program;
-use crate::test_utils::{checked_compile_to_sierra, setup_db_and_get_crate_id};
+use crate::test_utils::{
+    checked_compile_to_sierra, setup_db_and_get_crate_id, SierraGenDatabaseForTesting,
+};
 
 #[test]
 fn test_program_generator() {
@@ -144,3 +153,74 @@ fn test_only_include_dependencies(func_name: &str, sierra_used_funcs: &[&str]) {
         sierra_used_funcs
     );
 }
+
+#[test]
+fn test_statement_locations() {
+    let mut db = SierraGenDatabaseForTesting::new_empty();
+    let add_statement_locations_flag_id = FlagId::new(db.upcast_mut(), "add_statement_locations");
+    db.set_flag(add_statement_locations_flag_id, Some(Arc::new(Flag::AddStatementLocations(true))));
+    let crate_id = setup_test_crate(
+        &db,
+        indoc! {"
+        fn foo(a: felt252) -> felt252 {
+            let b = bar(a);
+            match felt252_is_zero(b) {
+                zeroable::IsZeroResult::Zero => 0,
+                zeroable::IsZeroResult::NonZero(_) => bar(b),
+            }
+        }
+
+        fn bar(a: felt252) -> felt252 {
+            felt252_add(a, a)
+        }
+    "},
+    );
+    let func_id = ConcreteFunctionWithBodyId::from_no_generics_free(
+        &db,
+        db.crate_modules(crate_id)
+            .iter()
+            .find_map(|module_id| {
+                try_extract_matches!(
+                    db.module_item_by_name(*module_id, "foo".into()).unwrap().unwrap(),
+                    ModuleItemId::FreeFunction
+                )
+            })
+            .unwrap(),
+    )
+    .unwrap();
+    let program_with_debug = db.get_sierra_program_with_debug_for_functions(vec![func_id]).unwrap();
+    let program = replace_sierra_ids_in_program(&db, &program_with_debug.program);
+    let locations = &program_with_debug.debug_info.statements_locations;
+    let statements_with_locations = zip_eq(&program.statements, locations)
+        .map(|(statement, location)| {
+            let Some(location) = location else { return statement.to_string() };
+            let DiagnosticLocation { file_id, span } = location.diagnostic_location(&db);
+            let position = span.start.position_in_file(&db, file_id).unwrap();
+            format!("{statement} // {}:{}", position.line + 1, position.col + 1)
+        })
+        .join("\n");
+    // `bar` is inlined, so some of the statements of `foo` are located in `bar`.
+    assert_eq!(
+        statements_with_locations,
+        indoc! {"
+            dup<felt252>([0]) -> ([0], [2]) // 10:5
+            felt252_add([2], [0]) -> ([1]) // 10:5
+            store_temp<felt252>([1]) -> ([1]) // 3:11
+            dup<felt252>([1]) -> ([1], [3]) // 3:11
+            felt252_is_zero([3]) { fallthrough() 10([4]) } // 3:11
+            branch_align() -> () // 3:11
+            drop<felt252>([1]) -> () // 3:11
+            felt252_const<0>() -> ([5]) // 4:41
+            store_temp<felt252>([5]) -> ([6]) // 4:41
+            jump() { 15() } // 4:41
+            branch_align() -> ()
+            drop<NonZero<felt252>>([4]) -> ()
+            dup<felt252>([1]) -> ([1], [8]) // 10:5
+            felt252_add([8], [1]) -> ([7]) // 10:5
+            store_temp<felt252>([7]) -> ([6]) // 10:5
+            rename<felt252>([6]) -> ([9]) // 3:11
+            return([9]) // 3:11
+        "}
+        .trim_end()
+    );
+}
@@ -182,6 +182,67 @@ fn write_read_large_value() {
     );
 }
 
+#[test]
+#[available_gas(300000)]
+fn test_load_written_value() {
+    assert(test_contract::__external::set_value(serialized(4)).is_empty(), 'Not empty');
+    let key = selector!("value").try_into().unwrap();
+    assert_eq(
+        @starknet::testing::load(starknet::get_contract_address(), key, 1),
+        @array![4].span(),
+        'Wrong loaded value'
+    );
+}
+
+#[test]
+#[available_gas(300000)]
+fn test_read_stored_value() {
+    let key = selector!("value").try_into().unwrap();
+    starknet::testing::store(starknet::get_contract_address(), key, array![7].span());
+    assert_eq(
+        @test_contract::__external::get_value(serialized(())), @serialized(7), 'Wrong result'
+    );
+}
+
+#[test]
+#[available_gas(300000)]
+fn test_store_and_load_consecutive_values() {
+    let address = starknet::contract_address_const::<0x1234>();
+    starknet::testing::store(address, 0x17.try_into().unwrap(), array![1, 2].span());
+    assert_eq(
+        @starknet::testing::load(address, 0x17.try_into().unwrap(), 3),
+        @array![1, 2, 0].span(),
+        'Wrong loaded values'
+    );
+    assert_eq(
+        @starknet::testing::load(starknet::get_contract_address(), 0x17.try_into().unwrap(), 2),
+        @array![0, 0].span(),
+        'Storage not per contract'
+    );
+}
+
+#[test]
+#[available_gas(500000)]
+fn test_revert_to_snapshot() {
+    assert(test_contract::__external::set_value(serialized(4)).is_empty(), 'Not empty');
+    starknet::testing::set_block_number(1);
+    let snapshot_id = starknet::testing::snapshot();
+    assert(test_contract::__external::set_value(serialized(5)).is_empty(), 'Not empty');
+    starknet::testing::set_block_number(2);
+    starknet::testing::revert_to_snapshot(snapshot_id);
+    assert_eq(
+        @test_contract::__external::get_value(serialized(())),
+        @serialized(4),
+        'Storage not reverted'
+    );
+    assert_eq(@starknet::get_block_info().unbox().block_number, @1, 'Block number not reverted');
+    assert(test_contract::__external::set_value(serialized(6)).is_empty(), 'Not empty');
+    starknet::testing::revert_to_snapshot(snapshot_id);
+    assert_eq(
+        @test_contract::__external::get_value(serialized(())), @serialized(4), 'Snapshot not kept'
+    );
+}
+
 #[test]
 #[available_gas(300000)]
 fn test_get_block_info() {
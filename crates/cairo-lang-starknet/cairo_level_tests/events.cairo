@@ -2,6 +2,7 @@ use core::debug::PrintTrait;
 use test::test_utils::{assert_eq, assert_ne};
 use starknet::syscalls::{deploy_syscall, get_block_hash_syscall};
 use starknet::SyscallResultTrait;
+use starknet::testing::EventSpyTrait;
 
 #[starknet::interface]
 trait IContractWithEvent<T> {
@@ -159,3 +160,77 @@ fn test_pop_log() {
     assert_eq(keys.at(0), @1234, 'unexpected key');
     assert_eq(data.at(0), @2345, 'unexpected data');
 }
+
+#[starknet::interface]
+trait IEventForwarder<T> {
+    fn forward(ref self: T, target: starknet::ContractAddress);
+}
+
+#[starknet::contract]
+mod event_forwarder {
+    use super::{IContractWithEventDispatcher, IContractWithEventDispatcherTrait};
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    fn forward(ref self: ContractState, target: starknet::ContractAddress) {
+        IContractWithEventDispatcher { contract_address: target }.emit_event(true);
+    }
+}
+
+fn deploy_contract_with_event() -> IContractWithEventDispatcher {
+    let (contract_address, _) = deploy_syscall(
+        contract_with_event::TEST_CLASS_HASH.try_into().unwrap(), 0, array![].span(), false
+    )
+        .unwrap();
+    IContractWithEventDispatcher { contract_address }
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_spy_events() {
+    internal::revoke_ap_tracking();
+    let mut contract = deploy_contract_with_event();
+    contract.emit_event(true);
+    let mut spy = starknet::testing::spy_events();
+    contract.emit_event(true);
+    contract.emit_event(false);
+    spy
+        .assert_emitted(
+            array![
+                (contract.contract_address, Event::IncrementalEvent(IncrementalEvent { value: 1 })),
+                (contract.contract_address, Event::StaticEvent(StaticEvent {})),
+            ]
+        );
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_spy_events_in_nested_calls() {
+    internal::revoke_ap_tracking();
+    let mut contract = deploy_contract_with_event();
+    let (forwarder_address, _) = deploy_syscall(
+        event_forwarder::TEST_CLASS_HASH.try_into().unwrap(), 0, array![].span(), false
+    )
+        .unwrap();
+    let mut spy = starknet::testing::spy_events();
+    IEventForwarderDispatcher { contract_address: forwarder_address }
+        .forward(contract.contract_address);
+    let (address, event) = spy.pop::<Event>().unwrap();
+    assert_eq(@address, @contract.contract_address, 'Unexpected emitter');
+    assert_eq(@event, @Event::IncrementalEvent(IncrementalEvent { value: 0 }), 'Unexpected event');
+    assert(spy.pop_raw().is_none(), 'no more events');
+}
+
+#[test]
+#[available_gas(30000000)]
+#[should_panic(expected: ('Unexpected extra event',))]
+fn test_spy_events_unexpected_extra_event() {
+    internal::revoke_ap_tracking();
+    let mut contract = deploy_contract_with_event();
+    let mut spy = starknet::testing::spy_events();
+    contract.emit_event(false);
+    contract.emit_event(false);
+    spy.assert_emitted(array![(contract.contract_address, Event::StaticEvent(StaticEvent {}))]);
+}
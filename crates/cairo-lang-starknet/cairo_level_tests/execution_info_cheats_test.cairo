@@ -0,0 +1,162 @@
+use test::test_utils::assert_eq;
+use starknet::ContractAddress;
+use starknet::syscalls::deploy_syscall;
+use starknet::testing::{
+    set_account_contract_address, set_account_nonce, set_block_number, set_contract_address,
+    set_nonce, start_prank, start_prank_for_calls, start_roll, start_warp_for_calls, stop_prank,
+    stop_roll
+};
+
+#[starknet::interface]
+trait IExecutionInfoReader<T> {
+    fn caller_address(self: @T) -> ContractAddress;
+    fn block_number(self: @T) -> u64;
+    fn block_timestamp(self: @T) -> u64;
+}
+
+#[starknet::contract]
+mod execution_info_reader {
+    use starknet::ContractAddress;
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    impl ExecutionInfoReader of super::IExecutionInfoReader<ContractState> {
+        fn caller_address(self: @ContractState) -> ContractAddress {
+            starknet::get_caller_address()
+        }
+        fn block_number(self: @ContractState) -> u64 {
+            starknet::get_block_info().unbox().block_number
+        }
+        fn block_timestamp(self: @ContractState) -> u64 {
+            starknet::get_block_info().unbox().block_timestamp
+        }
+    }
+}
+
+#[starknet::interface]
+trait ILibraryCaller<T> {
+    fn caller_address_after_library_call(
+        self: @T, reader_class_hash: starknet::ClassHash
+    ) -> ContractAddress;
+}
+
+#[starknet::contract]
+mod library_caller {
+    use starknet::{ClassHash, ContractAddress};
+    use super::{IExecutionInfoReaderDispatcherTrait, IExecutionInfoReaderLibraryDispatcher};
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    impl LibraryCaller of super::ILibraryCaller<ContractState> {
+        fn caller_address_after_library_call(
+            self: @ContractState, reader_class_hash: ClassHash
+        ) -> ContractAddress {
+            IExecutionInfoReaderLibraryDispatcher { class_hash: reader_class_hash }
+                .caller_address();
+            starknet::get_caller_address()
+        }
+    }
+}
+
+fn deploy_reader() -> IExecutionInfoReaderDispatcher {
+    let (contract_address, _) = deploy_syscall(
+        execution_info_reader::TEST_CLASS_HASH.try_into().unwrap(), 0, array![].span(), false
+    )
+        .unwrap();
+    IExecutionInfoReaderDispatcher { contract_address }
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_prank_only_affects_target() {
+    let reader0 = deploy_reader();
+    let reader1 = deploy_reader();
+    start_prank(reader0.contract_address, starknet::contract_address_const::<17>());
+    assert_eq(@reader0.caller_address().into(), @17, 'target not pranked');
+    assert_eq(@reader0.caller_address().into(), @17, 'target prank expired');
+    assert_eq(@reader1.caller_address().into(), @0, 'non target pranked');
+    stop_prank(reader0.contract_address);
+    assert_eq(@reader0.caller_address().into(), @0, 'prank not stopped');
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_prank_for_calls() {
+    let reader = deploy_reader();
+    start_prank_for_calls(reader.contract_address, starknet::contract_address_const::<17>(), 2);
+    assert_eq(@reader.caller_address().into(), @17, 'first call not pranked');
+    assert_eq(@reader.caller_address().into(), @17, 'second call not pranked');
+    assert_eq(@reader.caller_address().into(), @0, 'third call pranked');
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_prank_for_library_calls() {
+    let reader = IExecutionInfoReaderLibraryDispatcher {
+        class_hash: execution_info_reader::TEST_CLASS_HASH.try_into().unwrap()
+    };
+    // Library calls run in the context of the calling contract, and are counted as calls into it.
+    let contract_address = starknet::contract_address_const::<5>();
+    set_contract_address(contract_address);
+    start_prank_for_calls(contract_address, starknet::contract_address_const::<17>(), 1);
+    assert_eq(@reader.caller_address().into(), @17, 'library call not pranked');
+    assert_eq(@reader.caller_address().into(), @0, 'prank applied too many times');
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_prank_for_calls_with_library_calls() {
+    let (contract_address, _) = deploy_syscall(
+        library_caller::TEST_CLASS_HASH.try_into().unwrap(), 0, array![].span(), false
+    )
+        .unwrap();
+    let caller = ILibraryCallerDispatcher { contract_address };
+    // Library calls made by a contract are a part of the call into it.
+    start_prank_for_calls(contract_address, starknet::contract_address_const::<17>(), 1);
+    let reader_class_hash = execution_info_reader::TEST_CLASS_HASH.try_into().unwrap();
+    assert_eq(
+        @caller.caller_address_after_library_call(reader_class_hash).into(),
+        @17,
+        'library call counted'
+    );
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_roll_and_warp() {
+    let reader = deploy_reader();
+    set_block_number(3);
+    start_roll(reader.contract_address, 5);
+    start_warp_for_calls(reader.contract_address, 7, 1);
+    assert_eq(@reader.block_number(), @5, 'block number not rolled');
+    assert_eq(@reader.block_timestamp(), @0, 'warp applied too many times');
+    assert_eq(@starknet::get_block_info().unbox().block_number, @3, 'non target rolled');
+    stop_roll(reader.contract_address);
+    assert_eq(@reader.block_number(), @3, 'roll not stopped');
+}
+
+#[test]
+#[available_gas(300000)]
+fn test_nonce_per_account() {
+    set_account_contract_address(starknet::contract_address_const::<1>());
+    set_nonce(5);
+    set_account_nonce(starknet::contract_address_const::<2>(), 8);
+    assert_eq(@starknet::get_tx_info().unbox().nonce, @5, 'wrong account 1 nonce');
+    set_account_contract_address(starknet::contract_address_const::<2>());
+    assert_eq(@starknet::get_tx_info().unbox().nonce, @8, 'wrong account 2 nonce');
+    // Accounts without a nonce of their own have the nonce of the transaction.
+    set_account_contract_address(starknet::contract_address_const::<3>());
+    assert_eq(@starknet::get_tx_info().unbox().nonce, @5, 'wrong account 3 nonce');
+}
+
+#[test]
+#[available_gas(300000)]
+fn test_nonce_before_account() {
+    set_nonce(5);
+    set_account_contract_address(starknet::contract_address_const::<1>());
+    assert_eq(@starknet::get_tx_info().unbox().nonce, @5, 'nonce not kept');
+}
@@ -115,6 +115,41 @@ fn test_contract_not_deployed() {
     contract.foo(10);
 }
 
+#[test]
+#[available_gas(30000000)]
+fn test_mocked_call() {
+    let contract_address = 5.try_into().unwrap();
+    starknet::testing::mock_call(contract_address, selector!("foo"), array![17].span());
+    let mut contract = IContractDispatcher { contract_address };
+    assert_eq(@contract.foo(10), @17, 'mocked value not returned');
+}
+
+#[test]
+#[available_gas(30000000)]
+#[should_panic(expected: ('Mocked failure', 'ENTRYPOINT_FAILED',))]
+fn test_mocked_call_revert() {
+    let contract_address = 5.try_into().unwrap();
+    starknet::testing::mock_call_revert(
+        contract_address, selector!("foo"), array!['Mocked failure'].span()
+    );
+    let mut contract = IContractDispatcher { contract_address };
+    contract.foo(10);
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_mocked_call_overrides_deployed() {
+    let (contract_address, _) = deploy_syscall(
+        contract_a::TEST_CLASS_HASH.try_into().unwrap(), 0, array![100].span(), false
+    )
+        .unwrap();
+    let mut contract = IContractDispatcher { contract_address };
+    starknet::testing::mock_call(contract_address, selector!("foo"), array![17].span());
+    assert_eq(@contract.foo(300), @17, 'mocked value not returned');
+    starknet::testing::stop_mock_call(contract_address, selector!("foo"));
+    assert_eq(@contract.foo(300), @100, 'mock not stopped');
+}
+
 #[starknet::contract]
 mod contract_failed_constructor {
     #[storage]
@@ -178,7 +213,34 @@ fn test_entrypoint_failed() {
 
 #[test]
 #[available_gas(30000000)]
-#[should_panic(expected: ('GET_BLOCK_HASH_UNIMPLEMENTED',))]
 fn test_get_block_hash() {
-    get_block_hash_syscall(0).unwrap_syscall();
+    starknet::testing::set_block_hash(5, 'some_hash');
+    starknet::testing::set_block_number(15);
+    assert_eq(@get_block_hash_syscall(5).unwrap_syscall(), @'some_hash', 'unexpected block hash');
+}
+
+#[test]
+#[available_gas(30000000)]
+#[should_panic(expected: ('Block number out of range',))]
+fn test_get_block_hash_too_recent() {
+    starknet::testing::set_block_hash(6, 'some_hash');
+    starknet::testing::set_block_number(15);
+    get_block_hash_syscall(6).unwrap_syscall();
+}
+
+#[test]
+#[available_gas(30000000)]
+#[should_panic(expected: ('Block number out of range',))]
+fn test_get_block_hash_unknown() {
+    starknet::testing::set_block_number(15);
+    get_block_hash_syscall(5).unwrap_syscall();
+}
+
+#[test]
+#[available_gas(30000000)]
+#[should_panic(expected: ('Invalid block number',))]
+fn test_get_block_hash_invalid_block_number() {
+    // The cheatcode sets block numbers which do not fit in a `u64`, unlike `set_block_number`.
+    starknet::testing::cheatcode::<'set_block_number'>(array![0x10000000000000000].span());
+    get_block_hash_syscall(5).unwrap_syscall();
 }
@@ -2,6 +2,7 @@ mod abi_dispatchers_tests;
 mod components;
 mod contract_tests;
 mod events;
+mod execution_info_cheats_test;
 mod interoperability;
 mod l2_to_l1_messages;
 mod multi_component_test;
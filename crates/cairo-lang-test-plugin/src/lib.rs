@@ -1,10 +1,12 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use cairo_felt::Felt252;
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_debug::DebugWithDb;
+use cairo_lang_defs::diagnostic_utils::StableLocation;
 use cairo_lang_defs::ids::{FreeFunctionId, FunctionWithBodyId, ModuleItemId};
-use cairo_lang_diagnostics::ToOption;
-use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_diagnostics::{DiagnosticLocation, ToOption};
+use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_filesystem::ids::{CrateId, FileLongId};
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
 use cairo_lang_semantic::db::SemanticGroup;
 use cairo_lang_semantic::items::functions::GenericFunctionId;
@@ -24,7 +26,9 @@ use cairo_lang_utils::ordered_hash_map::{
 };
 use itertools::{chain, Itertools};
 use serde::{Deserialize, Serialize};
-pub use test_config::{try_extract_test_config, TestConfig};
+pub use test_config::{
+    try_extract_fixture_config, try_extract_test_config, FixtureConfig, TestConfig,
+};
 
 pub mod plugin;
 pub mod test_config;
@@ -34,6 +38,12 @@ const TEST_ATTR: &str = "test";
 const SHOULD_PANIC_ATTR: &str = "should_panic";
 const IGNORE_ATTR: &str = "ignore";
 const AVAILABLE_GAS_ATTR: &str = "available_gas";
+const FUZZER_ATTR: &str = "fuzzer";
+const SETUP_ATTR: &str = "setup";
+const FIXTURE_ATTR: &str = "fixture";
+const USE_FIXTURE_ATTR: &str = "use_fixture";
+const MAX_STEPS_ATTR: &str = "max_steps";
+const TIMEOUT_ATTR: &str = "timeout";
 
 /// Runs Cairo compiler.
 ///
@@ -77,43 +87,99 @@ pub fn compile_test_prepared_db(
                 )
             })
             .collect();
-    let all_tests = find_all_tests(db, test_crate_ids.clone());
+    let (all_tests, all_fixtures) = find_all_tests(db, test_crate_ids.clone());
+    let function_ids = chain!(
+        all_entry_points.into_iter(),
+        all_tests
+            .iter()
+            .map(|(func_id, _cfg)| func_id)
+            .chain(all_fixtures.iter().map(|(func_id, _cfg)| func_id))
+            .flat_map(|func_id| ConcreteFunctionWithBodyId::from_no_generics_free(db, *func_id))
+    )
+    .collect_vec();
     let sierra_program = db
-        .get_sierra_program_for_functions(
-            chain!(
-                all_entry_points.into_iter(),
-                all_tests.iter().flat_map(|(func_id, _cfg)| {
-                    ConcreteFunctionWithBodyId::from_no_generics_free(db, *func_id)
-                })
-            )
-            .collect(),
-        )
+        .get_sierra_program_with_debug_for_functions(function_ids)
         .to_option()
         .with_context(|| "Compilation failed without any diagnostics.")?;
+    let statements_locations = sierra_program
+        .debug_info
+        .statements_locations
+        .iter()
+        .map(|location| location.and_then(|location| CodeLocation::from_stable(db, location)))
+        .collect();
     let replacer = DebugReplacer { db };
-    let sierra_program = replacer.apply(&sierra_program);
+    let sierra_program = replacer.apply(&sierra_program.program);
 
+    let named_fixtures = all_fixtures
+        .into_iter()
+        .map(|(func_id, fixture)| (function_full_path(db, func_id), fixture))
+        .collect_vec();
     let named_tests = all_tests
         .into_iter()
-        .map(|(func_id, test)| {
-            (
-                format!(
-                    "{:?}",
-                    FunctionLongId {
-                        function: ConcreteFunction {
-                            generic_function: GenericFunctionId::Free(func_id),
-                            generic_args: vec![]
-                        }
-                    }
-                    .debug(db)
-                ),
-                test,
-            )
+        .map(|(func_id, test)| (function_full_path(db, func_id), test))
+        .map(|(name, test)| {
+            let fixture = resolve_fixture(&name, &test, &named_fixtures)?;
+            Ok((name, TestConfig { fixture, ..test }))
         })
-        .collect_vec();
+        .collect::<Result<Vec<_>>>()?;
     let contracts_info = get_contracts_info(db, main_crate_ids.clone(), &replacer)?;
 
-    Ok(TestCompilation { named_tests, sierra_program, function_set_costs, contracts_info })
+    Ok(TestCompilation {
+        named_tests,
+        named_fixtures,
+        sierra_program,
+        statements_locations,
+        function_set_costs,
+        contracts_info,
+    })
+}
+
+/// Returns the full path of a free function, as used for naming tests and fixtures.
+fn function_full_path(db: &RootDatabase, func_id: FreeFunctionId) -> String {
+    format!(
+        "{:?}",
+        FunctionLongId {
+            function: ConcreteFunction {
+                generic_function: GenericFunctionId::Free(func_id),
+                generic_args: vec![]
+            }
+        }
+        .debug(db)
+    )
+}
+
+/// Returns the full path of the fixture the test `name` starts from, if any.
+///
+/// A fixture given by name is looked up in the module of the test, and otherwise it is looked up
+/// by its full path. Tests that do not use a fixture start from the setup fixture of their module,
+/// if it has one.
+fn resolve_fixture(
+    name: &str,
+    test: &TestConfig,
+    named_fixtures: &[(String, FixtureConfig)],
+) -> Result<Option<String>> {
+    let module_path = name.rsplit_once("::").map_or("", |(module_path, _)| module_path);
+    let Some(fixture) = &test.fixture else {
+        let mut setups = named_fixtures.iter().filter(|(fixture_name, fixture)| {
+            fixture.is_setup
+                && fixture_name.rsplit_once("::").map(|(path, _)| path) == Some(module_path)
+        });
+        let setup = setups.next().map(|(fixture_name, _)| fixture_name.clone());
+        if setups.next().is_some() {
+            bail!("Module `{module_path}` has more than one `#[{SETUP_ATTR}]` function.");
+        }
+        return Ok(setup);
+    };
+    let local_path = format!("{module_path}::{fixture}");
+    for path in [&local_path, fixture] {
+        if named_fixtures.iter().any(|(fixture_name, _)| fixture_name == path) {
+            return Ok(Some(path.clone()));
+        }
+    }
+    bail!(
+        "Test `{name}` uses an unknown fixture `{fixture}`. Fixtures are functions with the \
+         `#[{FIXTURE_ATTR}]` or `#[{SETUP_ATTR}]` attributes."
+    )
 }
 
 /// Compiled test cases.
@@ -130,31 +196,65 @@ pub struct TestCompilation {
     )]
     pub function_set_costs: OrderedHashMap<FunctionId, OrderedHashMap<CostTokenType, i32>>,
     pub named_tests: Vec<(String, TestConfig)>,
+    pub named_fixtures: Vec<(String, FixtureConfig)>,
     pub sierra_program: Program,
+    /// The locations in the Cairo code that the statements of the Sierra program were generated
+    /// from, by statement index.
+    pub statements_locations: Vec<Option<CodeLocation>>,
+}
+
+/// A location in a Cairo file, with 1-based line and column.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CodeLocation {
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
 }
+impl CodeLocation {
+    /// Returns the start of a stable location, or None if it is not in a file on disk (e.g. in
+    /// code generated by a plugin).
+    fn from_stable(db: &RootDatabase, location: StableLocation) -> Option<Self> {
+        let DiagnosticLocation { file_id, span } = location.diagnostic_location(db);
+        if !matches!(db.lookup_intern_file(file_id), FileLongId::OnDisk(_)) {
+            return None;
+        }
+        let position = span.start.position_in_file(db, file_id)?;
+        Some(Self { file: file_id.full_path(db), line: position.line + 1, col: position.col + 1 })
+    }
+}
+
+/// Free functions along with their test or fixture configuration.
+type FunctionsWithConfig<Config> = Vec<(FreeFunctionId, Config)>;
 
-/// Finds the tests in the requested crates.
+/// Finds the tests and the fixtures in the requested crates.
 fn find_all_tests(
     db: &dyn SemanticGroup,
     main_crates: Vec<CrateId>,
-) -> Vec<(FreeFunctionId, TestConfig)> {
+) -> (FunctionsWithConfig<TestConfig>, FunctionsWithConfig<FixtureConfig>) {
     let mut tests = vec![];
+    let mut fixtures = vec![];
     for crate_id in main_crates {
         let modules = db.crate_modules(crate_id);
         for module_id in modules.iter() {
             let Ok(module_items) = db.module_items(*module_id) else {
                 continue;
             };
-            tests.extend(module_items.iter().filter_map(|item| {
-                let ModuleItemId::FreeFunction(func_id) = item else { return None };
+            for item in module_items.iter() {
+                let ModuleItemId::FreeFunction(func_id) = item else { continue };
                 let Ok(attrs) =
                     db.function_with_body_attributes(FunctionWithBodyId::Free(*func_id))
                 else {
-                    return None;
+                    continue;
                 };
-                Some((*func_id, try_extract_test_config(db.upcast(), attrs).unwrap()?))
-            }));
+                if let Some(fixture) =
+                    try_extract_fixture_config(db.upcast(), attrs.clone()).unwrap()
+                {
+                    fixtures.push((*func_id, fixture));
+                } else if let Some(test) = try_extract_test_config(db.upcast(), attrs).unwrap() {
+                    tests.push((*func_id, test));
+                }
+            }
         }
     }
-    tests
+    (tests, fixtures)
 }
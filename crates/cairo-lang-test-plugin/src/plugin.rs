@@ -1,10 +1,13 @@
-use cairo_lang_defs::plugin::{MacroPlugin, PluginResult};
+use cairo_lang_defs::plugin::{MacroPlugin, PluginDiagnostic, PluginResult};
 use cairo_lang_syntax::attribute::structured::AttributeListStructurize;
-use cairo_lang_syntax::node::ast;
 use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 
-use super::{AVAILABLE_GAS_ATTR, IGNORE_ATTR, SHOULD_PANIC_ATTR, TEST_ATTR};
-use crate::test_config::try_extract_test_config;
+use super::{
+    AVAILABLE_GAS_ATTR, FIXTURE_ATTR, FUZZER_ATTR, IGNORE_ATTR, MAX_STEPS_ATTR, SETUP_ATTR,
+    SHOULD_PANIC_ATTR, TEST_ATTR, TIMEOUT_ATTR, USE_FIXTURE_ATTR,
+};
+use crate::test_config::{try_extract_fixture_config, try_extract_test_config};
 
 /// Plugin to create diagnostics for tests attributes.
 #[derive(Debug, Default)]
@@ -16,11 +19,21 @@ impl MacroPlugin for TestPlugin {
         PluginResult {
             code: None,
             diagnostics: if let ast::Item::FreeFunction(free_func_ast) = item_ast {
-                try_extract_test_config(db, free_func_ast.attributes(db).structurize(db)).err()
+                let attrs = free_func_ast.attributes(db).structurize(db);
+                match try_extract_fixture_config(db, attrs.clone()) {
+                    Ok(Some(_)) => check_fixture_parameters(db, &free_func_ast),
+                    Ok(None) => match try_extract_test_config(db, attrs) {
+                        Ok(Some(config)) => {
+                            check_test_parameters(db, &free_func_ast, config.fuzzer.is_some())
+                        }
+                        Ok(None) => vec![],
+                        Err(diagnostics) => diagnostics,
+                    },
+                    Err(diagnostics) => diagnostics,
+                }
             } else {
-                None
-            }
-            .unwrap_or_default(),
+                vec![]
+            },
             remove_original_item: false,
         }
     }
@@ -31,6 +44,54 @@ impl MacroPlugin for TestPlugin {
             AVAILABLE_GAS_ATTR.to_string(),
             SHOULD_PANIC_ATTR.to_string(),
             IGNORE_ATTR.to_string(),
+            FUZZER_ATTR.to_string(),
+            SETUP_ATTR.to_string(),
+            FIXTURE_ATTR.to_string(),
+            USE_FIXTURE_ATTR.to_string(),
+            MAX_STEPS_ATTR.to_string(),
+            TIMEOUT_ATTR.to_string(),
         ]
     }
 }
+
+/// Checks that a test has parameters if and only if it is a fuzz test.
+fn check_test_parameters(
+    db: &dyn SyntaxGroup,
+    func: &ast::FunctionWithBody,
+    is_fuzz_test: bool,
+) -> Vec<PluginDiagnostic> {
+    let params = func.declaration(db).signature(db).parameters(db);
+    let has_params = !params.elements(db).is_empty();
+    if has_params && !is_fuzz_test {
+        vec![PluginDiagnostic {
+            stable_ptr: params.stable_ptr().untyped(),
+            message: format!(
+                "Tests with parameters must have the `#[{FUZZER_ATTR}]` attribute, for the \
+                 parameters to be randomly generated."
+            ),
+        }]
+    } else if !has_params && is_fuzz_test {
+        vec![PluginDiagnostic {
+            stable_ptr: func.declaration(db).name(db).stable_ptr().untyped(),
+            message: "Fuzz tests must have parameters.".into(),
+        }]
+    } else {
+        vec![]
+    }
+}
+
+/// Checks that a fixture has no parameters.
+fn check_fixture_parameters(
+    db: &dyn SyntaxGroup,
+    func: &ast::FunctionWithBody,
+) -> Vec<PluginDiagnostic> {
+    let params = func.declaration(db).signature(db).parameters(db);
+    if params.elements(db).is_empty() {
+        vec![]
+    } else {
+        vec![PluginDiagnostic {
+            stable_ptr: params.stable_ptr().untyped(),
+            message: "Fixtures must not have parameters.".into(),
+        }]
+    }
+}
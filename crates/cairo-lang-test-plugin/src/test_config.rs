@@ -1,13 +1,18 @@
+use std::time::Duration;
+
 use cairo_felt::Felt252;
 use cairo_lang_defs::plugin::PluginDiagnostic;
 use cairo_lang_syntax::attribute::structured::{Attribute, AttributeArg, AttributeArgVariant};
-use cairo_lang_syntax::node::ast;
 use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 use cairo_lang_utils::OptionHelper;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
-use super::{AVAILABLE_GAS_ATTR, IGNORE_ATTR, SHOULD_PANIC_ATTR, TEST_ATTR};
+use super::{
+    AVAILABLE_GAS_ATTR, FIXTURE_ATTR, FUZZER_ATTR, IGNORE_ATTR, MAX_STEPS_ATTR, SETUP_ATTR,
+    SHOULD_PANIC_ATTR, TEST_ATTR, TIMEOUT_ATTR, USE_FIXTURE_ATTR,
+};
 
 /// Expectation for a panic case.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -16,6 +21,12 @@ pub enum PanicExpectation {
     Any,
     /// Accept only this specific vector of panics.
     Exact(Vec<Felt252>),
+    /// Accept only a panic with this message.
+    Message(String),
+    /// Accept only a panic with a message containing this string.
+    MessageSubstring(String),
+    /// Accept only a panic with a message starting with this string.
+    MessagePrefix(String),
 }
 
 /// Expectation for a result of a test.
@@ -27,6 +38,18 @@ pub enum TestExpectation {
     Panics(PanicExpectation),
 }
 
+/// The number of runs of a fuzz test, if not given explicitly.
+pub const DEFAULT_FUZZER_RUNS: usize = 256;
+
+/// The configuration for running a fuzz test - a test whose parameters are randomly generated.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FuzzerConfig {
+    /// The number of runs with different random inputs.
+    pub runs: usize,
+    /// The seed for generating the inputs, or None for a random seed.
+    pub seed: Option<u64>,
+}
+
 /// The configuration for running a single test.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct TestConfig {
@@ -36,6 +59,26 @@ pub struct TestConfig {
     pub expectation: TestExpectation,
     /// Should the test be ignored.
     pub ignored: bool,
+    /// The fuzzer configuration, for tests with parameters.
+    pub fuzzer: Option<FuzzerConfig>,
+    /// The fixture the test starts from. Extracted as written in the `use_fixture` attribute, and
+    /// replaced with the full path of the fixture function in a `TestCompilation`.
+    pub fixture: Option<String>,
+    /// The maximal number of steps of a run of the test, overriding the runner's default.
+    pub max_steps: Option<usize>,
+    /// The maximal duration of a run of the test, overriding the runner's default.
+    pub timeout: Option<Duration>,
+}
+
+/// The configuration for running a fixture - a function whose resulting Starknet state is used as
+/// the initial state of tests.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct FixtureConfig {
+    /// The amount of gas the fixture requested.
+    pub available_gas: Option<usize>,
+    /// Whether this is the setup fixture of its module - the fixture of the module's tests that do
+    /// not use another fixture.
+    pub is_setup: bool,
 }
 
 /// Extracts the configuration of a tests from attributes, or returns the diagnostics if the
@@ -48,6 +91,12 @@ pub fn try_extract_test_config(
     let ignore_attr = attrs.iter().find(|attr| attr.id.as_str() == IGNORE_ATTR);
     let available_gas_attr = attrs.iter().find(|attr| attr.id.as_str() == AVAILABLE_GAS_ATTR);
     let should_panic_attr = attrs.iter().find(|attr| attr.id.as_str() == SHOULD_PANIC_ATTR);
+    let fuzzer_attr = attrs.iter().find(|attr| attr.id.as_str() == FUZZER_ATTR);
+    let use_fixture_attr = attrs.iter().find(|attr| attr.id.as_str() == USE_FIXTURE_ATTR);
+    let max_steps_attr = attrs.iter().find(|attr| attr.id.as_str() == MAX_STEPS_ATTR);
+    let timeout_attr = attrs.iter().find(|attr| attr.id.as_str() == TIMEOUT_ATTR);
+    let is_fixture =
+        attrs.iter().any(|attr| [SETUP_ATTR, FIXTURE_ATTR].contains(&attr.id.as_str()));
     let mut diagnostics = vec![];
     if let Some(attr) = test_attr {
         if !attr.args.is_empty() {
@@ -57,7 +106,20 @@ pub fn try_extract_test_config(
             });
         }
     } else {
-        for attr in [ignore_attr, available_gas_attr, should_panic_attr].into_iter().flatten() {
+        // Fixtures may request gas as well.
+        let available_gas_attr = if is_fixture { None } else { available_gas_attr };
+        for attr in [
+            ignore_attr,
+            available_gas_attr,
+            should_panic_attr,
+            fuzzer_attr,
+            use_fixture_attr,
+            max_steps_attr,
+            timeout_attr,
+        ]
+        .into_iter()
+        .flatten()
+        {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: attr.id_stable_ptr.untyped(),
                 message: "Attribute should only appear on tests.".into(),
@@ -75,44 +137,47 @@ pub fn try_extract_test_config(
     } else {
         false
     };
-    let available_gas = if let Some(attr) = available_gas_attr {
-        if let [
-            AttributeArg {
-                variant: AttributeArgVariant::Unnamed { value: ast::Expr::Literal(literal), .. },
-                ..
-            },
-        ] = &attr.args[..]
-        {
-            literal.numeric_value(db).unwrap_or_default().to_usize()
-        } else {
-            diagnostics.push(PluginDiagnostic {
-                stable_ptr: attr.id_stable_ptr.untyped(),
-                message: "Attribute should have a single value argument.".into(),
-            });
-            None
-        }
-    } else {
-        None
-    };
-    let (should_panic, expected_panic_value) = if let Some(attr) = should_panic_attr {
+    let available_gas = extract_numeric_arg(db, available_gas_attr, &mut diagnostics);
+    let max_steps = extract_numeric_arg(db, max_steps_attr, &mut diagnostics);
+    let timeout = extract_numeric_arg(db, timeout_attr, &mut diagnostics)
+        .map(|seconds| Duration::from_secs(seconds as u64));
+    let panic_expectation = should_panic_attr.map(|attr| {
         if attr.args.is_empty() {
-            (true, None)
+            PanicExpectation::Any
         } else {
-            (
-                true,
-                extract_panic_values(db, attr).on_none(|| {
-                    diagnostics.push(PluginDiagnostic {
-                        stable_ptr: attr.args_stable_ptr.untyped(),
-                        message: "Expected panic must be of the form `expected: <tuple of \
-                                  felt252s>`."
-                            .into(),
-                    });
-                }),
-            )
+            extract_panic_expectation(db, attr).unwrap_or_else(|| {
+                diagnostics.push(PluginDiagnostic {
+                    stable_ptr: attr.args_stable_ptr.untyped(),
+                    message: "Expected panic must be of the form `expected: <tuple of felt252s>`, \
+                              `expected: \"<message>\"`, `expected_substring: \"<string>\"` or \
+                              `expected_prefix: \"<string>\"`."
+                        .into(),
+                });
+                PanicExpectation::Any
+            })
         }
-    } else {
-        (false, None)
-    };
+    });
+    let fuzzer = fuzzer_attr.map(|attr| {
+        extract_fuzzer_config(db, attr).unwrap_or_else(|| {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: attr.args_stable_ptr.untyped(),
+                message: "Expected fuzzer arguments of the form `runs: <number>, seed: <number>` \
+                          (both optional)."
+                    .into(),
+            });
+            FuzzerConfig { runs: DEFAULT_FUZZER_RUNS, seed: None }
+        })
+    });
+    let fixture = use_fixture_attr.and_then(|attr| {
+        extract_fixture_name(db, attr).on_none(|| {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: attr.args_stable_ptr.untyped(),
+                message: "Attribute should have a single argument - the name or the full path of \
+                          the fixture."
+                    .into(),
+            });
+        })
+    });
     if !diagnostics.is_empty() {
         return Err(diagnostics);
     }
@@ -121,30 +186,147 @@ pub fn try_extract_test_config(
     } else {
         Some(TestConfig {
             available_gas,
-            expectation: if should_panic {
-                TestExpectation::Panics(if let Some(values) = expected_panic_value {
-                    PanicExpectation::Exact(values)
-                } else {
-                    PanicExpectation::Any
-                })
-            } else {
-                TestExpectation::Success
-            },
+            expectation: panic_expectation
+                .map_or(TestExpectation::Success, TestExpectation::Panics),
             ignored,
+            fuzzer,
+            fixture,
+            max_steps,
+            timeout,
         })
     })
 }
 
-/// Tries to extract the relevant expected panic values.
-fn extract_panic_values(db: &dyn SyntaxGroup, attr: &Attribute) -> Option<Vec<Felt252>> {
-    let [AttributeArg { variant: AttributeArgVariant::Named { name, value: panics, .. }, .. }] =
-        &attr.args[..]
+/// Extracts the configuration of a fixture from attributes, or returns the diagnostics if the
+/// attributes are set illegally.
+pub fn try_extract_fixture_config(
+    db: &dyn SyntaxGroup,
+    attrs: Vec<Attribute>,
+) -> Result<Option<FixtureConfig>, Vec<PluginDiagnostic>> {
+    let setup_attr = attrs.iter().find(|attr| attr.id.as_str() == SETUP_ATTR);
+    let fixture_attr = attrs.iter().find(|attr| attr.id.as_str() == FIXTURE_ATTR);
+    let Some(attr) = setup_attr.or(fixture_attr) else {
+        return Ok(None);
+    };
+    let mut diagnostics = vec![];
+    if setup_attr.is_some() && fixture_attr.is_some() {
+        diagnostics.push(PluginDiagnostic {
+            stable_ptr: attr.id_stable_ptr.untyped(),
+            message: format!(
+                "A function can not have both the `#[{SETUP_ATTR}]` and `#[{FIXTURE_ATTR}]` \
+                 attributes."
+            ),
+        });
+    }
+    if attrs.iter().any(|attr| attr.id.as_str() == TEST_ATTR) {
+        diagnostics.push(PluginDiagnostic {
+            stable_ptr: attr.id_stable_ptr.untyped(),
+            message: "Fixtures can not be tests.".into(),
+        });
+    }
+    for attr in [setup_attr, fixture_attr].into_iter().flatten() {
+        if !attr.args.is_empty() {
+            diagnostics.push(PluginDiagnostic {
+                stable_ptr: attr.id_stable_ptr.untyped(),
+                message: "Attribute should not have arguments.".into(),
+            });
+        }
+    }
+    let available_gas_attr = attrs.iter().find(|attr| attr.id.as_str() == AVAILABLE_GAS_ATTR);
+    let available_gas = extract_numeric_arg(db, available_gas_attr, &mut diagnostics);
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+    Ok(Some(FixtureConfig { available_gas, is_setup: setup_attr.is_some() }))
+}
+
+/// Extracts the single numeric argument of an attribute (e.g. the requested amount of gas), if the
+/// attribute is given.
+fn extract_numeric_arg(
+    db: &dyn SyntaxGroup,
+    attr: Option<&Attribute>,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Option<usize> {
+    let attr = attr?;
+    if let [
+        AttributeArg {
+            variant: AttributeArgVariant::Unnamed { value: ast::Expr::Literal(literal), .. },
+            ..
+        },
+    ] = &attr.args[..]
+    {
+        literal.numeric_value(db).unwrap_or_default().to_usize()
+    } else {
+        diagnostics.push(PluginDiagnostic {
+            stable_ptr: attr.id_stable_ptr.untyped(),
+            message: "Attribute should have a single value argument.".into(),
+        });
+        None
+    }
+}
+
+/// Tries to extract the name of the used fixture from its attribute.
+fn extract_fixture_name(db: &dyn SyntaxGroup, attr: &Attribute) -> Option<String> {
+    let [
+        AttributeArg {
+            variant: AttributeArgVariant::Unnamed { value: ast::Expr::Path(path), .. },
+            ..
+        },
+    ] = &attr.args[..]
     else {
         return None;
     };
-    if name != "expected" {
+    Some(path.as_syntax_node().get_text_without_trivia(db))
+}
+
+/// Tries to extract the fuzzer configuration from its attribute.
+fn extract_fuzzer_config(db: &dyn SyntaxGroup, attr: &Attribute) -> Option<FuzzerConfig> {
+    let mut config = FuzzerConfig { runs: DEFAULT_FUZZER_RUNS, seed: None };
+    let mut seen_names = vec![];
+    for arg in &attr.args {
+        let AttributeArgVariant::Named { name, value: ast::Expr::Literal(literal), .. } =
+            &arg.variant
+        else {
+            return None;
+        };
+        if seen_names.contains(&name.as_str()) {
+            return None;
+        }
+        seen_names.push(name.as_str());
+        let value = literal.numeric_value(db)?;
+        match name.as_str() {
+            "runs" => config.runs = value.to_usize().filter(|runs| *runs > 0)?,
+            "seed" => config.seed = Some(value.to_u64()?),
+            _ => return None,
+        }
+    }
+    Some(config)
+}
+
+/// Tries to extract the expected panic from the arguments of the `should_panic` attribute.
+fn extract_panic_expectation(db: &dyn SyntaxGroup, attr: &Attribute) -> Option<PanicExpectation> {
+    let [AttributeArg { variant: AttributeArgVariant::Named { name, value, .. }, .. }] =
+        &attr.args[..]
+    else {
         return None;
+    };
+    let message = || {
+        let ast::Expr::String(message) = value else { return None };
+        message.string_value(db)
+    };
+    match name.as_str() {
+        "expected" => match value {
+            ast::Expr::String(_) => Some(PanicExpectation::Message(message()?)),
+            _ => Some(PanicExpectation::Exact(extract_panic_values(db, value)?)),
+        },
+        "expected_substring" => Some(PanicExpectation::MessageSubstring(message()?)),
+        "expected_prefix" => Some(PanicExpectation::MessagePrefix(message()?)),
+        _ => None,
     }
+}
+
+/// Tries to extract the expected panic values from a tuple expression.
+fn extract_panic_values(db: &dyn SyntaxGroup, panics: &ast::Expr) -> Option<Vec<Felt252>> {
     let ast::Expr::Tuple(panics) = panics else { return None };
     panics
         .expressions(db)
@@ -0,0 +1,61 @@
+//! Line and branch coverage reports of test runs, in the LCOV format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use cairo_lang_runner::StatementCoverage;
+use cairo_lang_test_plugin::CodeLocation;
+
+/// The coverage of a single file.
+#[derive(Default)]
+struct FileCoverage {
+    /// The number of executions of each line with code, by line number.
+    lines: BTreeMap<usize, usize>,
+    /// The branches of the file, as (line, statement index, branch index, times taken), where
+    /// times taken is None if the branching statement was never executed.
+    branches: Vec<(usize, usize, usize, Option<usize>)>,
+}
+
+/// Returns an LCOV report of `coverage`, given the locations of the statements of the program.
+/// A line is considered executed as many times as its most executed statement. Statements with no
+/// location in a file on disk are not reported.
+pub fn lcov_report(
+    coverage: &StatementCoverage,
+    statements_locations: &[Option<CodeLocation>],
+) -> String {
+    let location = |idx: usize| statements_locations.get(idx).and_then(Option::as_ref);
+    let mut files = BTreeMap::<&str, FileCoverage>::new();
+    for (idx, hits) in coverage.statements.iter() {
+        let Some(location) = location(*idx) else { continue };
+        let line_hits =
+            files.entry(&location.file).or_default().lines.entry(location.line).or_default();
+        *line_hits = (*line_hits).max(*hits);
+    }
+    for (idx, branch_hits) in coverage.branches.iter() {
+        let Some(location) = location(*idx) else { continue };
+        let executed = coverage.statements.get(idx).is_some_and(|hits| *hits > 0);
+        let file = files.entry(&location.file).or_default();
+        for (branch, hits) in branch_hits.iter().enumerate() {
+            file.branches.push((location.line, *idx, branch, executed.then_some(*hits)));
+        }
+    }
+
+    let mut report = String::new();
+    for (path, file) in files {
+        writeln!(report, "SF:{path}").unwrap();
+        for (line, hits) in &file.lines {
+            writeln!(report, "DA:{line},{hits}").unwrap();
+        }
+        writeln!(report, "LF:{}", file.lines.len()).unwrap();
+        writeln!(report, "LH:{}", file.lines.values().filter(|hits| **hits > 0).count()).unwrap();
+        for (line, block, branch, taken) in &file.branches {
+            let taken = taken.map_or_else(|| "-".into(), |taken| taken.to_string());
+            writeln!(report, "BRDA:{line},{block},{branch},{taken}").unwrap();
+        }
+        writeln!(report, "BRF:{}", file.branches.len()).unwrap();
+        let branches_hit = file.branches.iter().filter(|(.., taken)| taken.unwrap_or(0) > 0);
+        writeln!(report, "BRH:{}", branches_hit.count()).unwrap();
+        writeln!(report, "end_of_record").unwrap();
+    }
+    report
+}
@@ -0,0 +1,68 @@
+//! Selection of the tests to run by their names.
+
+/// A selection of tests by their full names.
+///
+/// A pattern containing `*` (any sequence of characters) or `?` (any single character) is a glob
+/// pattern, matched against the full name of a test. Other patterns match the names containing
+/// them, or only the names equal to them in `exact` mode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestFilter {
+    /// Select only the tests matching this pattern. An empty pattern matches all the tests.
+    pub pattern: String,
+    /// Whether non-glob patterns (including the skip patterns) must be equal to the test names.
+    pub exact: bool,
+    /// Skip the tests matching any of these patterns.
+    pub skip: Vec<String>,
+}
+impl TestFilter {
+    /// Returns whether the test with the given full name is selected.
+    pub fn matches(&self, name: &str) -> bool {
+        (self.pattern.is_empty() || self.pattern_matches(&self.pattern, name))
+            && !self.skip.iter().any(|pattern| self.pattern_matches(pattern, name))
+    }
+
+    /// Returns whether a single pattern matches the given test name.
+    fn pattern_matches(&self, pattern: &str, name: &str) -> bool {
+        if pattern.contains(['*', '?']) {
+            glob_matches(pattern.as_bytes(), name.as_bytes())
+        } else if self.exact {
+            name == pattern
+        } else {
+            name.contains(pattern)
+        }
+    }
+}
+impl From<&str> for TestFilter {
+    /// Returns a filter of the tests containing `pattern`.
+    fn from(pattern: &str) -> Self {
+        Self { pattern: pattern.into(), ..Self::default() }
+    }
+}
+
+/// Returns whether `text` fully matches the glob `pattern`.
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut pattern_idx, mut text_idx) = (0, 0);
+    // The position after the last `*` seen, and the position in the text it currently matches up
+    // to, for backtracking when the rest of the pattern does not match.
+    let mut backtrack = None;
+    while text_idx < text.len() {
+        match pattern.get(pattern_idx) {
+            Some(b'*') => {
+                pattern_idx += 1;
+                backtrack = Some((pattern_idx, text_idx));
+            }
+            Some(c) if *c == b'?' || *c == text[text_idx] => {
+                pattern_idx += 1;
+                text_idx += 1;
+            }
+            _ => {
+                // Let the last `*` match one more character.
+                let Some((star_pattern_idx, star_text_idx)) = backtrack else { return false };
+                pattern_idx = star_pattern_idx;
+                text_idx = star_text_idx + 1;
+                backtrack = Some((star_pattern_idx, text_idx));
+            }
+        }
+    }
+    pattern[pattern_idx..].iter().all(|c| *c == b'*')
+}
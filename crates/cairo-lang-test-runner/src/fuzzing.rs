@@ -0,0 +1,373 @@
+//! Fuzzing of tests with parameters: running them with random inputs, and shrinking the inputs of
+//! failing runs to a minimal counterexample.
+
+use anyhow::{bail, Result};
+use cairo_felt::{Felt252, PRIME_STR};
+use cairo_lang_runner::{Arg, SierraCasmRunner};
+use cairo_lang_sierra::extensions::array::ArrayType;
+use cairo_lang_sierra::extensions::enm::EnumType;
+use cairo_lang_sierra::extensions::felt252::Felt252Type;
+use cairo_lang_sierra::extensions::int::signed::{Sint16Type, Sint32Type, Sint64Type, Sint8Type};
+use cairo_lang_sierra::extensions::int::signed128::Sint128Type;
+use cairo_lang_sierra::extensions::int::unsigned::{Uint16Type, Uint32Type, Uint64Type, Uint8Type};
+use cairo_lang_sierra::extensions::int::unsigned128::Uint128Type;
+use cairo_lang_sierra::extensions::snapshot::SnapshotType;
+use cairo_lang_sierra::extensions::structure::StructType;
+use cairo_lang_sierra::extensions::NamedType;
+use cairo_lang_sierra::ids::ConcreteTypeId;
+use cairo_lang_sierra::program::{Function, GenericArg};
+use cairo_lang_test_plugin::test_config::FuzzerConfig;
+use itertools::{chain, Itertools};
+use num_bigint::{BigInt, BigUint};
+use num_traits::{Num, One, Signed, Zero};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{TestFailure, TestRunResult, TestStatus};
+
+/// The maximal length of generated arrays.
+const MAX_ARRAY_LEN: usize = 32;
+/// The maximal number of runs made while shrinking a counterexample.
+const MAX_SHRINK_RUNS: usize = 1000;
+
+/// The type of a value that can be generated for a single parameter, or a single array element.
+#[derive(Clone, Debug)]
+enum ScalarType {
+    /// A single-cell numeric value in the range `min..=max`. Felts are given by their signed
+    /// representation.
+    Int { min: BigInt, max: BigInt },
+    /// A `bool`.
+    Bool,
+    /// A `u256`, given in two cells.
+    U256,
+}
+impl ScalarType {
+    /// Returns the range of the values of the type.
+    fn range(&self) -> (BigInt, BigInt) {
+        match self {
+            ScalarType::Int { min, max } => (min.clone(), max.clone()),
+            ScalarType::Bool => (BigInt::zero(), BigInt::one()),
+            ScalarType::U256 => (BigInt::zero(), (BigInt::one() << 256_usize) - 1_u8),
+        }
+    }
+
+    /// Generates a random value, preferring edge values every once in a while.
+    fn generate(&self, rng: &mut StdRng) -> BigInt {
+        let (min, max) = self.range();
+        if rng.gen_ratio(1, 4) {
+            let edges = [min.clone(), max.clone(), BigInt::zero(), BigInt::one(), -BigInt::one()];
+            let edges =
+                edges.into_iter().filter(|value| &min <= value && value <= &max).collect_vec();
+            return edges[rng.gen_range(0..edges.len())].clone();
+        }
+        // Taking the remainder of 320 random bits gives a practically uniform value in the range.
+        let mut bytes = [0_u8; 40];
+        rng.fill(&mut bytes[..]);
+        let random = BigUint::from_bytes_be(&bytes);
+        let span = (&max - &min + 1_u8).to_biguint().unwrap();
+        min + BigInt::from(random % span)
+    }
+
+    /// Appends the cells of `value` to `cells`.
+    fn encode(&self, value: &BigInt, cells: &mut Vec<Felt252>) {
+        match self {
+            ScalarType::Int { .. } | ScalarType::Bool => cells.push(Felt252::from(value.clone())),
+            ScalarType::U256 => {
+                let low_mask = (BigInt::one() << 128) - 1;
+                cells.push(Felt252::from(value & low_mask));
+                cells.push(Felt252::from(value >> 128));
+            }
+        }
+    }
+
+    /// Formats a value of the type.
+    fn format(&self, value: &BigInt) -> String {
+        match self {
+            ScalarType::Bool => (!value.is_zero()).to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// The type of a parameter of a fuzz test.
+#[derive(Clone, Debug)]
+enum ParamType {
+    Scalar(ScalarType),
+    /// An array or a span.
+    Array(ScalarType),
+}
+
+/// The value of a parameter of a fuzz test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ParamValue {
+    Scalar(BigInt),
+    Array(Vec<BigInt>),
+}
+
+/// Runs a fuzz test `config.runs` times with random inputs, using `run` to run the test with given
+/// args. If a run fails, its inputs are shrunk, and the failure of the minimal counterexample is
+/// returned.
+pub(crate) fn run_fuzz_test(
+    runner: &SierraCasmRunner,
+    func: &Function,
+    config: &FuzzerConfig,
+    mut run: impl FnMut(&[Arg]) -> Result<TestRunResult>,
+) -> Result<TestRunResult> {
+    let param_types =
+        runner.find_arg_types(func).map(|ty| param_type(runner, ty)).collect::<Result<Vec<_>>>()?;
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut max_gas_usage = None;
+    let mut max_steps = None;
+    for _ in 0..config.runs {
+        let values = param_types.iter().map(|ty| generate(ty, &mut rng)).collect_vec();
+        let result = run(&to_args(&param_types, &values))?;
+        max_gas_usage = max_gas_usage.max(result.gas_usage);
+        max_steps = max_steps.max(result.steps);
+        if let TestStatus::Fail(failure) = result.status {
+            let (values, failure) = shrink(&param_types, values, failure, &mut run)?;
+            return Ok(TestRunResult {
+                status: TestStatus::Fail(TestFailure::Fuzzing {
+                    counterexample: format_values(&param_types, &values),
+                    seed,
+                    failure: Box::new(failure),
+                }),
+                gas_usage: result.gas_usage,
+                steps: result.steps,
+                profile: result.profile,
+            });
+        }
+    }
+    Ok(TestRunResult {
+        status: TestStatus::Success,
+        gas_usage: max_gas_usage,
+        steps: max_steps,
+        profile: None,
+    })
+}
+
+/// Returns the fuzzed parameter type of the Sierra type `ty`.
+fn param_type(runner: &SierraCasmRunner, ty: &ConcreteTypeId) -> Result<ParamType> {
+    let long_id = &runner.get_info(ty).long_id;
+    if long_id.generic_id == ArrayType::ID {
+        let [GenericArg::Type(element_ty)] = &long_id.generic_args[..] else { unreachable!() };
+        return Ok(ParamType::Array(scalar_type(runner, element_ty)?));
+    }
+    if long_id.generic_id == StructType::ID && type_name(ty).starts_with("core::array::Span::<") {
+        // A span is a struct holding a snapshot of an array.
+        let [_, GenericArg::Type(snapshot_ty)] = &long_id.generic_args[..] else { unreachable!() };
+        let snapshot_long_id = &runner.get_info(snapshot_ty).long_id;
+        if snapshot_long_id.generic_id == SnapshotType::ID {
+            let [GenericArg::Type(array_ty)] = &snapshot_long_id.generic_args[..] else {
+                unreachable!()
+            };
+            return param_type(runner, array_ty);
+        }
+    }
+    Ok(ParamType::Scalar(scalar_type(runner, ty)?))
+}
+
+/// Returns the fuzzed scalar type of the Sierra type `ty`.
+fn scalar_type(runner: &SierraCasmRunner, ty: &ConcreteTypeId) -> Result<ScalarType> {
+    let long_id = &runner.get_info(ty).long_id;
+    let generic_id = &long_id.generic_id;
+    let unsigned_bits = [
+        (Uint8Type::ID, 8_usize),
+        (Uint16Type::ID, 16),
+        (Uint32Type::ID, 32),
+        (Uint64Type::ID, 64),
+        (Uint128Type::ID, 128),
+    ];
+    let signed_bits = [
+        (Sint8Type::ID, 8_usize),
+        (Sint16Type::ID, 16),
+        (Sint32Type::ID, 32),
+        (Sint64Type::ID, 64),
+        (Sint128Type::ID, 128),
+    ];
+    if *generic_id == Felt252Type::ID {
+        let half_prime = BigInt::from_str_radix(&PRIME_STR[2..], 16).unwrap() / 2_u8;
+        Ok(ScalarType::Int { min: -half_prime.clone(), max: half_prime })
+    } else if let Some((_, bits)) = unsigned_bits.iter().find(|(id, _)| id == generic_id) {
+        Ok(ScalarType::Int { min: BigInt::zero(), max: (BigInt::one() << bits) - 1 })
+    } else if let Some((_, bits)) = signed_bits.iter().find(|(id, _)| id == generic_id) {
+        let half = BigInt::one() << (bits - 1);
+        Ok(ScalarType::Int { min: -half.clone(), max: half - 1 })
+    } else if *generic_id == EnumType::ID && type_name(ty) == "core::bool" {
+        Ok(ScalarType::Bool)
+    } else if *generic_id == StructType::ID && type_name(ty) == "core::integer::u256" {
+        Ok(ScalarType::U256)
+    } else {
+        bail!(
+            "Unsupported fuzz test parameter type `{}`. Supported types are integers, felt252, \
+             bool, and arrays and spans of those.",
+            type_name(ty)
+        )
+    }
+}
+
+/// Returns the debug name of a type.
+fn type_name(ty: &ConcreteTypeId) -> String {
+    ty.debug_name.as_ref().map_or_else(|| ty.to_string(), |name| name.to_string())
+}
+
+/// Generates a random value for a parameter.
+fn generate(ty: &ParamType, rng: &mut StdRng) -> ParamValue {
+    match ty {
+        ParamType::Scalar(ty) => ParamValue::Scalar(ty.generate(rng)),
+        ParamType::Array(ty) => {
+            let len = if rng.gen_ratio(1, 8) { 0 } else { rng.gen_range(0..=MAX_ARRAY_LEN) };
+            ParamValue::Array((0..len).map(|_| ty.generate(rng)).collect())
+        }
+    }
+}
+
+/// Returns the args of a run with the given parameter values.
+fn to_args(types: &[ParamType], values: &[ParamValue]) -> Vec<Arg> {
+    let mut args = vec![];
+    for (ty, value) in types.iter().zip_eq(values) {
+        match (ty, value) {
+            (ParamType::Scalar(ty), ParamValue::Scalar(value)) => {
+                let mut cells = vec![];
+                ty.encode(value, &mut cells);
+                args.extend(cells.into_iter().map(Arg::Value));
+            }
+            (ParamType::Array(ty), ParamValue::Array(elements)) => {
+                let mut cells = vec![];
+                for element in elements {
+                    ty.encode(element, &mut cells);
+                }
+                args.push(Arg::Array(cells));
+            }
+            _ => unreachable!("Parameter value does not match its type."),
+        }
+    }
+    args
+}
+
+/// Formats parameter values as a tuple.
+fn format_values(types: &[ParamType], values: &[ParamValue]) -> String {
+    let formatted = types.iter().zip_eq(values).map(|(ty, value)| match (ty, value) {
+        (ParamType::Scalar(ty), ParamValue::Scalar(value)) => ty.format(value),
+        (ParamType::Array(ty), ParamValue::Array(elements)) => {
+            format!("[{}]", elements.iter().map(|element| ty.format(element)).join(", "))
+        }
+        _ => unreachable!("Parameter value does not match its type."),
+    });
+    format!("({})", formatted.format(", "))
+}
+
+/// Shrinks the values of a failing run, as long as the test keeps failing. Returns the minimal
+/// values found along with their failure.
+fn shrink(
+    types: &[ParamType],
+    values: Vec<ParamValue>,
+    failure: TestFailure,
+    run: &mut impl FnMut(&[Arg]) -> Result<TestRunResult>,
+) -> Result<(Vec<ParamValue>, TestFailure)> {
+    let mut shrinker = Shrinker { types, run, runs_left: MAX_SHRINK_RUNS, values, failure };
+    while shrinker.shorten_arrays()? || shrinker.minimize_scalars()? {}
+    Ok((shrinker.values, shrinker.failure))
+}
+
+/// The state of shrinking the values of a failing run.
+struct Shrinker<'a, F: FnMut(&[Arg]) -> Result<TestRunResult>> {
+    types: &'a [ParamType],
+    run: &'a mut F,
+    /// The number of runs left for shrinking.
+    runs_left: usize,
+    /// The minimal failing values found so far.
+    values: Vec<ParamValue>,
+    /// The failure of the run with `values`.
+    failure: TestFailure,
+}
+impl<F: FnMut(&[Arg]) -> Result<TestRunResult>> Shrinker<'_, F> {
+    /// Runs the test with `candidate` values, and keeps them if the test fails. Returns whether it
+    /// failed.
+    fn try_candidate(&mut self, candidate: Vec<ParamValue>) -> Result<bool> {
+        if self.runs_left == 0 {
+            return Ok(false);
+        }
+        self.runs_left -= 1;
+        let TestStatus::Fail(failure) = (self.run)(&to_args(self.types, &candidate))?.status else {
+            return Ok(false);
+        };
+        self.values = candidate;
+        self.failure = failure;
+        Ok(true)
+    }
+
+    /// Tries to remove elements from the arrays. Returns whether any array was shortened.
+    fn shorten_arrays(&mut self) -> Result<bool> {
+        for i in 0..self.values.len() {
+            let ParamValue::Array(elements) = &self.values[i] else { continue };
+            let half = elements.len() / 2;
+            let shortened = chain!(
+                [vec![], elements[..half].to_vec(), elements[half..].to_vec()],
+                (0..elements.len()).map(|j| {
+                    let mut shortened = elements.clone();
+                    shortened.remove(j);
+                    shortened
+                })
+            )
+            .filter(|shortened| shortened.len() < elements.len())
+            .unique()
+            .collect_vec();
+            for shortened in shortened {
+                let mut candidate = self.values.clone();
+                candidate[i] = ParamValue::Array(shortened);
+                if self.try_candidate(candidate)? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Tries to bring each of the scalars (including array elements) closer to zero, using a
+    /// binary search on its distance from zero. Returns whether any scalar was changed.
+    fn minimize_scalars(&mut self) -> Result<bool> {
+        let mut changed = false;
+        for i in 0..self.values.len() {
+            let len = match &self.values[i] {
+                ParamValue::Scalar(_) => 1,
+                ParamValue::Array(elements) => elements.len(),
+            };
+            for j in 0..len {
+                let value = match &self.values[i] {
+                    ParamValue::Scalar(value) => value.clone(),
+                    ParamValue::Array(elements) => elements[j].clone(),
+                };
+                let with_value = |values: &[ParamValue], value: BigInt| {
+                    let mut candidate = values.to_vec();
+                    match &mut candidate[i] {
+                        ParamValue::Scalar(scalar) => *scalar = value,
+                        ParamValue::Array(elements) => elements[j] = value,
+                    }
+                    candidate
+                };
+                // The test passes with distance `passing` from zero, and fails with `failing`.
+                let mut passing = BigInt::zero();
+                let mut failing = value.abs();
+                if failing.is_zero()
+                    || self.try_candidate(with_value(&self.values, passing.clone()))?
+                {
+                    changed |= !failing.is_zero();
+                    continue;
+                }
+                while &failing - &passing > BigInt::one() {
+                    let middle: BigInt = (&passing + &failing) / 2_u8;
+                    let candidate_value =
+                        if value.is_negative() { -middle.clone() } else { middle.clone() };
+                    if self.try_candidate(with_value(&self.values, candidate_value))? {
+                        failing = middle;
+                        changed = true;
+                    } else {
+                        passing = middle;
+                    }
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,125 @@
+//! Gas snapshots - files recording the gas usage and the number of steps of each test, used for
+//! detecting gas regressions.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{TestStatus, TestsSummary};
+
+/// How to use a gas snapshot file in a test run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GasSnapshotMode {
+    /// Write the gas usage of the passing tests to the file. The entries of tests that were not
+    /// run are kept.
+    Write(PathBuf),
+    /// Compare the gas usage of the passing tests with the file, failing if the gas usage of a
+    /// test grew by more than `tolerance` percent. With `CAIRO_FIX_TESTS=1` the file is
+    /// updated instead, as in [GasSnapshotMode::Write].
+    Check { path: PathBuf, tolerance: f64 },
+}
+
+/// The recorded resources of a single test.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSnapshotEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<i64>,
+    pub steps: usize,
+}
+
+/// A gas snapshot, mapping the full paths of tests to their recorded resources.
+pub type GasSnapshot = BTreeMap<String, GasSnapshotEntry>;
+
+/// Returns the gas snapshot of the passing tests of a run.
+pub fn gas_snapshot(summary: &TestsSummary) -> GasSnapshot {
+    summary
+        .results
+        .iter()
+        .filter(|result| result.status == TestStatus::Success)
+        .filter_map(|result| {
+            Some((
+                result.name.clone(),
+                GasSnapshotEntry { gas: result.gas_usage, steps: result.steps? },
+            ))
+        })
+        .collect()
+}
+
+/// Handles the gas snapshot of a run according to `mode`.
+pub fn handle_gas_snapshot(mode: &GasSnapshotMode, summary: &TestsSummary) -> Result<()> {
+    let snapshot = gas_snapshot(summary);
+    match mode {
+        GasSnapshotMode::Write(path) => update_gas_snapshot(path, snapshot),
+        GasSnapshotMode::Check { path, tolerance } => {
+            if std::env::var("CAIRO_FIX_TESTS") == Ok("1".into()) {
+                return update_gas_snapshot(path, snapshot);
+            }
+            let expected = read_gas_snapshot(path)?;
+            let regressions = find_gas_regressions(&expected, &snapshot, *tolerance);
+            if !regressions.is_empty() {
+                bail!(
+                    "gas snapshot check failed, the gas usage of {} tests grew by more than \
+                     {tolerance}% (run with CAIRO_FIX_TESTS=1 to update {}):\n{regressions}",
+                    regressions.lines().count(),
+                    path.display()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Reads a gas snapshot from a file.
+fn read_gas_snapshot(path: &Path) -> Result<GasSnapshot> {
+    serde_json::from_str(
+        &fs::read_to_string(path).with_context(|| format!("Failed to read {}.", path.display()))?,
+    )
+    .with_context(|| format!("Failed to parse the gas snapshot {}.", path.display()))
+}
+
+/// Updates the gas snapshot file with the entries of `snapshot`, keeping the entries of the tests
+/// that are not in it (e.g., as they were filtered out of the run). Creates the file if missing.
+fn update_gas_snapshot(path: &Path, snapshot: GasSnapshot) -> Result<()> {
+    let mut updated = if path.exists() { read_gas_snapshot(path)? } else { GasSnapshot::new() };
+    updated.extend(snapshot);
+    write_gas_snapshot(path, &updated)
+}
+
+/// Writes a gas snapshot to a file.
+fn write_gas_snapshot(path: &Path, snapshot: &GasSnapshot) -> Result<()> {
+    let mut content = serde_json::to_string_pretty(snapshot)?;
+    content.push('\n');
+    fs::write(path, content).with_context(|| format!("Failed to write {}.", path.display()))
+}
+
+/// Returns a description of the tests whose gas usage in `actual` grew by more than `tolerance`
+/// percent compared to `expected`, one per line. Tests missing from either snapshot are ignored.
+pub fn find_gas_regressions(
+    expected: &GasSnapshot,
+    actual: &GasSnapshot,
+    tolerance: f64,
+) -> String {
+    let mut regressions = String::new();
+    for (name, entry) in actual {
+        let Some(expected_entry) = expected.get(name) else { continue };
+        let (Some(expected_gas), Some(gas)) = (expected_entry.gas, entry.gas) else { continue };
+        if gas as f64 > expected_gas as f64 * (1.0 + tolerance / 100.0) {
+            let change = if expected_gas == 0 {
+                String::new()
+            } else {
+                format!(" (+{:.2}%)", (gas - expected_gas) as f64 * 100.0 / expected_gas as f64)
+            };
+            writeln!(
+                regressions,
+                "    {name}: gas {expected_gas} -> {gas}{change}, steps {} -> {}",
+                expected_entry.steps, entry.steps
+            )
+            .unwrap();
+        }
+    }
+    regressions
+}
@@ -1,5 +1,8 @@
-use std::path::Path;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
 use cairo_felt::Felt252;
@@ -7,27 +10,48 @@ use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_compiler::project::setup_project;
 use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
-use cairo_lang_filesystem::ids::CrateId;
-use cairo_lang_runner::short_string::as_cairo_short_string;
-use cairo_lang_runner::{RunResultValue, SierraCasmRunner};
-use cairo_lang_sierra::extensions::gas::CostTokenType;
-use cairo_lang_sierra::ids::FunctionId;
-use cairo_lang_sierra::program::Program;
+use cairo_lang_filesystem::db::FilesGroupEx;
+use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_filesystem::ids::{CrateId, FlagId};
+use cairo_lang_runner::short_string::{as_cairo_byte_array_panic, as_cairo_short_string};
+use cairo_lang_runner::{
+    collect_enum_variant_names, Arg, EnumVariantNames, ProfilingInfo, RunLimits, RunResultValue,
+    RunnerError, SierraCasmRunner, StarknetState, StatementCoverage, Value,
+};
+use cairo_lang_sierra::program::Function;
 use cairo_lang_sierra_to_casm::metadata::MetadataComputationConfig;
-use cairo_lang_starknet::contract::ContractInfo;
 use cairo_lang_starknet::inline_macros::selector::SelectorMacro;
 use cairo_lang_starknet::plugin::StarkNetPlugin;
 use cairo_lang_test_plugin::test_config::{PanicExpectation, TestExpectation};
-use cairo_lang_test_plugin::{compile_test_prepared_db, TestCompilation, TestConfig, TestPlugin};
+use cairo_lang_test_plugin::{
+    compile_test_prepared_db, FixtureConfig, TestCompilation, TestConfig, TestPlugin,
+};
 use cairo_lang_utils::casts::IntoOrPanic;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use colored::Colorize;
+use coverage::lcov_report;
+use fuzzing::run_fuzz_test;
+use gas_snapshot::handle_gas_snapshot;
 use itertools::Itertools;
+use list::list_tests;
 use num_traits::ToPrimitive;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
+mod coverage;
+mod filter;
+mod fuzzing;
+mod gas_snapshot;
+mod list;
+mod reporter;
 #[cfg(test)]
 mod test;
+mod watch;
+
+pub use filter::TestFilter;
+pub use gas_snapshot::{
+    find_gas_regressions, gas_snapshot, GasSnapshot, GasSnapshotEntry, GasSnapshotMode,
+};
+pub use reporter::{JsonReporter, JunitReporter, PrettyReporter, TestReporter};
 
 /// Compile and run tests.
 pub struct TestRunner {
@@ -41,18 +65,32 @@ impl TestRunner {
     /// # Arguments
     ///
     /// * `path` - The path to compile and run its tests
-    /// * `filter` - Run only tests containing the filter string
+    /// * `filter` - Run only tests selected by the filter
     /// * `include_ignored` - Include ignored tests as well
     /// * `ignored` - Run ignored tests only
     /// * `starknet` - Add the starknet plugin to run the tests
     pub fn new(path: &Path, starknet: bool, config: TestRunConfig) -> Result<Self> {
-        let compiler = TestCompiler::try_new(path, starknet)?;
+        let mut compiler = TestCompiler::try_new(path, starknet)?;
+        if config.coverage_path.is_some() {
+            // The coverage report is built from the locations of the executed statements.
+            let add_statement_locations_flag_id =
+                FlagId::new(&compiler.db, "add_statement_locations");
+            compiler.db.set_flag(
+                add_statement_locations_flag_id,
+                Some(Arc::new(Flag::AddStatementLocations(true))),
+            );
+        }
         Ok(Self { compiler, config })
     }
 
     /// Runs the tests and process the results for a summary.
+    ///
+    /// Returns the summary of the run if all the tests passed, or None in `list` mode, where the
+    /// tests are not run. Returns an error if any of the tests failed.
     pub fn run(&self) -> Result<Option<TestsSummary>> {
-        let runner = CompiledTestRunner::new(self.compiler.build()?, self.config.clone());
+        let mut runner = CompiledTestRunner::new(self.compiler.build()?, self.config.clone());
+        runner.enum_variant_names =
+            collect_enum_variant_names(&self.compiler.db, &self.compiler.main_crate_ids);
         runner.run()
     }
 }
@@ -60,6 +98,8 @@ impl TestRunner {
 pub struct CompiledTestRunner {
     pub compiled: TestCompilation,
     pub config: TestRunConfig,
+    /// The variant names of the enums, for displaying the values returned by tests.
+    pub enum_variant_names: EnumVariantNames,
 }
 
 impl CompiledTestRunner {
@@ -70,61 +110,55 @@ impl CompiledTestRunner {
     /// * `compiled` - The compiled tests to run
     /// * `config` - Test run configuration
     pub fn new(compiled: TestCompilation, config: TestRunConfig) -> Self {
-        Self { compiled, config }
+        Self { compiled, config, enum_variant_names: Default::default() }
     }
 
     /// Execute preconfigured test execution.
+    ///
+    /// Returns the summary of the run if all the tests passed. In `list` mode, the selected tests
+    /// are printed instead of run, and None is returned. Returns an error if any of the tests
+    /// failed.
     pub fn run(self) -> Result<Option<TestsSummary>> {
+        let mut reporter = self.config.reporter();
         let (compiled, filtered_out) = filter_test_cases(
             self.compiled,
             self.config.include_ignored,
             self.config.ignored,
             self.config.filter,
         );
+        if self.config.list {
+            print!("{}", list_tests(&compiled.named_tests, self.config.format));
+            return Ok(None);
+        }
 
-        let TestsSummary { passed, failed, ignored, failed_run_results } = run_tests(
-            compiled.named_tests,
-            compiled.sierra_program,
-            compiled.function_set_costs,
-            compiled.contracts_info,
+        let statements_locations = compiled.statements_locations.clone();
+        let summary = run_tests(
+            compiled,
+            &self.enum_variant_names,
+            self.config.run_profiler,
+            self.config.coverage_path.is_some(),
+            self.config.limits,
+            &mut reporter,
         )?;
+        reporter.run_finished(&summary, filtered_out)?;
+        if let (Some(path), Some(coverage)) = (&self.config.coverage_path, &summary.coverage) {
+            std::fs::write(path, lcov_report(coverage, &statements_locations)).with_context(
+                || format!("Failed to write the coverage report to `{}`.", path.display()),
+            )?;
+        }
 
-        if failed.is_empty() {
-            println!(
-                "test result: {}. {} passed; {} failed; {} ignored; {filtered_out} filtered out;",
-                "ok".bright_green(),
-                passed.len(),
-                failed.len(),
-                ignored.len()
-            );
-            Ok(None)
-        } else {
-            println!("failures:");
-            for (failure, run_result) in failed.iter().zip_eq(failed_run_results) {
-                print!("   {failure} - ");
-                match run_result {
-                    RunResultValue::Success(_) => {
-                        println!("expected panic but finished successfully.");
-                    }
-                    RunResultValue::Panic(values) => {
-                        print!("panicked with [");
-                        for value in &values {
-                            match as_cairo_short_string(value) {
-                                Some(as_string) => print!("{value} ('{as_string}'), "),
-                                None => print!("{value}, "),
-                            }
-                        }
-                        println!("].")
-                    }
-                }
+        if summary.failures().next().is_none() {
+            if let Some(mode) = &self.config.gas_snapshot {
+                handle_gas_snapshot(mode, &summary)?;
             }
-            println!();
+            Ok(Some(summary))
+        } else {
             bail!(
                 "test result: {}. {} passed; {} failed; {} ignored",
                 "FAILED".bright_red(),
-                passed.len(),
-                failed.len(),
-                ignored.len()
+                summary.passed().count(),
+                summary.failures().count(),
+                summary.ignored().count()
             );
         }
     }
@@ -133,9 +167,60 @@ impl CompiledTestRunner {
 /// Configuration of compiled tests runner.
 #[derive(Clone, Debug)]
 pub struct TestRunConfig {
-    pub filter: String,
+    /// The selection of the tests to run by their names.
+    pub filter: TestFilter,
     pub include_ignored: bool,
     pub ignored: bool,
+    /// Whether to print the profile of each test.
+    pub run_profiler: bool,
+    /// The format of the report printed to the standard output.
+    pub format: ReportFormat,
+    /// A file to write a JUnit XML report of the run to.
+    pub junit_path: Option<PathBuf>,
+    /// How to use a gas snapshot file, if at all.
+    pub gas_snapshot: Option<GasSnapshotMode>,
+    /// A file to write an LCOV coverage report of the run to.
+    pub coverage_path: Option<PathBuf>,
+    /// Whether to only list the selected tests, in the configured format, without running them.
+    pub list: bool,
+    /// The limits of each run of a test (and of each fixture), unless overridden by the test's
+    /// `max_steps` and `timeout` attributes.
+    pub limits: RunLimits,
+}
+
+impl TestRunConfig {
+    /// Returns the reporter of the configured reports.
+    fn reporter(&self) -> Vec<Box<dyn TestReporter>> {
+        let mut reporters: Vec<Box<dyn TestReporter>> = match self.format {
+            ReportFormat::Pretty => vec![Box::<PrettyReporter>::default()],
+            ReportFormat::Json => vec![Box::<JsonReporter>::default()],
+        };
+        if let Some(path) = &self.junit_path {
+            reporters.push(Box::new(JunitReporter::new(path.clone())));
+        }
+        reporters
+    }
+}
+
+/// The format of the report printed to the standard output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Human readable text.
+    #[default]
+    Pretty,
+    /// JSON objects describing the events of the run, one per line.
+    Json,
+}
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => bail!("Unknown report format `{s}`, expected `pretty` or `json`."),
+        }
+    }
 }
 
 /// The test cases compiler.
@@ -154,7 +239,7 @@ impl TestCompiler {
     /// * `path` - The path to compile and run its tests
     /// * `starknet` - Add the starknet plugin to run the tests
     pub fn try_new(path: &Path, starknet: bool) -> Result<Self> {
-        let db = &mut {
+        let mut db = {
             let mut b = RootDatabase::builder();
             b.detect_corelib();
             b.with_cfg(CfgSet::from_iter([Cfg::name("test")]));
@@ -168,18 +253,19 @@ impl TestCompiler {
             b.build()?
         };
 
-        let main_crate_ids = setup_project(db, Path::new(&path))?;
-
-        if DiagnosticsReporter::stderr().with_extra_crates(&main_crate_ids).check(db) {
+        let main_crate_ids = setup_project(&mut db, Path::new(&path))?;
+        let compiler =
+            Self { db, test_crate_ids: main_crate_ids.clone(), main_crate_ids, starknet };
+        if compiler.report_diagnostics() {
             bail!("failed to compile: {}", path.display());
         }
+        Ok(compiler)
+    }
 
-        Ok(Self {
-            db: db.snapshot(),
-            test_crate_ids: main_crate_ids.clone(),
-            main_crate_ids,
-            starknet,
-        })
+    /// Reports the diagnostics of the tested crates to the standard error. Returns whether there
+    /// were any.
+    pub fn report_diagnostics(&self) -> bool {
+        DiagnosticsReporter::stderr().with_extra_crates(&self.main_crate_ids).check(&self.db)
     }
 
     /// Build the tests and collect metadata.
@@ -199,14 +285,14 @@ impl TestCompiler {
 /// * `compiled` - Compiled test cases with metadata.
 /// * `include_ignored` - Include ignored tests as well.
 /// * `ignored` - Run ignored tests only.l
-/// * `filter` - Include only tests containing the filter string.
+/// * `filter` - Include only tests selected by the filter.
 /// # Returns
 /// * (`TestCompilation`, `usize`) - The filtered test cases and the number of filtered out cases.
 pub fn filter_test_cases(
     compiled: TestCompilation,
     include_ignored: bool,
     ignored: bool,
-    filter: String,
+    filter: TestFilter,
 ) -> (TestCompilation, usize) {
     let total_tests_count = compiled.named_tests.len();
     let named_tests = compiled.named_tests
@@ -218,7 +304,7 @@ pub fn filter_test_cases(
             }
             (func, test)
         })
-        .filter(|(name, _)| name.contains(&filter))
+        .filter(|(name, _)| filter.matches(name))
         // Filtering unignored tests in `ignored` mode
         .filter(|(_, test)| !ignored || test.ignored)
         .collect_vec();
@@ -228,121 +314,407 @@ pub fn filter_test_cases(
 }
 
 /// The status of a ran test.
-enum TestStatus {
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestStatus {
     Success,
-    Fail(RunResultValue),
+    Fail(TestFailure),
+    /// The test was not run, as it is ignored.
+    Ignored,
 }
 
-/// The result of a ran test.
-struct TestResult {
+/// The reason of a test failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestFailure {
+    /// The test was expected to panic, but finished successfully returning the given value.
+    MissingPanic(Value),
+    /// The test panicked unexpectedly or with unexpected data, given as the panic data.
+    Panic(Vec<Felt252>),
+    /// A run of a fuzz test failed, with the given minimal counterexample of the test's
+    /// parameters, found using the given seed.
+    Fuzzing { counterexample: String, seed: u64, failure: Box<TestFailure> },
+    /// The fixture the test starts from failed, so the test was not run.
+    Fixture { fixture: String, failure: Box<TestFailure> },
+    /// The run was stopped after reaching the maximal number of steps.
+    StepLimitExceeded { max_steps: usize },
+    /// The run was stopped after running for longer than the timeout.
+    Timeout { timeout: Duration },
+}
+impl TestFailure {
+    /// Returns the panic data of a test that panicked unexpectedly.
+    pub fn panic_data(&self) -> Option<&[Felt252]> {
+        match self {
+            TestFailure::MissingPanic(_)
+            | TestFailure::StepLimitExceeded { .. }
+            | TestFailure::Timeout { .. } => None,
+            TestFailure::Panic(values) => Some(values),
+            TestFailure::Fuzzing { failure, .. } | TestFailure::Fixture { failure, .. } => {
+                failure.panic_data()
+            }
+        }
+    }
+}
+impl Display for TestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestFailure::MissingPanic(value) if *value == Value::Tuple(vec![]) => {
+                write!(f, "expected panic but finished successfully.")
+            }
+            TestFailure::MissingPanic(value) => {
+                write!(f, "expected panic but finished successfully, returning {value}.")
+            }
+            TestFailure::Panic(values) => {
+                if let Some(message) = as_cairo_byte_array_panic(values) {
+                    return write!(f, "panicked with \"{message}\".");
+                }
+                write!(f, "panicked with [")?;
+                for value in values {
+                    match as_cairo_short_string(value) {
+                        Some(as_string) => write!(f, "{value} ('{as_string}'), ")?,
+                        None => write!(f, "{value}, ")?,
+                    }
+                }
+                write!(f, "].")
+            }
+            TestFailure::Fuzzing { counterexample, seed, failure } => {
+                write!(f, "{failure} Counterexample: {counterexample} (fuzzer seed: {seed}).")
+            }
+            TestFailure::Fixture { fixture, failure } => {
+                write!(f, "fixture `{fixture}` {failure}")
+            }
+            TestFailure::StepLimitExceeded { max_steps } => {
+                write!(f, "exceeded the step limit of {max_steps} steps.")
+            }
+            TestFailure::Timeout { timeout } => {
+                write!(f, "timed out after {}s.", timeout.as_secs_f64())
+            }
+        }
+    }
+}
+
+/// The result of a test.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    /// The full path of the test function.
+    pub name: String,
     /// The status of the run.
-    status: TestStatus,
-    /// The gas usage of the run if relevant.
-    gas_usage: Option<i64>,
+    pub status: TestStatus,
+    /// The gas usage of the run if relevant. For fuzz tests, this is the maximal gas usage of the
+    /// runs, or the gas usage of the failing run.
+    pub gas_usage: Option<i64>,
+    /// The number of steps of the run, or None if the test was not run or timed out. For fuzz
+    /// tests, this is the maximal number of steps of the runs, or the number of steps of the
+    /// failing run.
+    pub steps: Option<usize>,
+    /// The wall-clock duration of the run.
+    pub duration: Duration,
+    /// The profile of the run if requested.
+    pub profile: Option<ProfilingInfo>,
 }
 
 /// Summary data of the ran tests.
+#[derive(Clone, Debug, Default)]
 pub struct TestsSummary {
-    passed: Vec<String>,
-    failed: Vec<String>,
-    ignored: Vec<String>,
-    failed_run_results: Vec<RunResultValue>,
+    /// The results of the tests, in the order they finished in.
+    pub results: Vec<TestResult>,
+    /// The coverage of the runs of the tests, if requested.
+    pub coverage: Option<StatementCoverage>,
+}
+impl TestsSummary {
+    /// Returns the names of the tests that passed.
+    pub fn passed(&self) -> impl Iterator<Item = &str> {
+        self.results
+            .iter()
+            .filter(|result| result.status == TestStatus::Success)
+            .map(|result| result.name.as_str())
+    }
+
+    /// Returns the names of the tests that failed, along with their failure.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &TestFailure)> {
+        self.results.iter().filter_map(|result| match &result.status {
+            TestStatus::Fail(failure) => Some((result.name.as_str(), failure)),
+            _ => None,
+        })
+    }
+
+    /// Returns the names of the tests that were ignored.
+    pub fn ignored(&self) -> impl Iterator<Item = &str> {
+        self.results
+            .iter()
+            .filter(|result| result.status == TestStatus::Ignored)
+            .map(|result| result.name.as_str())
+    }
 }
 
 /// Runs the tests and process the results for a summary.
 pub fn run_tests(
-    named_tests: Vec<(String, TestConfig)>,
-    sierra_program: Program,
-    function_set_costs: OrderedHashMap<FunctionId, OrderedHashMap<CostTokenType, i32>>,
-    contracts_info: OrderedHashMap<Felt252, ContractInfo>,
+    compiled: TestCompilation,
+    enum_variant_names: &EnumVariantNames,
+    run_profiler: bool,
+    collect_coverage: bool,
+    limits: RunLimits,
+    reporter: &mut dyn TestReporter,
 ) -> Result<TestsSummary> {
+    let TestCompilation {
+        named_tests,
+        named_fixtures,
+        sierra_program,
+        function_set_costs,
+        contracts_info,
+        statements_locations: _,
+    } = compiled;
     let runner = SierraCasmRunner::new(
         sierra_program,
         Some(MetadataComputationConfig { function_set_costs }),
         contracts_info,
     )
-    .with_context(|| "Failed setting up runner.")?;
-    println!("running {} tests", named_tests.len());
-    let wrapped_summary = Mutex::new(Ok(TestsSummary {
-        passed: vec![],
-        failed: vec![],
-        ignored: vec![],
-        failed_run_results: vec![],
-    }));
+    .with_context(|| "Failed setting up runner.")?
+    .with_trace_collection(run_profiler || collect_coverage);
+    let fixture_states = run_fixtures(&runner, &named_tests, named_fixtures, limits)?;
+    let context = RunContext {
+        runner: &runner,
+        enum_variant_names,
+        run_profiler,
+        limits,
+        coverage: collect_coverage.then(|| Mutex::new(runner.empty_coverage())),
+    };
+    reporter.run_started(named_tests.len())?;
+    let reporter = Mutex::new(reporter);
+    let wrapped_summary = Mutex::new(Ok(TestsSummary::default()));
     named_tests
         .into_par_iter()
-        .map(|(name, test)| -> anyhow::Result<(String, Option<TestResult>)> {
+        .map(|(name, test)| -> anyhow::Result<TestResult> {
             if test.ignored {
-                return Ok((name, None));
+                return Ok(TestResult {
+                    name,
+                    status: TestStatus::Ignored,
+                    gas_usage: None,
+                    steps: None,
+                    duration: Duration::ZERO,
+                    profile: None,
+                });
             }
+            reporter.lock().unwrap().test_started(&name)?;
+            let start = Instant::now();
             let func = runner.find_function(name.as_str())?;
-            let result = runner
-                .run_function_with_starknet_context(
-                    func,
-                    &[],
-                    test.available_gas,
-                    Default::default(),
-                )
-                .with_context(|| format!("Failed to run the function `{}`.", name.as_str()))?;
-            Ok((
-                name,
-                Some(TestResult {
-                    status: match &result.value {
-                        RunResultValue::Success(_) => match test.expectation {
-                            TestExpectation::Success => TestStatus::Success,
-                            TestExpectation::Panics(_) => TestStatus::Fail(result.value),
-                        },
-                        RunResultValue::Panic(value) => match test.expectation {
-                            TestExpectation::Success => TestStatus::Fail(result.value),
-                            TestExpectation::Panics(panic_expectation) => match panic_expectation {
-                                PanicExpectation::Exact(expected) if value != &expected => {
-                                    TestStatus::Fail(result.value)
-                                }
-                                _ => TestStatus::Success,
-                            },
-                        },
-                    },
-                    gas_usage: test
-                        .available_gas
-                        .zip(result.gas_counter)
-                        .map(|(before, after)| {
-                            before.into_or_panic::<i64>() - after.to_bigint().to_i64().unwrap()
-                        })
-                        .or_else(|| {
-                            runner.initial_required_gas(func).map(|gas| gas.into_or_panic::<i64>())
-                        }),
-                }),
-            ))
+            let starknet_state = match test.fixture.as_ref().map(|fixture| &fixture_states[fixture])
+            {
+                Some(Ok(state)) => state.clone(),
+                Some(Err(failure)) => {
+                    return Ok(TestResult {
+                        name,
+                        status: TestStatus::Fail(failure.clone()),
+                        gas_usage: None,
+                        steps: None,
+                        duration: start.elapsed(),
+                        profile: None,
+                    });
+                }
+                None => StarknetState::default(),
+            };
+            let run = |args: &[Arg]| {
+                run_single_test(&context, func, &test, args, starknet_state.clone())
+                    .with_context(|| format!("Failed to run the function `{}`.", name.as_str()))
+            };
+            let TestRunResult { status, gas_usage, steps, profile } = match &test.fuzzer {
+                Some(fuzzer) => run_fuzz_test(&runner, func, fuzzer, run)?,
+                None => run(&[])?,
+            };
+            Ok(TestResult { name, status, gas_usage, steps, duration: start.elapsed(), profile })
         })
         .for_each(|r| {
             let mut wrapped_summary = wrapped_summary.lock().unwrap();
             if wrapped_summary.is_err() {
                 return;
             }
-            let (name, status) = match r {
-                Ok((name, status)) => (name, status),
-                Err(err) => {
-                    *wrapped_summary = Err(err);
-                    return;
-                }
-            };
-            let summary = wrapped_summary.as_mut().unwrap();
-            let (res_type, status_str, gas_usage) = match status {
-                Some(TestResult { status: TestStatus::Success, gas_usage }) => {
-                    (&mut summary.passed, "ok".bright_green(), gas_usage)
-                }
-                Some(TestResult { status: TestStatus::Fail(run_result), gas_usage }) => {
-                    summary.failed_run_results.push(run_result);
-                    (&mut summary.failed, "fail".bright_red(), gas_usage)
-                }
-                None => (&mut summary.ignored, "ignored".bright_yellow(), None),
-            };
-            if let Some(gas_usage) = gas_usage {
-                println!("test {name} ... {status_str} (gas usage est.: {gas_usage})");
-            } else {
-                println!("test {name} ... {status_str}");
+            let result = r.and_then(|result| {
+                reporter.lock().unwrap().test_finished(&result)?;
+                Ok(result)
+            });
+            match result {
+                Ok(result) => wrapped_summary.as_mut().unwrap().results.push(result),
+                Err(err) => *wrapped_summary = Err(err),
             }
-            res_type.push(name);
         });
-    wrapped_summary.into_inner().unwrap()
+    let mut summary = wrapped_summary.into_inner().unwrap()?;
+    summary.coverage = context.coverage.map(|coverage| coverage.into_inner().unwrap());
+    Ok(summary)
+}
+
+/// Runs the fixtures used by the (non-ignored) tests, each once. Returns the resulting Starknet
+/// state of each fixture, or the failure of the fixture if it panicked.
+fn run_fixtures(
+    runner: &SierraCasmRunner,
+    named_tests: &[(String, TestConfig)],
+    named_fixtures: Vec<(String, FixtureConfig)>,
+    limits: RunLimits,
+) -> Result<OrderedHashMap<String, Result<StarknetState, TestFailure>>> {
+    named_fixtures
+        .into_par_iter()
+        .filter(|(name, _)| {
+            named_tests.iter().any(|(_, test)| !test.ignored && test.fixture.as_ref() == Some(name))
+        })
+        .map(|(name, fixture)| {
+            let func = runner.find_function(name.as_str())?;
+            let result = runner.run_function_with_starknet_context(
+                func,
+                &[],
+                fixture.available_gas,
+                Default::default(),
+                limits,
+            );
+            let failure = match result {
+                Ok(result) => match result.value {
+                    RunResultValue::Success(_) => return Ok((name, Ok(result.starknet_state))),
+                    RunResultValue::Panic(values) => TestFailure::Panic(values),
+                },
+                Err(err) => limit_failure(&err)
+                    .ok_or(err)
+                    .with_context(|| format!("Failed to run the fixture `{name}`."))?,
+            };
+            let state =
+                Err(TestFailure::Fixture { fixture: name.clone(), failure: Box::new(failure) });
+            Ok((name, state))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|states| states.into_iter().collect())
+}
+
+/// The result of a single run of a test.
+struct TestRunResult {
+    /// The status of the run.
+    status: TestStatus,
+    /// The gas usage of the run if relevant.
+    gas_usage: Option<i64>,
+    /// The number of steps of the run, if known.
+    steps: Option<usize>,
+    /// The profile of the run if requested.
+    profile: Option<ProfilingInfo>,
+}
+
+/// The context shared by the runs of the tests.
+struct RunContext<'a> {
+    runner: &'a SierraCasmRunner,
+    enum_variant_names: &'a EnumVariantNames,
+    /// Whether to profile the runs.
+    run_profiler: bool,
+    /// The limits of the runs of tests that do not override them.
+    limits: RunLimits,
+    /// The coverage of the runs so far, if collected.
+    coverage: Option<Mutex<StatementCoverage>>,
+}
+
+/// Runs a test once with the given args, starting from the given Starknet state.
+fn run_single_test(
+    context: &RunContext<'_>,
+    func: &Function,
+    test: &TestConfig,
+    args: &[Arg],
+    starknet_state: StarknetState,
+) -> Result<TestRunResult> {
+    let RunContext { runner, enum_variant_names, run_profiler, limits, coverage } = context;
+    let limits = RunLimits {
+        max_steps: test.max_steps.or(limits.max_steps),
+        timeout: test.timeout.or(limits.timeout),
+    };
+    let result = match runner.run_function_with_starknet_context(
+        func,
+        args,
+        test.available_gas,
+        starknet_state,
+        limits,
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            let failure = limit_failure(&err).ok_or(err)?;
+            return Ok(TestRunResult {
+                steps: match failure {
+                    TestFailure::StepLimitExceeded { max_steps } => Some(max_steps),
+                    _ => None,
+                },
+                status: TestStatus::Fail(failure),
+                gas_usage: None,
+                profile: None,
+            });
+        }
+    };
+    let run_result = TestRunResult {
+        status: match result.value {
+            RunResultValue::Success(values) => match test.expectation {
+                TestExpectation::Success => TestStatus::Success,
+                TestExpectation::Panics(_) => TestStatus::Fail(TestFailure::MissingPanic(
+                    runner.decode_return_value(func, &values, &result.memory, enum_variant_names),
+                )),
+            },
+            RunResultValue::Panic(value) => match &test.expectation {
+                TestExpectation::Success => TestStatus::Fail(TestFailure::Panic(value)),
+                TestExpectation::Panics(panic_expectation) => {
+                    if matches_panic_expectation(panic_expectation, &value) {
+                        TestStatus::Success
+                    } else {
+                        TestStatus::Fail(TestFailure::Panic(value))
+                    }
+                }
+            },
+        },
+        gas_usage: test
+            .available_gas
+            .zip(result.gas_counter)
+            .map(|(before, after)| {
+                before.into_or_panic::<i64>() - after.to_bigint().to_i64().unwrap()
+            })
+            .or_else(|| runner.initial_required_gas(func).map(|gas| gas.into_or_panic::<i64>())),
+        steps: Some(result.steps),
+        profile: result
+            .trace
+            .as_ref()
+            .filter(|_| *run_profiler)
+            .map(|trace| runner.profile(trace, &result.memory)),
+    };
+    if let Some(coverage) = coverage {
+        let mut run_coverage = StatementCoverage::default();
+        for trace in result.trace.iter().chain(&result.call_traces) {
+            runner.add_coverage(&mut run_coverage, trace);
+        }
+        coverage.lock().unwrap().merge(&run_coverage);
+    }
+    Ok(run_result)
+}
+
+/// Returns the failure of a run that was stopped for exceeding its limits, given its error.
+fn limit_failure(err: &RunnerError) -> Option<TestFailure> {
+    match err {
+        RunnerError::StepLimitExceeded { max_steps } => {
+            Some(TestFailure::StepLimitExceeded { max_steps: *max_steps })
+        }
+        RunnerError::TimeoutExceeded { timeout } => {
+            Some(TestFailure::Timeout { timeout: *timeout })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the messages of a panic - the message of a panic with a ByteArray message, or otherwise
+/// the short strings in the panic data.
+fn panic_messages(data: &[Felt252]) -> Vec<String> {
+    match as_cairo_byte_array_panic(data) {
+        Some(message) => vec![message],
+        None => data.iter().filter_map(as_cairo_short_string).collect(),
+    }
+}
+
+/// Returns whether the data of a panic matches the expected panic. Message expectations match if
+/// any of the messages of the panic matches.
+fn matches_panic_expectation(expectation: &PanicExpectation, data: &[Felt252]) -> bool {
+    let mut messages = panic_messages(data).into_iter();
+    match expectation {
+        PanicExpectation::Any => true,
+        PanicExpectation::Exact(expected) => data == expected,
+        PanicExpectation::Message(expected) => messages.any(|message| &message == expected),
+        PanicExpectation::MessageSubstring(expected) => {
+            messages.any(|message| message.contains(expected.as_str()))
+        }
+        PanicExpectation::MessagePrefix(expected) => {
+            messages.any(|message| message.starts_with(expected.as_str()))
+        }
+    }
 }
@@ -0,0 +1,95 @@
+//! Listing of the tests, without running them.
+
+use std::fmt::Write;
+
+use cairo_lang_runner::short_string::as_cairo_short_string;
+use cairo_lang_test_plugin::test_config::{PanicExpectation, TestExpectation};
+use cairo_lang_test_plugin::TestConfig;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::ReportFormat;
+
+/// A test in a JSON listing.
+#[derive(Serialize)]
+struct JsonTest<'a> {
+    name: &'a str,
+    config: &'a TestConfig,
+}
+
+/// Returns a listing of the given tests, a line per test. In the `Pretty` format, each line is the
+/// full name of the test followed by its configuration attributes (other than `#[test]`). In the
+/// `Json` format, each line is a JSON object with the name and the configuration of the test.
+pub fn list_tests(named_tests: &[(String, TestConfig)], format: ReportFormat) -> String {
+    let mut listing = String::new();
+    for (name, config) in named_tests {
+        match format {
+            ReportFormat::Pretty => {
+                let attributes = config_attributes(config);
+                if attributes.is_empty() {
+                    writeln!(listing, "{name}").unwrap();
+                } else {
+                    writeln!(listing, "{name}: {}", attributes.join(" ")).unwrap();
+                }
+            }
+            ReportFormat::Json => {
+                let test = JsonTest { name, config };
+                writeln!(listing, "{}", serde_json::to_string(&test).unwrap()).unwrap();
+            }
+        }
+    }
+    listing
+}
+
+/// Returns the attributes setting the configuration of a test, as they may be written in code.
+fn config_attributes(config: &TestConfig) -> Vec<String> {
+    let TestConfig { available_gas, expectation, ignored, fuzzer, fixture, max_steps, timeout } =
+        config;
+    let mut attributes = vec![];
+    if let Some(available_gas) = available_gas {
+        attributes.push(format!("#[available_gas({available_gas})]"));
+    }
+    if let TestExpectation::Panics(expectation) = expectation {
+        attributes.push(match expectation {
+            PanicExpectation::Any => "#[should_panic]".into(),
+            PanicExpectation::Exact(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| match as_cairo_short_string(value) {
+                        Some(as_string) => format!("'{as_string}'"),
+                        None => value.to_string(),
+                    })
+                    .join(", ");
+                format!("#[should_panic(expected: ({values},))]")
+            }
+            PanicExpectation::Message(message) => {
+                format!("#[should_panic(expected: {message:?})]")
+            }
+            PanicExpectation::MessageSubstring(substring) => {
+                format!("#[should_panic(expected_substring: {substring:?})]")
+            }
+            PanicExpectation::MessagePrefix(prefix) => {
+                format!("#[should_panic(expected_prefix: {prefix:?})]")
+            }
+        });
+    }
+    if *ignored {
+        attributes.push("#[ignore]".into());
+    }
+    if let Some(fuzzer) = fuzzer {
+        attributes.push(match fuzzer.seed {
+            Some(seed) => format!("#[fuzzer(runs: {}, seed: {seed})]", fuzzer.runs),
+            None => format!("#[fuzzer(runs: {})]", fuzzer.runs),
+        });
+    }
+    if let Some(fixture) = fixture {
+        attributes.push(format!("#[use_fixture({fixture})]"));
+    }
+    if let Some(max_steps) = max_steps {
+        attributes.push(format!("#[max_steps({max_steps})]"));
+    }
+    if let Some(timeout) = timeout {
+        attributes.push(format!("#[timeout({})]", timeout.as_secs()));
+    }
+    attributes
+}
@@ -0,0 +1,274 @@
+//! Reporters of the progress and results of test runs.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{TestResult, TestStatus, TestsSummary};
+
+/// A reporter of the progress and results of a test run.
+///
+/// The tests are run in parallel, so the events of different tests may interleave.
+pub trait TestReporter: Send {
+    /// Called before running the tests, with the number of tests to run (including ignored ones).
+    fn run_started(&mut self, _tests_count: usize) -> Result<()> {
+        Ok(())
+    }
+    /// Called when a test starts running. Not called for ignored tests.
+    fn test_started(&mut self, _name: &str) -> Result<()> {
+        Ok(())
+    }
+    /// Called when a test finishes, or is skipped for being ignored.
+    fn test_finished(&mut self, _result: &TestResult) -> Result<()> {
+        Ok(())
+    }
+    /// Called after all the tests finished, with the number of tests filtered out of the run.
+    fn run_finished(&mut self, _summary: &TestsSummary, _filtered_out: usize) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl TestReporter for Vec<Box<dyn TestReporter>> {
+    fn run_started(&mut self, tests_count: usize) -> Result<()> {
+        self.iter_mut().try_for_each(|reporter| reporter.run_started(tests_count))
+    }
+    fn test_started(&mut self, name: &str) -> Result<()> {
+        self.iter_mut().try_for_each(|reporter| reporter.test_started(name))
+    }
+    fn test_finished(&mut self, result: &TestResult) -> Result<()> {
+        self.iter_mut().try_for_each(|reporter| reporter.test_finished(result))
+    }
+    fn run_finished(&mut self, summary: &TestsSummary, filtered_out: usize) -> Result<()> {
+        self.iter_mut().try_for_each(|reporter| reporter.run_finished(summary, filtered_out))
+    }
+}
+
+/// Reports human readable text to the standard output.
+#[derive(Default)]
+pub struct PrettyReporter;
+
+impl TestReporter for PrettyReporter {
+    fn run_started(&mut self, tests_count: usize) -> Result<()> {
+        println!("running {tests_count} tests");
+        Ok(())
+    }
+
+    fn test_finished(&mut self, result: &TestResult) -> Result<()> {
+        let TestResult { name, status, gas_usage, profile, .. } = result;
+        let status_str = match status {
+            TestStatus::Success => "ok".bright_green(),
+            TestStatus::Fail(_) => "fail".bright_red(),
+            TestStatus::Ignored => "ignored".bright_yellow(),
+        };
+        if let Some(gas_usage) = gas_usage {
+            println!("test {name} ... {status_str} (gas usage est.: {gas_usage})");
+        } else {
+            println!("test {name} ... {status_str}");
+        }
+        if let Some(profile) = profile {
+            print!("{profile}");
+        }
+        Ok(())
+    }
+
+    fn run_finished(&mut self, summary: &TestsSummary, filtered_out: usize) -> Result<()> {
+        let failures = summary.failures().collect_vec();
+        if failures.is_empty() {
+            println!(
+                "test result: {}. {} passed; {} failed; {} ignored; {filtered_out} filtered out;",
+                "ok".bright_green(),
+                summary.passed().count(),
+                failures.len(),
+                summary.ignored().count()
+            );
+        } else {
+            println!("failures:");
+            for (name, failure) in failures {
+                println!("   {name} - {failure}");
+            }
+            println!();
+        }
+        Ok(())
+    }
+}
+
+/// Reports events as JSON objects, one per line, to a writer (the standard output by default).
+pub struct JsonReporter<W: Write + Send = io::Stdout> {
+    writer: W,
+}
+
+impl Default for JsonReporter {
+    fn default() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write + Send> JsonReporter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes a single event line.
+    fn write_event(&mut self, event: JsonEvent<'_>) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, &event)?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// An event reported by `JsonReporter`.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum JsonEvent<'a> {
+    RunStarted {
+        tests: usize,
+    },
+    TestStarted {
+        name: &'a str,
+    },
+    TestFinished {
+        name: &'a str,
+        status: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        gas_usage: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        steps: Option<usize>,
+        /// The duration of the test run, in seconds.
+        duration: f64,
+        /// The panic data of a test that panicked unexpectedly, as decimal strings.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        panic_data: Option<Vec<String>>,
+        /// A description of the failure of a failed test.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+    RunFinished {
+        passed: usize,
+        failed: usize,
+        ignored: usize,
+        filtered_out: usize,
+    },
+}
+
+impl<W: Write + Send> TestReporter for JsonReporter<W> {
+    fn run_started(&mut self, tests_count: usize) -> Result<()> {
+        self.write_event(JsonEvent::RunStarted { tests: tests_count })
+    }
+
+    fn test_started(&mut self, name: &str) -> Result<()> {
+        self.write_event(JsonEvent::TestStarted { name })
+    }
+
+    fn test_finished(&mut self, result: &TestResult) -> Result<()> {
+        let TestResult { name, status, gas_usage, steps, duration, .. } = result;
+        let (status, failure) = match status {
+            TestStatus::Success => ("ok", None),
+            TestStatus::Fail(failure) => ("failed", Some(failure)),
+            TestStatus::Ignored => ("ignored", None),
+        };
+        self.write_event(JsonEvent::TestFinished {
+            name,
+            status,
+            gas_usage: *gas_usage,
+            steps: *steps,
+            duration: duration.as_secs_f64(),
+            panic_data: failure
+                .and_then(|failure| failure.panic_data())
+                .map(|values| values.iter().map(|value| value.to_string()).collect()),
+            message: failure.map(|failure| failure.to_string()),
+        })
+    }
+
+    fn run_finished(&mut self, summary: &TestsSummary, filtered_out: usize) -> Result<()> {
+        self.write_event(JsonEvent::RunFinished {
+            passed: summary.passed().count(),
+            failed: summary.failures().count(),
+            ignored: summary.ignored().count(),
+            filtered_out,
+        })
+    }
+}
+
+/// Writes a JUnit XML report to a file at the end of the run.
+pub struct JunitReporter {
+    path: PathBuf,
+}
+
+impl JunitReporter {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl TestReporter for JunitReporter {
+    fn run_finished(&mut self, summary: &TestsSummary, _filtered_out: usize) -> Result<()> {
+        fs::write(&self.path, junit_xml(summary))
+            .with_context(|| format!("Failed to write {}.", self.path.display()))
+    }
+}
+
+/// Returns the JUnit XML report of a test run.
+fn junit_xml(summary: &TestsSummary) -> String {
+    let total_time: Duration = summary.results.iter().map(|result| result.duration).sum();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    writeln!(
+        xml,
+        "  <testsuite name=\"cairo-test\" tests=\"{}\" failures=\"{}\" errors=\"0\" \
+         skipped=\"{}\" time=\"{:.3}\">",
+        summary.results.len(),
+        summary.failures().count(),
+        summary.ignored().count(),
+        total_time.as_secs_f64()
+    )
+    .unwrap();
+    // Sort the tests, as they finish in a nondeterministic order.
+    for result in summary.results.iter().sorted_by_key(|result| &result.name) {
+        let (classname, name) = result.name.rsplit_once("::").unwrap_or(("", &result.name));
+        write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            xml_escape(name),
+            xml_escape(classname),
+            result.duration.as_secs_f64()
+        )
+        .unwrap();
+        match &result.status {
+            TestStatus::Success => xml.push_str("/>\n"),
+            TestStatus::Fail(failure) => {
+                let message = xml_escape(&failure.to_string());
+                writeln!(xml, ">\n      <failure message=\"{message}\"/>\n    </testcase>")
+                    .unwrap();
+            }
+            TestStatus::Ignored => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escapes text for usage in XML attributes.
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => write!(escaped, "&#{};", c as u32).unwrap(),
+            // Other control characters are not allowed in XML 1.0.
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,11 +1,67 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cairo_felt::Felt252;
+use cairo_lang_filesystem::db::FilesGroupEx;
+use cairo_lang_filesystem::flag::Flag;
+use cairo_lang_filesystem::ids::{FileId, FlagId};
+use cairo_lang_runner::short_string::BYTE_ARRAY_MAGIC;
+use cairo_lang_runner::RunLimits;
+use cairo_lang_test_plugin::test_config::{FuzzerConfig, PanicExpectation};
 use itertools::Itertools;
 
-use crate::{TestCompilation, TestCompiler};
+use crate::coverage::lcov_report;
+use crate::gas_snapshot::handle_gas_snapshot;
+use crate::list::list_tests;
+use crate::reporter::xml_escape;
+use crate::watch::test_fingerprints;
+use crate::{
+    filter_test_cases, find_gas_regressions, matches_panic_expectation, run_tests, GasSnapshot,
+    GasSnapshotEntry, GasSnapshotMode, JsonReporter, JunitReporter, ReportFormat, TestCompilation,
+    TestCompiler, TestFailure, TestFilter, TestReporter, TestResult, TestStatus, TestsSummary,
+};
+
+/// Returns the path of `test_data`, the crate compiled by the tests of the runner.
+fn test_data_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data")
+}
+
+/// Compiles the crate at [test_data_path] and runs its tests matching `pattern`, with the given
+/// default limits, collecting the coverage of the run if `coverage` is set. Returns the compiled
+/// tests and the summary of the run.
+fn run_test_data(
+    pattern: &str,
+    limits: RunLimits,
+    coverage: bool,
+) -> (TestCompilation, TestsSummary) {
+    let mut compiler = TestCompiler::try_new(&test_data_path(), true).unwrap();
+    if coverage {
+        // The coverage is mapped back to the Cairo code through the locations of the statements.
+        let add_statement_locations_flag_id = FlagId::new(&compiler.db, "add_statement_locations");
+        compiler.db.set_flag(
+            add_statement_locations_flag_id,
+            Some(Arc::new(Flag::AddStatementLocations(true))),
+        );
+    }
+    let (include_ignored, ignored, run_profiler) = (false, false, false);
+    let (compiled, _) =
+        filter_test_cases(compiler.build().unwrap(), include_ignored, ignored, pattern.into());
+    let summary = run_tests(
+        compiled.clone(),
+        &Default::default(),
+        run_profiler,
+        coverage,
+        limits,
+        &mut JsonReporter::new(std::io::sink()),
+    )
+    .unwrap();
+    (compiled, summary)
+}
 
 #[test]
 fn test_compiled_serialization() {
-    use std::path::PathBuf;
-    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data");
+    let path = test_data_path();
 
     let compiler = TestCompiler::try_new(&path, true).unwrap();
     let compiled = compiler.build().unwrap();
@@ -15,8 +71,391 @@ fn test_compiled_serialization() {
     assert_eq!(compiled.sierra_program, deserialized.sierra_program);
     assert_eq!(compiled.function_set_costs, deserialized.function_set_costs);
     assert_eq!(compiled.named_tests, deserialized.named_tests);
+    assert_eq!(compiled.named_fixtures, deserialized.named_fixtures);
     assert_eq!(
         compiled.contracts_info.values().collect_vec(),
         deserialized.contracts_info.values().collect_vec()
     );
 }
+
+#[test]
+fn test_json_reporter() {
+    let result = TestResult {
+        name: "test::test_panics".into(),
+        status: TestStatus::Fail(TestFailure::Panic(vec![Felt252::from(0x61)])),
+        gas_usage: Some(600),
+        steps: Some(60),
+        duration: Duration::from_millis(2),
+        profile: None,
+    };
+    let mut output = vec![];
+    let mut reporter = JsonReporter::new(&mut output);
+    reporter.run_started(1).unwrap();
+    reporter.test_started(&result.name).unwrap();
+    reporter.test_finished(&result).unwrap();
+    reporter.run_finished(&TestsSummary { results: vec![result], coverage: None }, 2).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        indoc::indoc! {r#"
+            {"event":"run_started","tests":1}
+            {"event":"test_started","name":"test::test_panics"}
+            {"event":"test_finished","name":"test::test_panics","status":"failed","gas_usage":600,"steps":60,"duration":0.002,"panic_data":["97"],"message":"panicked with [97 ('a'), ]."}
+            {"event":"run_finished","passed":0,"failed":1,"ignored":0,"filtered_out":2}
+        "#}
+    );
+}
+
+#[test]
+fn test_junit_reporter() {
+    let result = |name: &str, status: TestStatus| TestResult {
+        name: name.into(),
+        status,
+        gas_usage: None,
+        steps: None,
+        duration: Duration::from_millis(1500),
+        profile: None,
+    };
+    let summary = TestsSummary {
+        results: vec![
+            result("test::test_passes", TestStatus::Success),
+            result("test::inner::test_ignored", TestStatus::Ignored),
+            result(
+                "test::test_panics",
+                TestStatus::Fail(TestFailure::Panic(vec![Felt252::from_bytes_be(b"a<b")])),
+            ),
+        ],
+        coverage: None,
+    };
+    let path = std::env::temp_dir().join(format!("junit_reporter_{}.xml", std::process::id()));
+    JunitReporter::new(path.clone()).run_finished(&summary, 0).unwrap();
+    let report = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        report,
+        indoc::indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <testsuites>
+              <testsuite name="cairo-test" tests="3" failures="1" errors="0" skipped="1" time="4.500">
+                <testcase name="test_ignored" classname="test::inner" time="1.500">
+                  <skipped/>
+                </testcase>
+                <testcase name="test_panics" classname="test" time="1.500">
+                  <failure message="panicked with [6372450 (&apos;a&lt;b&apos;), ]."/>
+                </testcase>
+                <testcase name="test_passes" classname="test" time="1.500"/>
+              </testsuite>
+            </testsuites>
+        "#}
+    );
+}
+
+#[test]
+fn test_xml_escape() {
+    assert_eq!(xml_escape("plain text"), "plain text");
+    assert_eq!(
+        xml_escape("<a href=\"x\">'b' & c</a>"),
+        "&lt;a href=&quot;x&quot;&gt;&apos;b&apos; &amp; c&lt;/a&gt;"
+    );
+    assert_eq!(xml_escape("line\nnext\ttab"), "line&#10;next&#9;tab");
+    // Control characters that are not allowed in XML are dropped.
+    assert_eq!(xml_escape("a\u{0}b\u{1b}c"), "abc");
+}
+
+#[test]
+fn test_fuzzing() {
+    let (compiled, summary) = run_test_data("test_fuzz", Default::default(), false);
+    assert!(
+        compiled
+            .named_tests
+            .iter()
+            .all(|(_, test)| { test.fuzzer == Some(FuzzerConfig { runs: 100, seed: Some(1) }) })
+    );
+    assert_eq!(summary.passed().collect_vec(), vec!["contracts::fuzzing::test_fuzz_passes"]);
+    let [(name, failure)] = summary.failures().collect_vec()[..] else { panic!() };
+    assert_eq!(name, "contracts::fuzzing::test_fuzz_fails");
+    let TestFailure::Fuzzing { counterexample, seed, .. } = failure else { panic!() };
+    assert_eq!((counterexample.as_str(), *seed), ("(1000, [0])", 1));
+}
+
+#[test]
+fn test_fixtures() {
+    let (compiled, summary) = run_test_data("fixtures::", Default::default(), false);
+    assert_eq!(
+        compiled
+            .named_tests
+            .iter()
+            .map(|(name, test)| (name.as_str(), test.fixture.as_deref()))
+            .collect_vec(),
+        vec![
+            ("contracts::fixtures::test_setup_increase", Some("contracts::fixtures::setup")),
+            ("contracts::fixtures::test_setup_is_cloned", Some("contracts::fixtures::setup")),
+            (
+                "contracts::fixtures::test_named_fixture",
+                Some("contracts::fixtures::deployed_with_200")
+            ),
+            (
+                "contracts::fixtures::test_failing_fixture",
+                Some("contracts::fixtures::failing_fixture")
+            ),
+        ]
+    );
+    assert_eq!(
+        summary.passed().sorted().collect_vec(),
+        vec![
+            "contracts::fixtures::test_named_fixture",
+            "contracts::fixtures::test_setup_increase",
+            "contracts::fixtures::test_setup_is_cloned",
+        ]
+    );
+    let [(name, failure)] = summary.failures().collect_vec()[..] else { panic!() };
+    assert_eq!(name, "contracts::fixtures::test_failing_fixture");
+    let TestFailure::Fixture { fixture, .. } = failure else { panic!() };
+    assert_eq!(fixture, "contracts::fixtures::failing_fixture");
+}
+
+#[test]
+fn test_run_limits() {
+    // The timeouts of the tests override the default timeout.
+    let limits = RunLimits { max_steps: None, timeout: Some(Duration::from_secs(1000)) };
+    let (_, summary) = run_test_data("limits::", limits, false);
+    assert_eq!(summary.passed().collect_vec(), vec!["contracts::limits::test_within_step_limit"]);
+    assert_eq!(
+        summary.failures().sorted_by_key(|(name, _)| *name).collect_vec(),
+        vec![
+            (
+                "contracts::limits::test_step_limit_exceeded",
+                &TestFailure::StepLimitExceeded { max_steps: 100000 }
+            ),
+            (
+                "contracts::limits::test_step_limit_exceeded_in_contract_call",
+                &TestFailure::StepLimitExceeded { max_steps: 100000 }
+            ),
+            (
+                "contracts::limits::test_step_limit_exceeded_in_nested_contract_calls",
+                &TestFailure::StepLimitExceeded { max_steps: 100000 }
+            ),
+            (
+                "contracts::limits::test_timeout",
+                &TestFailure::Timeout { timeout: Duration::from_secs(1) }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_filter() {
+    let filter = |pattern: &str, exact: bool, skip: &[&str]| TestFilter {
+        pattern: pattern.into(),
+        exact,
+        skip: skip.iter().map(|pattern| pattern.to_string()).collect(),
+    };
+    let name = "contracts::fuzzing::test_fuzz_fails";
+    for (filter, matches) in [
+        (filter("", false, &[]), true),
+        (filter("fuzz", false, &[]), true),
+        (filter("fuzz", true, &[]), false),
+        (filter(name, true, &[]), true),
+        (filter("contracts::*::test_*_fails", false, &[]), true),
+        (filter("contracts::*::test_*_passes", false, &[]), false),
+        (filter("*fuzzing*", true, &[]), true),
+        (filter("fuzzing", false, &["fails"]), false),
+        (filter("fuzzing", false, &["passes"]), true),
+        (filter("fuzzing", true, &["fails"]), false),
+        (filter("", false, &["passes", "*_fail?"]), false),
+        (filter("contracts::fuzzing::test_fuzz_fail?", false, &[]), true),
+        (filter("contracts::fuzzing::test_fuzz_fail?", false, &[name]), false),
+    ] {
+        assert_eq!(filter.matches(name), matches, "{filter:?}");
+    }
+}
+
+#[test]
+fn test_list_tests() {
+    let path = test_data_path();
+    let compiler = TestCompiler::try_new(&path, true).unwrap();
+    let (compiled, _) = filter_test_cases(
+        compiler.build().unwrap(),
+        false,
+        false,
+        "contracts::fixtures::test_*_fixture".into(),
+    );
+    assert_eq!(
+        list_tests(&compiled.named_tests, ReportFormat::Pretty).lines().collect_vec(),
+        vec![
+            "contracts::fixtures::test_named_fixture: #[available_gas(30000000)] \
+             #[use_fixture(contracts::fixtures::deployed_with_200)]",
+            "contracts::fixtures::test_failing_fixture: \
+             #[use_fixture(contracts::fixtures::failing_fixture)]",
+        ]
+    );
+    assert_eq!(
+        list_tests(&compiled.named_tests[1..], ReportFormat::Json),
+        indoc::indoc! {r#"
+            {"name":"contracts::fixtures::test_failing_fixture","config":{"available_gas":null,"expectation":"Success","ignored":false,"fuzzer":null,"fixture":"contracts::fixtures::failing_fixture","max_steps":null,"timeout":null}}
+        "#}
+    );
+}
+
+#[test]
+fn test_find_gas_regressions() {
+    let snapshot = |entries: &[(&str, Option<i64>)]| -> GasSnapshot {
+        entries
+            .iter()
+            .map(|(name, gas)| (name.to_string(), GasSnapshotEntry { gas: *gas, steps: 10 }))
+            .collect()
+    };
+    let expected = snapshot(&[
+        ("test::a", Some(1000)),
+        ("test::b", Some(1000)),
+        ("test::c", Some(1000)),
+        ("test::d", None),
+        ("test::removed", Some(1000)),
+    ]);
+    let actual = snapshot(&[
+        ("test::a", Some(900)),
+        ("test::b", Some(1010)),
+        ("test::c", Some(1500)),
+        ("test::d", Some(1000)),
+        ("test::added", Some(1000)),
+    ]);
+    assert_eq!(
+        find_gas_regressions(&expected, &actual, 0.0),
+        "    test::b: gas 1000 -> 1010 (+1.00%), steps 10 -> 10\n    test::c: gas 1000 -> 1500 \
+         (+50.00%), steps 10 -> 10\n"
+    );
+    assert_eq!(
+        find_gas_regressions(&expected, &actual, 1.0),
+        "    test::c: gas 1000 -> 1500 (+50.00%), steps 10 -> 10\n"
+    );
+}
+
+#[test]
+fn test_gas_snapshot_update() {
+    let path =
+        std::env::temp_dir().join(format!("gas_snapshot_update_{}.json", std::process::id()));
+    let entry = |gas: i64| GasSnapshotEntry { gas: Some(gas), steps: 10 };
+    let initial = GasSnapshot::from([
+        ("test::a".to_string(), entry(1000)),
+        ("test::b".to_string(), entry(1000)),
+    ]);
+    std::fs::write(&path, serde_json::to_string(&initial).unwrap()).unwrap();
+    // A run of a filtered set of the tests.
+    let summary = TestsSummary {
+        results: [("test::b", 1200), ("test::c", 500)]
+            .into_iter()
+            .map(|(name, gas)| TestResult {
+                name: name.into(),
+                status: TestStatus::Success,
+                gas_usage: Some(gas),
+                steps: Some(10),
+                duration: Duration::ZERO,
+                profile: None,
+            })
+            .collect(),
+        coverage: None,
+    };
+    handle_gas_snapshot(&GasSnapshotMode::Write(path.clone()), &summary).unwrap();
+    let updated: GasSnapshot =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        updated,
+        GasSnapshot::from([
+            ("test::a".to_string(), entry(1000)),
+            ("test::b".to_string(), entry(1200)),
+            ("test::c".to_string(), entry(500)),
+        ])
+    );
+}
+
+#[test]
+fn test_coverage() {
+    let (compiled, summary) = run_test_data("test_flow", Default::default(), true);
+    let report = lcov_report(&summary.coverage.unwrap(), &compiled.statements_locations);
+    let lib_path = test_data_path().join("lib.cairo");
+    let lib_content = std::fs::read_to_string(&lib_path).unwrap();
+    let lib_file = lib_path.to_string_lossy().to_string();
+    let lib_record = report
+        .split("end_of_record\n")
+        .find(|record| record.starts_with(&format!("SF:{lib_file}\n")))
+        .unwrap();
+    // The lines of the contract are covered through the calls made by the test.
+    for (code, hits) in [
+        ("self.value.write(value_);", 2),
+        ("self.value.read()\n", 4),
+        ("self.value.write(self.value.read() + a);", 1),
+        ("assert_eq(@contract1.get(), @400, 'contract1.get() == 400');", 1),
+    ] {
+        let line =
+            lib_content.lines().position(|line| line.trim_start() == code.trim_end()).unwrap();
+        let executed_line = format!("DA:{},{hits}", line + 1);
+        assert!(
+            lib_record.lines().contains(&executed_line.as_str()),
+            "{executed_line} missing in {lib_record}"
+        );
+    }
+}
+
+#[test]
+fn test_panic_expectations() {
+    // The panic data of `panic_with_byte_array(@"This is a ByteArray longer than a single word")`.
+    let byte_array_panic = vec![
+        Felt252::parse_bytes(BYTE_ARRAY_MAGIC.as_bytes(), 16).unwrap(),
+        Felt252::from(1),
+        Felt252::parse_bytes(b"54686973206973206120427974654172726179206c6f6e676572207468616e", 16)
+            .unwrap(),
+        Felt252::parse_bytes(b"20612073696e676c6520776f7264", 16).unwrap(),
+        Felt252::from(14),
+    ];
+    let felt252_panic = vec![Felt252::from(1), Felt252::from_bytes_be(b"short string")];
+    let message = |message: &str| PanicExpectation::Message(message.into());
+    let substring = |substring: &str| PanicExpectation::MessageSubstring(substring.into());
+    let prefix = |prefix: &str| PanicExpectation::MessagePrefix(prefix.into());
+
+    for (expectation, data, matches) in [
+        (PanicExpectation::Any, &byte_array_panic, true),
+        (PanicExpectation::Exact(felt252_panic.clone()), &felt252_panic, true),
+        (PanicExpectation::Exact(felt252_panic.clone()), &byte_array_panic, false),
+        (message("This is a ByteArray longer than a single word"), &byte_array_panic, true),
+        (message("This is a ByteArray"), &byte_array_panic, false),
+        (message("short string"), &felt252_panic, true),
+        (substring("longer than"), &byte_array_panic, true),
+        (substring("shorter than"), &byte_array_panic, false),
+        (substring("string"), &felt252_panic, true),
+        (prefix("This is"), &byte_array_panic, true),
+        (prefix("single word"), &byte_array_panic, false),
+        (prefix("short"), &felt252_panic, true),
+    ] {
+        assert_eq!(
+            matches_panic_expectation(&expectation, data),
+            matches,
+            "{expectation:?} on {data:?}"
+        );
+    }
+    assert_eq!(
+        TestFailure::Panic(byte_array_panic).to_string(),
+        "panicked with \"This is a ByteArray longer than a single word\"."
+    );
+}
+
+#[test]
+fn test_fingerprints_of_changed_code() {
+    let path = test_data_path();
+    let mut compiler = TestCompiler::try_new(&path, true).unwrap();
+    let fingerprints = test_fingerprints(&compiler.build().unwrap());
+    let fuzzing_file = path.join("fuzzing.cairo");
+    let content = std::fs::read_to_string(&fuzzing_file).unwrap();
+    let file_id = FileId::new(&compiler.db, fuzzing_file);
+    compiler
+        .db
+        .override_file_content(file_id, Some(Arc::new(content.replace("a < 1000", "a < 2000"))));
+    let new_fingerprints = test_fingerprints(&compiler.build().unwrap());
+    assert_eq!(
+        fingerprints
+            .iter()
+            .filter(|(name, fingerprint)| new_fingerprints.get(name.as_str()) != Some(fingerprint))
+            .map(|(name, _)| name.as_str())
+            .collect_vec(),
+        vec!["contracts::fuzzing::test_fuzz_fails"]
+    );
+}
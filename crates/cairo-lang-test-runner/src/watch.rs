@@ -0,0 +1,310 @@
+//! A watch mode for the test runner: the tests are re-run on each change of the tested files,
+//! recompiling incrementally and re-running only the tests whose code changed.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use std::{fs, thread};
+
+use anyhow::Result;
+use cairo_lang_filesystem::db::{
+    AsFilesGroupMut, FilesGroup, FilesGroupEx, PrivRawFileContentQuery,
+};
+use cairo_lang_filesystem::ids::{Directory, FileId};
+use cairo_lang_runner::collect_enum_variant_names;
+use cairo_lang_sierra::ids::{ConcreteLibfuncId, ConcreteTypeId, FunctionId};
+use cairo_lang_sierra::program::{Function, GenericArg, Program, Statement, StatementIdx};
+use cairo_lang_test_plugin::TestCompilation;
+use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
+use itertools::{chain, Itertools};
+
+use crate::{CompiledTestRunner, TestRunner};
+
+/// The interval between checks for changes of the watched files.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+impl TestRunner {
+    /// Runs the tests, and then re-runs the tests affected by each change of the Cairo files of the
+    /// tested crates, until the process is stopped. The compiler database is kept alive between
+    /// the runs, so only the code affected by the changes is recompiled.
+    pub fn watch(&mut self) -> Result<()> {
+        let roots = self
+            .compiler
+            .main_crate_ids
+            .iter()
+            .filter_map(|crate_id| match self.compiler.db.crate_root_dir(*crate_id) {
+                Some(Directory::Real(path)) => Some(path),
+                _ => None,
+            })
+            .collect_vec();
+        let mut modification_times = cairo_files_modification_times(&roots);
+        let mut fingerprints = OrderedHashMap::default();
+        loop {
+            if let Err(err) = self.run_affected(&mut fingerprints) {
+                eprintln!("{err}");
+            }
+            println!("Watching for changes...");
+            let changed_files = loop {
+                thread::sleep(POLL_INTERVAL);
+                let new_modification_times = cairo_files_modification_times(&roots);
+                let changed_files =
+                    chain!(modification_times.keys(), new_modification_times.keys())
+                        .unique()
+                        .filter(|path| {
+                            modification_times.get(*path) != new_modification_times.get(*path)
+                        })
+                        .cloned()
+                        .collect_vec();
+                modification_times = new_modification_times;
+                if !changed_files.is_empty() {
+                    break changed_files;
+                }
+            };
+            for path in changed_files {
+                self.apply_file_change(path);
+            }
+        }
+    }
+
+    /// Applies a change of a file on the disk to the compiler database.
+    fn apply_file_change(&mut self, path: PathBuf) {
+        let db = &mut self.compiler.db;
+        let file_id = FileId::new(db, path.clone());
+        match fs::read_to_string(&path) {
+            Ok(content) => db.override_file_content(file_id, Some(Arc::new(content))),
+            Err(_) => {
+                // The file was removed - it is read again from the disk, so it is missing.
+                db.override_file_content(file_id, None);
+                PrivRawFileContentQuery.in_db_mut(db.as_files_group_mut()).invalidate(&file_id);
+            }
+        }
+    }
+
+    /// Compiles the tests and runs the tests whose fingerprint changed since the previous run,
+    /// given the fingerprints of the previous run.
+    fn run_affected(&self, fingerprints: &mut OrderedHashMap<String, u64>) -> Result<()> {
+        if self.compiler.report_diagnostics() {
+            anyhow::bail!("failed to compile");
+        }
+        let mut compiled = self.compiler.build()?;
+        let new_fingerprints = test_fingerprints(&compiled);
+        let all_tests_count = compiled.named_tests.len();
+        compiled
+            .named_tests
+            .retain(|(name, _)| fingerprints.get(name) != new_fingerprints.get(name));
+        *fingerprints = new_fingerprints;
+        println!(
+            "{} tests affected by the changes, {} unchanged.",
+            compiled.named_tests.len(),
+            all_tests_count - compiled.named_tests.len()
+        );
+        if compiled.named_tests.is_empty() {
+            return Ok(());
+        }
+        let mut runner = CompiledTestRunner::new(compiled, self.config.clone());
+        runner.enum_variant_names =
+            collect_enum_variant_names(&self.compiler.db, &self.compiler.main_crate_ids);
+        runner.run()?;
+        Ok(())
+    }
+}
+
+/// Returns the modification times of the Cairo files in the given directories, recursively.
+fn cairo_files_modification_times(roots: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    fn visit(dir: &Path, modification_times: &mut BTreeMap<PathBuf, SystemTime>) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else { continue };
+            if metadata.is_dir() {
+                visit(&path, modification_times);
+            } else if path.extension().is_some_and(|extension| extension == "cairo") {
+                if let Ok(modified) = metadata.modified() {
+                    modification_times.insert(path, modified);
+                }
+            }
+        }
+    }
+    let mut modification_times = BTreeMap::new();
+    for root in roots {
+        visit(root, &mut modification_times);
+    }
+    modification_times
+}
+
+/// Returns a fingerprint of each test - a hash of its configuration and of the code it may run:
+/// the Sierra code of the functions it calls (directly or indirectly), of its fixture, and of the
+/// tested contracts.
+pub fn test_fingerprints(compiled: &TestCompilation) -> OrderedHashMap<String, u64> {
+    let code = ProgramCode::new(&compiled.sierra_program);
+    let contract_functions = compiled
+        .contracts_info
+        .values()
+        .flat_map(|info| {
+            chain!(&info.constructor, info.externals.values(), info.l1_handlers.values())
+        })
+        .cloned()
+        .collect_vec();
+    compiled
+        .named_tests
+        .iter()
+        .map(|(name, test)| {
+            let test_functions = chain!([name], &test.fixture)
+                .filter_map(|name| code.function_by_name(name))
+                .cloned();
+            let mut hasher = DefaultHasher::new();
+            format!("{test:?}").hash(&mut hasher);
+            for function_id in
+                code.reachable_functions(chain!(test_functions, contract_functions.iter().cloned()))
+            {
+                function_id.to_string().hash(&mut hasher);
+                code.function_hashes.get(&function_id).hash(&mut hasher);
+            }
+            (name.clone(), hasher.finish())
+        })
+        .collect()
+}
+
+/// The code of the functions of a Sierra program.
+struct ProgramCode<'a> {
+    program: &'a Program,
+    /// The range of the statements of each function.
+    bodies: UnorderedHashMap<FunctionId, Range<usize>>,
+    /// The user functions each libfunc refers to (e.g. the function called by `function_call`).
+    libfunc_functions: UnorderedHashMap<ConcreteLibfuncId, Vec<FunctionId>>,
+    /// A hash of the code of each function.
+    function_hashes: UnorderedHashMap<FunctionId, u64>,
+}
+impl<'a> ProgramCode<'a> {
+    fn new(program: &'a Program) -> Self {
+        let entry_points = program
+            .funcs
+            .iter()
+            .map(|function| function.entry_point.0)
+            .chain([program.statements.len()])
+            .sorted()
+            .collect_vec();
+        let bodies = program
+            .funcs
+            .iter()
+            .map(|function| {
+                let start = function.entry_point.0;
+                let end = entry_points.iter().find(|entry_point| **entry_point > start).unwrap();
+                (function.id.clone(), start..*end)
+            })
+            .collect();
+        let libfunc_functions = program
+            .libfunc_declarations
+            .iter()
+            .map(|declaration| {
+                let functions = declaration
+                    .long_id
+                    .generic_args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArg::UserFunc(function_id) => Some(function_id.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                (declaration.id.clone(), functions)
+            })
+            .collect();
+        let mut code =
+            Self { program, bodies, libfunc_functions, function_hashes: Default::default() };
+        code.function_hashes = program
+            .funcs
+            .iter()
+            .map(|function| (function.id.clone(), code.function_hash(function)))
+            .collect();
+        code
+    }
+
+    /// Returns the id of the function with the given full path.
+    fn function_by_name(&self, name: &str) -> Option<&'a FunctionId> {
+        self.program
+            .funcs
+            .iter()
+            .map(|function| &function.id)
+            .find(|id| id.debug_name.as_deref() == Some(name))
+    }
+
+    /// Returns the statements of a function.
+    fn body(&self, function_id: &FunctionId) -> &'a [Statement] {
+        self.bodies.get(function_id).map_or(&[], |body| &self.program.statements[body.clone()])
+    }
+
+    /// Returns the functions reachable from the given functions by function calls, including
+    /// themselves, sorted by name.
+    fn reachable_functions(&self, roots: impl Iterator<Item = FunctionId>) -> Vec<FunctionId> {
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<FunctionId> = roots.collect();
+        while let Some(function_id) = queue.pop_front() {
+            if !visited.insert(function_id.clone()) {
+                continue;
+            }
+            for statement in self.body(&function_id) {
+                if let Statement::Invocation(invocation) = statement {
+                    if let Some(functions) = self.libfunc_functions.get(&invocation.libfunc_id) {
+                        queue.extend(functions.iter().cloned());
+                    }
+                }
+            }
+        }
+        visited.into_iter().sorted_by_key(|function_id| function_id.to_string()).collect()
+    }
+
+    /// Returns a hash of the code of a single function: its signature, its statements (with
+    /// branch targets relative to the function's entry point, so that changes of other functions
+    /// do not affect it), and the declarations of the libfuncs and types it uses. The ids in the
+    /// code are hashed by their names, as their numeric ids may change between compilations.
+    fn function_hash(&self, function: &Function) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for type_id in chain!(&function.signature.param_types, &function.signature.ret_types) {
+            type_id.to_string().hash(&mut hasher);
+        }
+        let mut libfuncs = HashSet::new();
+        for statement in self.body(&function.id) {
+            if let Statement::Invocation(invocation) = statement {
+                libfuncs.insert(&invocation.libfunc_id);
+            }
+            let statement = statement.clone().map(|StatementIdx(idx)| idx - function.entry_point.0);
+            statement.to_string().hash(&mut hasher);
+        }
+        let mut types = function.signature.param_types.clone();
+        for declaration in &self.program.libfunc_declarations {
+            if libfuncs.contains(&declaration.id) {
+                declaration.long_id.to_string().hash(&mut hasher);
+                types.extend(generic_arg_types(&declaration.long_id.generic_args));
+            }
+        }
+        // The declarations of the used types, and of the types they consist of.
+        let mut visited_types = HashSet::new();
+        while let Some(type_id) = types.pop() {
+            if !visited_types.insert(type_id.clone()) {
+                continue;
+            }
+            let Some(declaration) =
+                self.program.type_declarations.iter().find(|declaration| declaration.id == type_id)
+            else {
+                continue;
+            };
+            format!("{}{:?}", declaration.long_id, declaration.declared_type_info)
+                .hash(&mut hasher);
+            types.extend(generic_arg_types(&declaration.long_id.generic_args));
+        }
+        hasher.finish()
+    }
+}
+
+/// Returns the types in generic args.
+fn generic_arg_types(generic_args: &[GenericArg]) -> impl Iterator<Item = ConcreteTypeId> + '_ {
+    generic_args.iter().filter_map(|arg| match arg {
+        GenericArg::Type(type_id) => Some(type_id.clone()),
+        _ => None,
+    })
+}
@@ -0,0 +1,59 @@
+use starknet::syscalls::deploy_syscall;
+use starknet::{ContractAddress, contract_address_const};
+
+use super::{Balance, IBalanceDispatcher, IBalanceDispatcherTrait};
+
+fn deploy(value: u128) -> IBalanceDispatcher {
+    let (contract_address, _) = deploy_syscall(
+        Balance::TEST_CLASS_HASH.try_into().unwrap(), 0, array![value.into()].span(), false
+    )
+        .unwrap();
+    IBalanceDispatcher { contract_address }
+}
+
+fn deployed() -> IBalanceDispatcher {
+    // Contracts are deployed to consecutive addresses, starting from 1.
+    IBalanceDispatcher { contract_address: contract_address_const::<1>() }
+}
+
+#[setup]
+#[available_gas(30000000)]
+fn setup() {
+    deploy(100);
+}
+
+#[fixture]
+#[available_gas(30000000)]
+fn deployed_with_200() {
+    deploy(200);
+}
+
+#[fixture]
+fn failing_fixture() {
+    panic_with_felt252('fixture failed');
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_setup_increase() {
+    deployed().increase(10);
+    assert(deployed().get() == 110, 'wrong balance');
+}
+
+#[test]
+#[available_gas(30000000)]
+fn test_setup_is_cloned() {
+    deployed().increase(20);
+    assert(deployed().get() == 120, 'wrong balance');
+}
+
+#[test]
+#[available_gas(30000000)]
+#[use_fixture(deployed_with_200)]
+fn test_named_fixture() {
+    assert(deployed().get() == 200, 'wrong balance');
+}
+
+#[test]
+#[use_fixture(failing_fixture)]
+fn test_failing_fixture() {}
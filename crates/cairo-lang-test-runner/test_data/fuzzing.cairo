@@ -0,0 +1,13 @@
+#[test]
+#[fuzzer(runs: 100, seed: 1)]
+fn test_fuzz_passes(a: u64, b: u64) {
+    let a: u128 = a.into();
+    let b: u128 = b.into();
+    assert(a + b >= a, 'overflow');
+}
+
+#[test]
+#[fuzzer(runs: 100, seed: 1)]
+fn test_fuzz_fails(a: u32, values: Span<felt252>) {
+    assert(a < 1000 || values.len() == 0, 'too big');
+}
@@ -31,6 +31,15 @@ mod Balance {
     }
 }
 
+#[cfg(test)]
+mod fixtures;
+
+#[cfg(test)]
+mod fuzzing;
+
+#[cfg(test)]
+mod limits;
+
 #[cfg(test)]
 mod tests {
     use array::ArrayTrait;
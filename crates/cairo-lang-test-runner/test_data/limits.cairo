@@ -0,0 +1,93 @@
+#[starknet::interface]
+trait ICounter<T> {
+    // Counts from 0 to `n`, returning `n`.
+    fn count_to(self: @T, n: u128) -> u128;
+    // Counts from 0 to `n` by calling `counter`, returning `n`.
+    fn count_to_through(self: @T, counter: starknet::ContractAddress, n: u128) -> u128;
+}
+
+#[starknet::contract]
+mod Counter {
+    use super::{ICounterDispatcher, ICounterDispatcherTrait};
+
+    #[storage]
+    struct Storage {}
+
+    #[external(v0)]
+    impl Counter of super::ICounter<ContractState> {
+        fn count_to(self: @ContractState, n: u128) -> u128 {
+            super::count_to(n)
+        }
+
+        fn count_to_through(
+            self: @ContractState, counter: starknet::ContractAddress, n: u128
+        ) -> u128 {
+            ICounterDispatcher { contract_address: counter }.count_to(n)
+        }
+    }
+}
+
+use starknet::syscalls::deploy_syscall;
+
+fn count_to(n: u128) -> u128 {
+    let mut i = 0;
+    loop {
+        if i == n {
+            break i;
+        }
+        i += 1;
+    }
+}
+
+fn deploy_counter() -> ICounterDispatcher {
+    let (contract_address, _) = deploy_syscall(
+        Counter::TEST_CLASS_HASH.try_into().unwrap(), 0, array![].span(), false
+    )
+        .unwrap();
+    ICounterDispatcher { contract_address }
+}
+
+#[test]
+#[available_gas(1000000000)]
+#[max_steps(100000)]
+fn test_within_step_limit() {
+    assert(count_to(100) == 100, 'wrong count');
+}
+
+#[test]
+#[available_gas(1000000000)]
+#[max_steps(100000)]
+fn test_step_limit_exceeded() {
+    count_to(1000000);
+}
+
+#[test]
+#[available_gas(1000000000)]
+#[max_steps(100000)]
+fn test_step_limit_exceeded_in_contract_call() {
+    deploy_counter().count_to(1000000);
+}
+
+#[test]
+#[available_gas(1000000000)]
+#[max_steps(100000)]
+fn test_step_limit_exceeded_in_nested_contract_calls() {
+    let counter = deploy_counter();
+    let inner_counter = deploy_counter();
+    // Each of the nested calls is within the limit, but all of them together exceed it.
+    let mut i = 0;
+    loop {
+        if i == 10 {
+            break;
+        }
+        counter.count_to_through(inner_counter.contract_address, 1000);
+        i += 1;
+    };
+}
+
+#[test]
+#[available_gas(1000000000000)]
+#[timeout(1)]
+fn test_timeout() {
+    count_to(1000000000);
+}
@@ -0,0 +1,4 @@
+// Returns the length and the first element of each of the two given arrays.
+fn array_args(a: Array<felt252>, b: Array<felt252>) -> (usize, felt252, usize, felt252) {
+    (a.len(), *a[0], b.len(), *b[0])
+}
@@ -0,0 +1,32 @@
+// Functions returning values of types with special decodings in the runner.
+
+#[derive(Drop)]
+enum Shape {
+    Circle: u32,
+    Rectangle: (u32, u32),
+}
+
+#[derive(Drop)]
+enum Drawing {
+    Empty,
+    Single: Shape,
+    Multiple: Array<Shape>,
+}
+
+fn u256_value() -> u256 {
+    0x100000000000000000000000000000002
+}
+
+fn byte_array_value() -> ByteArray {
+    "A string longer than the 31 bytes of a word."
+}
+
+fn nested_enum_value() -> Option<Drawing> {
+    Option::Some(Drawing::Single(Shape::Rectangle((2, 3))))
+}
+
+// The value of a function which may panic is wrapped in a `PanicResult`.
+fn panicable_value() -> u8 {
+    let values = array![3_u8, 4_u8];
+    *values[1]
+}
@@ -1,4 +1,6 @@
+mod array_args;
 mod corelib_usage;
+mod decoded_values;
 mod enum_flow;
 mod fib;
 mod fib_array;
@@ -11,7 +11,10 @@ use cairo_lang_filesystem::db::FilesGroupEx;
 use cairo_lang_filesystem::flag::Flag;
 use cairo_lang_filesystem::ids::{CrateId, FlagId};
 use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
-use cairo_lang_runner::{token_gas_cost, Arg, RunResultValue, SierraCasmRunner};
+use cairo_lang_runner::{
+    collect_enum_variant_names, collect_statement_locations, token_gas_cost, Arg, RunResultValue,
+    SierraCasmRunner, StatementLocations,
+};
 use cairo_lang_sierra::extensions::gas::CostTokenType;
 use cairo_lang_sierra_generator::db::SierraGenGroup;
 use cairo_lang_sierra_generator::replace_ids::replace_sierra_ids_in_program;
@@ -56,15 +59,32 @@ fn compare_contents_or_fix(name: &str, test_type: &str, content: String) {
 /// Compiles the Cairo code for submodule `name` of the examples crates to a Sierra program.
 fn checked_compile_to_sierra(
     name: &str,
-    (db, crate_ids): &ExampleDirData,
+    example_dir_data: &ExampleDirData,
     auto_add_withdraw_gas: bool,
 ) -> cairo_lang_sierra::program::Program {
+    checked_compile_to_sierra_with_locations(name, example_dir_data, auto_add_withdraw_gas, false).0
+}
+
+/// Compiles the Cairo code for submodule `name` of the examples crates to a Sierra program, along
+/// with the locations of its statements in the Cairo code if `add_statement_locations` is set.
+fn checked_compile_to_sierra_with_locations(
+    name: &str,
+    (db, crate_ids): &ExampleDirData,
+    auto_add_withdraw_gas: bool,
+    add_statement_locations: bool,
+) -> (cairo_lang_sierra::program::Program, StatementLocations) {
     let mut locked_db = db.lock().unwrap();
     let add_withdraw_gas_flag_id = FlagId::new(locked_db.snapshot().upcast(), "add_withdraw_gas");
     locked_db.set_flag(
         add_withdraw_gas_flag_id,
         Some(Arc::new(Flag::AddWithdrawGas(auto_add_withdraw_gas))),
     );
+    let add_statement_locations_flag_id =
+        FlagId::new(locked_db.snapshot().upcast(), "add_statement_locations");
+    locked_db.set_flag(
+        add_statement_locations_flag_id,
+        Some(Arc::new(Flag::AddStatementLocations(add_statement_locations))),
+    );
     let db = locked_db.snapshot();
     let mut requested_function_ids = vec![];
     for crate_id in crate_ids {
@@ -81,8 +101,12 @@ fn checked_compile_to_sierra(
             }
         }
     }
-    let sierra_program = db.get_sierra_program_for_functions(requested_function_ids).unwrap();
-    replace_sierra_ids_in_program(&db, &sierra_program)
+    let sierra_program =
+        db.get_sierra_program_with_debug_for_functions(requested_function_ids).unwrap();
+    (
+        replace_sierra_ids_in_program(&db, &sierra_program.program),
+        collect_statement_locations(&db, &sierra_program.debug_info.statements_locations),
+    )
 }
 
 /// Tests lowering from Cairo to Sierra.
@@ -196,6 +220,7 @@ fn run_function(
             &params.iter().cloned().map(Arg::Value).collect_vec(),
             available_gas,
             Default::default(),
+            Default::default(),
         )
         .expect("Failed running the function.");
     if let Some(expected_cost) = expected_cost {
@@ -326,3 +351,222 @@ fn run_fib_array_len(#[case] n: usize, #[case] last: usize, example_dir_data: &E
         [_, _, actual_last, actual_len] if actual_last == &Felt252::from(last) && actual_len == &Felt252::from(n)
     );
 }
+
+#[rstest]
+#[case::fib_counter("fib_counter", &[1, 1, 8], "(34, 8)")]
+#[case::fib_array("fib_array", &[5], "([1, 1, 2, 3, 5], 5, 5)")]
+#[case::fib_struct("fib_struct", &[1, 1, 9], "examples::fib_struct::FibResult(55, 9, ())")]
+#[case::fib_u128_checked_pass(
+    "fib_u128_checked", &[1, 1, 10], "core::option::Option::Some(89)"
+)]
+#[case::fib_u128_checked_fail(
+    "fib_u128_checked", &[1, 1, 200], "core::option::Option::None"
+)]
+fn decode_return_value_test(
+    #[case] name: &str,
+    #[case] params: &[usize],
+    #[case] expected: &str,
+    example_dir_data: &ExampleDirData,
+) {
+    let runner = SierraCasmRunner::new(
+        checked_compile_to_sierra(name, example_dir_data, false),
+        None,
+        Default::default(),
+    )
+    .expect("Failed setting up runner.");
+    let func = runner.find_function("").expect("Failed finding the function.");
+    let result = runner
+        .run_function_with_starknet_context(
+            func,
+            &params.iter().map(|param| Arg::Value(Felt252::from(*param))).collect_vec(),
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .expect("Failed running the function.");
+    let values = extract_matches!(result.value, RunResultValue::Success);
+    let enum_variant_names = {
+        let (db, crate_ids) = example_dir_data;
+        collect_enum_variant_names(&*db.lock().unwrap(), crate_ids)
+    };
+    assert_eq!(
+        runner.decode_return_value(func, &values, &result.memory, &enum_variant_names).to_string(),
+        expected
+    );
+}
+
+#[rstest]
+#[case::u256("u256_value", "340282366920938463463374607431768211458")]
+#[case::byte_array("byte_array_value", "\"A string longer than the 31 bytes of a word.\"")]
+#[case::nested_enum(
+    "nested_enum_value",
+    "core::option::Option::Some(examples::decoded_values::Drawing::Single(\
+     examples::decoded_values::Shape::Rectangle((2, 3))))"
+)]
+#[case::panic_result("panicable_value", "4")]
+fn decode_value_test(
+    #[case] function: &str,
+    #[case] expected: &str,
+    example_dir_data: &ExampleDirData,
+) {
+    let runner = SierraCasmRunner::new(
+        checked_compile_to_sierra("decoded_values", example_dir_data, false),
+        None,
+        Default::default(),
+    )
+    .expect("Failed setting up runner.");
+    let func =
+        runner.find_function(&format!("::{function}")).expect("Failed finding the function.");
+    let result = runner
+        .run_function_with_starknet_context(func, &[], None, Default::default(), Default::default())
+        .expect("Failed running the function.");
+    let values = extract_matches!(result.value, RunResultValue::Success);
+    let enum_variant_names = {
+        let (db, crate_ids) = example_dir_data;
+        collect_enum_variant_names(&*db.lock().unwrap(), crate_ids)
+    };
+    assert_eq!(
+        runner.decode_return_value(func, &values, &result.memory, &enum_variant_names).to_string(),
+        expected
+    );
+}
+
+#[rstest]
+fn run_function_trace_test(example_dir_data: &ExampleDirData) {
+    let (sierra_program, statement_locations) =
+        checked_compile_to_sierra_with_locations("fib", example_dir_data, false, true);
+    let runner = SierraCasmRunner::new(sierra_program, None, Default::default())
+        .expect("Failed setting up runner.")
+        .with_trace_collection(true);
+    let func = runner.find_function("").expect("Failed finding the function.");
+    let result = runner
+        .run_function_with_starknet_context(
+            func,
+            &[1, 1, 7].map(|param| Arg::Value(Felt252::from(param))),
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .expect("Failed running the function.");
+    let run_trace = result.trace.unwrap();
+    let trace = runner.map_trace(&run_trace, &statement_locations);
+    // The entry code is not part of the program, and is followed by the function's code.
+    assert_eq!(trace[0].sierra_statement, None);
+    let first_program_step = trace.iter().find(|entry| entry.sierra_statement.is_some()).unwrap();
+    assert_eq!(first_program_step.pc, run_trace.program_start);
+    assert!(
+        trace
+            .iter()
+            .filter_map(|entry| entry.function.as_deref())
+            .all(|function| function == "examples::fib::fib")
+    );
+    // The steps are mapped to the different statements of `fib` they execute.
+    let locations =
+        trace.iter().filter_map(|entry| entry.location.as_deref()).unique().collect_vec();
+    assert!(locations.len() > 1);
+    assert!(locations.iter().all(|location| location.contains("examples/fib.cairo:")));
+}
+
+#[rstest]
+fn run_function_profile_test(example_dir_data: &ExampleDirData) {
+    let runner = SierraCasmRunner::new(
+        checked_compile_to_sierra("hash_chain", example_dir_data, false),
+        None,
+        Default::default(),
+    )
+    .expect("Failed setting up runner.")
+    .with_trace_collection(true);
+    let func = runner.find_function("").expect("Failed finding the function.");
+    let result = runner
+        .run_function_with_starknet_context(
+            func,
+            &[Arg::Value(Felt252::from(3))],
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .expect("Failed running the function.");
+    let profile = runner.profile(&result.trace.unwrap(), &result.memory);
+    let function_profile = profile.functions.get("examples::hash_chain::hash_chain").unwrap();
+    assert_eq!(profile.functions.len(), 1);
+    // The recursive calls are counted once in the inclusive usage, and at each depth in the
+    // exclusive usage.
+    assert_eq!(function_profile.inclusive, function_profile.exclusive);
+    assert_eq!(function_profile.inclusive.builtins, [0, 3, 0, 0, 0]);
+    assert_eq!(function_profile.inclusive.steps, profile.stacks.values().sum::<usize>());
+    assert_eq!(
+        profile.stacks.keys().map(|stack| stack.split(';').count()).collect_vec(),
+        vec![1, 2, 3, 4]
+    );
+}
+
+#[rstest]
+fn run_function_with_array_args_test(example_dir_data: &ExampleDirData) {
+    let runner = SierraCasmRunner::new(
+        checked_compile_to_sierra("array_args", example_dir_data, false),
+        None,
+        Default::default(),
+    )
+    .expect("Failed setting up runner.");
+    // The arrays given by `cairo-run --args "[1 2] [3 4 5]"`.
+    let args = [
+        Arg::Array([1, 2].map(Felt252::from).to_vec()),
+        Arg::Array([3, 4, 5].map(Felt252::from).to_vec()),
+    ];
+    let result = runner
+        .run_function_with_starknet_context(
+            runner.find_function("").expect("Failed finding the function."),
+            &args,
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .expect("Failed running the function.");
+    assert_eq!(result.value, RunResultValue::Success([2, 1, 3, 3].map(Felt252::from).to_vec()));
+}
+
+#[rstest]
+#[case::array_for_value(
+    "fib",
+    vec![Arg::Value(Felt252::from(1)), Arg::Array(vec![Felt252::from(1)]), Arg::Value(Felt252::from(7))],
+    "Parameter #1 of type `felt252` expects 1 value."
+)]
+#[case::value_for_array(
+    "array_args",
+    vec![Arg::Array(vec![Felt252::from(1)]), Arg::Value(Felt252::from(2))],
+    "Parameter #1 of type `Array<felt252>` expects an array."
+)]
+#[case::too_short(
+    "fib",
+    [1, 1].map(|value| Arg::Value(Felt252::from(value))).into(),
+    "Parameter #2 of type `felt252` expects 1 value."
+)]
+#[case::too_long(
+    "fib",
+    [1, 1, 7, 8].map(|value| Arg::Value(Felt252::from(value))).into(),
+    "Function expects arguments of size 3 and received 4 instead."
+)]
+fn run_function_with_mismatched_args_test(
+    #[case] name: &str,
+    #[case] args: Vec<Arg>,
+    #[case] expected_error: &str,
+    example_dir_data: &ExampleDirData,
+) {
+    let runner = SierraCasmRunner::new(
+        checked_compile_to_sierra(name, example_dir_data, false),
+        None,
+        Default::default(),
+    )
+    .expect("Failed setting up runner.");
+    let error = runner
+        .run_function_with_starknet_context(
+            runner.find_function("").expect("Failed finding the function."),
+            &args,
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .err()
+        .expect("The arguments should not match the parameters.");
+    assert_eq!(error.to_string(), expected_error);
+}